@@ -1,23 +1,59 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    time::Duration,
+};
 
 use anyhow::{anyhow, Result};
+use chrono::Utc;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use common::{constants::MAX_LOBBY_SIZE, BackendMessage, JoinMode, LobbyListItem};
+use common::{
+    constants::{
+        MAX_LOBBY_CAPACITY, MAX_LOBBY_SIZE, MAX_START_COUNTDOWN, MIN_LOBBY_CAPACITY,
+        MIN_START_COUNTDOWN,
+    },
+    BackendMessage, ChallengeFiles, CreateLobbyOptions, GameEvent, JoinMode, LobbyListItem,
+    LobbyStatus,
+};
 
 use self::message::AppMessage;
-use crate::lobby::Lobby;
+use crate::{
+    args::ServerConfig,
+    challenge::ChallengeCatalog,
+    constants::LOBBY_START_TIMER,
+    database::Database,
+    leaderboard::Leaderboard,
+    lobby::{Lobby, ScoringMode},
+    metrics::Metrics,
+};
 
 pub(crate) mod message;
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug)]
 pub struct App {
     /// All non-playing clients.
     pub clients: BTreeMap<Uuid, UnboundedSender<BackendMessage>>,
+    /// All clients that opted into receiving game events.
+    pub event_subscribers: BTreeSet<Uuid>,
     /// All active lobbies.
     pub lobbies: BTreeMap<Uuid, Lobby>,
+    /// Stores the results of finished rounds, if configured.
+    pub database: Option<Database>,
+    /// The wins and best times of all players across games.
+    pub leaderboard: Leaderboard,
+    /// The scoring mode of newly created lobbies.
+    pub scoring_mode: ScoringMode,
+    /// All challenges new lobbies can be created with.
+    pub catalog: ChallengeCatalog,
+    /// Limits and timings of the lobbies.
+    pub config: ServerConfig,
+    /// Counters exposed via `GET /metrics`.
+    pub metrics: Metrics,
 
     pub tx: UnboundedSender<AppMessage>,
     pub rx: UnboundedReceiver<AppMessage>,
@@ -27,11 +63,28 @@ impl App {
     /// # Create a new app
     ///
     /// Creates a new app with no clients and lobbies. Holds the passed in
-    /// communication channel.
-    pub fn new(tx: UnboundedSender<AppMessage>, rx: UnboundedReceiver<AppMessage>) -> Self {
+    /// communication channel, the optional results database, the leaderboard,
+    /// the scoring mode and the challenge catalog of new lobbies and the
+    /// server config.
+    pub fn new(
+        tx: UnboundedSender<AppMessage>,
+        rx: UnboundedReceiver<AppMessage>,
+        database: Option<Database>,
+        leaderboard: Leaderboard,
+        scoring_mode: ScoringMode,
+        catalog: ChallengeCatalog,
+        config: ServerConfig,
+    ) -> Self {
         Self {
             clients: BTreeMap::default(),
+            event_subscribers: BTreeSet::default(),
             lobbies: BTreeMap::default(),
+            database,
+            leaderboard,
+            scoring_mode,
+            catalog,
+            config,
+            metrics: Metrics::default(),
             tx,
             rx,
         }
@@ -40,42 +93,135 @@ impl App {
     /// # Get lobby ID
     ///
     /// Returns the ID of an available lobby or creates a new one depending on
-    /// the provided `JoinMode`.
-    pub fn get_lobby_id(&mut self, join_mode: JoinMode) -> Result<Uuid> {
+    /// the provided `JoinMode`. New lobbies are created with the given options.
+    pub fn get_lobby_id(
+        &mut self,
+        join_mode: JoinMode,
+        options: CreateLobbyOptions,
+    ) -> Result<Uuid> {
         match join_mode {
-            // Find a non-full lobby. If there is none, create a new one.
+            // Find a non-full lobby that still waits for players. If there is
+            // none, create a new one.
             JoinMode::Quickplay => {
                 if let Some(lobby) = self
                     .lobbies
                     .values_mut()
-                    .filter(|lobby| lobby.players.len() < MAX_LOBBY_SIZE)
+                    .filter(|lobby| {
+                        lobby.players.len() < lobby.max_players
+                            && lobby.status == LobbyStatus::WaitingForPlayers
+                    })
                     .max_by_key(|lobby| lobby.players.len())
                 {
                     Ok(lobby.id)
                 } else {
-                    self.create_new_lobby()
+                    let challenge_files = self.catalog.get(None)?;
+                    self.create_new_lobby(MAX_LOBBY_SIZE, challenge_files, LOBBY_START_TIMER)
                 }
             }
-            // Try to join the lobby with the provided ID.
-            JoinMode::Join { lobby_id } => {
+            // Try to join or spectate the lobby with the provided ID.
+            JoinMode::Join { lobby_id } | JoinMode::Spectate { lobby_id } => {
                 let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
-                    return Err(anyhow!("Lobby with ID {} was not found in app state. Could not get lobby information.", lobby_id));
+                    return Err(LobbyNotFound(lobby_id).into());
                 };
                 Ok(lobby.id)
             }
-            // Create a new lobby.
-            JoinMode::Create => self.create_new_lobby(),
+            // Create a new lobby with the requested capacity and challenge.
+            JoinMode::Create => self.create_lobby(options),
         }
     }
 
+    /// # Create lobby
+    ///
+    /// Validates the given options and creates a new lobby with them. Fails
+    /// with `InvalidLobbyOptions` if an option is out of range or names an
+    /// unknown challenge, and with `ServerFull` if no further lobby can be
+    /// created.
+    pub fn create_lobby(&mut self, options: CreateLobbyOptions) -> Result<Uuid> {
+        let max_players = options.max_players.unwrap_or(MAX_LOBBY_SIZE);
+        if !(MIN_LOBBY_CAPACITY..=MAX_LOBBY_CAPACITY).contains(&max_players) {
+            return Err(InvalidLobbyOptions(format!(
+                "Lobby capacity {} is not within {} and {}.",
+                max_players, MIN_LOBBY_CAPACITY, MAX_LOBBY_CAPACITY
+            ))
+            .into());
+        }
+        let start_timer = match options.start_countdown {
+            Some(countdown) => {
+                if !(MIN_START_COUNTDOWN..=MAX_START_COUNTDOWN).contains(&countdown) {
+                    return Err(InvalidLobbyOptions(format!(
+                        "Start countdown {}s is not within {}s and {}s.",
+                        countdown, MIN_START_COUNTDOWN, MAX_START_COUNTDOWN
+                    ))
+                    .into());
+                }
+                Duration::from_secs(countdown)
+            }
+            None => LOBBY_START_TIMER,
+        };
+        let challenge_files = self
+            .catalog
+            .get(options.challenge.as_deref())
+            .map_err(|e| InvalidLobbyOptions(e.to_string()))?;
+        self.create_new_lobby(max_players, challenge_files, start_timer)
+    }
+
+    /// # Unique lobby name
+    ///
+    /// Appends a number to the given name if another lobby already uses it,
+    /// e.g., "Acme Inc #2", so lobbies can be told apart in the lobby list.
+    pub fn unique_lobby_name(&self, name: String) -> String {
+        let is_taken = |name: &str| self.lobbies.values().any(|lobby| lobby.name == name);
+        if !is_taken(&name) {
+            return name;
+        }
+        (2..)
+            .map(|number| format!("{name} #{number}"))
+            .find(|candidate| !is_taken(candidate))
+            .unwrap_or(name)
+    }
+
     /// # Create new lobby
     ///
-    /// Creates a new lobby and inserts it into the application state.
-    pub fn create_new_lobby(&mut self) -> Result<Uuid> {
+    /// Creates a new lobby holding up to `max_players` players, playing the
+    /// given challenge and counting down `start_timer` before each game.
+    /// Inserts it into the application state. Fails with `ServerFull` if
+    /// `max_lobbies` lobbies of the server config already exist.
+    pub fn create_new_lobby(
+        &mut self,
+        max_players: usize,
+        challenge_files: ChallengeFiles,
+        start_timer: Duration,
+    ) -> Result<Uuid> {
+        if self.lobbies.len() >= self.config.max_lobbies {
+            warn!(
+                "Refused to create a lobby, {} lobbies already exist.",
+                self.lobbies.len()
+            );
+            return Err(ServerFull.into());
+        }
+
         // Create the new lobby.
-        let lobby = Lobby::default();
+        let mut lobby = Lobby {
+            max_players,
+            start_timer,
+            challenge_files,
+            scoring_mode: self.scoring_mode,
+            empty_lobby_lifetime: self.config.empty_lobby_lifetime(),
+            normalize_line_endings: self.config.normalize_line_endings,
+            messages_broadcast: self.metrics.messages_broadcast.clone(),
+            ..Lobby::default()
+        };
+        lobby.name = self.unique_lobby_name(lobby.name);
+        self.metrics.lobbies_created += 1;
         self.lobbies.insert(lobby.id, lobby.clone());
         self.tx.send(AppMessage::AddLobby { lobby_id: lobby.id })?;
+        self.tx.send(AppMessage::SendConnectionCounts)?;
+        self.tx.send(AppMessage::PublishEvent {
+            event: GameEvent::LobbyCreated {
+                lobby_id: lobby.id,
+                name: lobby.name.clone(),
+            },
+        })?;
 
         info!(
             "Created new lobby {}. {} open lobby/lobbies.",
@@ -100,14 +246,22 @@ impl App {
 
     /// # Remove lobby
     ///
-    /// Removes a lobby if it exists and it is empty. All connected clients are
-    /// informed about the removed lobby.
+    /// Removes a lobby if it exists, it is empty and its removal time has
+    /// passed. All connected clients are informed about the removed lobby.
     pub fn remove_lobby(&mut self, lobby_id: Uuid) -> Result<()> {
         let Some(lobby) = self.lobbies.get(&lobby_id) else {
             let error_message = format!("Lobby with ID {} was not found.", lobby_id);
             error!("{}", error_message);
             return Err(anyhow!(error_message));
         };
+        // A player might have joined and left again in the meantime, which
+        // postpones the removal of the lobby.
+        if lobby
+            .removal_time
+            .is_some_and(|removal_time| removal_time > Utc::now())
+        {
+            return Ok(());
+        }
         if lobby.players.is_empty() {
             if let Some(lobby) = self.lobbies.remove(&lobby_id) {
                 info!(
@@ -119,6 +273,15 @@ impl App {
                 for client in self.clients.values() {
                     client.send(BackendMessage::RemoveLobby(lobby_id))?;
                 }
+                // Disconnect the remaining spectators, as there is nothing left
+                // to watch.
+                for spectator in lobby.spectators.values() {
+                    let _ = spectator.send(BackendMessage::CloseConnection);
+                }
+                if !lobby.spectators.is_empty() {
+                    self.tx.send(AppMessage::SendConnectionCounts)?;
+                }
+                self.publish_event(GameEvent::LobbyRemoved { lobby_id });
             }
         } else {
             error!(
@@ -145,4 +308,63 @@ impl App {
         }
         Ok(())
     }
+
+    /// # Publish event
+    ///
+    /// Sends a timestamped game event to every client that subscribed to
+    /// events.
+    pub fn publish_event(&self, event: GameEvent) {
+        let message = BackendMessage::Event {
+            timestamp: Utc::now(),
+            event,
+        };
+        for client_id in self.event_subscribers.iter() {
+            if let Some(client) = self.clients.get(client_id) {
+                let _ = client.send(message.clone());
+            }
+        }
+    }
 }
+
+/// # Server full
+///
+/// The error of creating a lobby while the maximum number of lobbies
+/// already exists.
+#[derive(Debug)]
+pub struct ServerFull;
+
+impl Display for ServerFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The maximum number of lobbies is reached.")
+    }
+}
+
+impl std::error::Error for ServerFull {}
+
+/// # Lobby not found
+///
+/// The error of looking up a lobby that does not exist (anymore).
+#[derive(Debug)]
+pub struct LobbyNotFound(pub Uuid);
+
+impl Display for LobbyNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Lobby with ID {} was not found.", self.0)
+    }
+}
+
+impl std::error::Error for LobbyNotFound {}
+
+/// # Invalid lobby options
+///
+/// The error of creating a lobby with options the backend does not accept.
+#[derive(Debug)]
+pub struct InvalidLobbyOptions(pub String);
+
+impl Display for InvalidLobbyOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidLobbyOptions {}
@@ -1,31 +1,88 @@
-use chrono::Utc;
-use strsim::normalized_levenshtein;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
 use tokio::sync::{mpsc::UnboundedSender, oneshot::Sender};
-use tracing::{error, info, warn};
+use tracing::{error, field, info, info_span, warn, Span};
 use uuid::Uuid;
 
-use common::{BackendMessage, JoinMode, LobbyInformation, LobbyStatus};
+use common::{
+    constants::MAX_LOBBY_NAME_LENGTH, BackendMessage, CreateLobbyOptions, FinalStanding, GameEvent,
+    JoinMode, LobbyInformation, LobbyListItem, LobbyStatus,
+};
 
-use super::App;
+use super::{App, InvalidLobbyOptions, LobbyNotFound, ServerFull};
 use crate::{
-    constants::{
-        LOBBY_FINISH_TIME, LOBBY_START_TIMER, MAX_LOBBY_PLAY_TIME, REDUCED_LOBBY_PLAY_TIME,
-    },
-    player::Player,
+    constants::IDLE_SWEEP_INTERVAL,
+    database::LeaderboardEntry,
+    lobby::{compute_progress, Lobby},
+    metrics::MetricsSnapshot,
+    player::{sanitize_name, Player},
 };
 
 pub enum AppMessage {
     /// Provide lobby information to the client who wants to play. Depending on
     /// the join mode this leads to the inspection of an already running lobby
     /// or the creation of a new one.
+    /// Replies with the information of the lobby matching the join mode, or
+    /// with the `BackendMessage` explaining why there is none, e.g.,
+    /// `BackendMessage::ServerFull` if no further lobby can be created.
     ProvideLobbyInformation {
-        tx: Sender<LobbyInformation>,
+        tx: Sender<Result<LobbyInformation, BackendMessage>>,
         join_mode: JoinMode,
+        options: CreateLobbyOptions,
+    },
+    /// Creates a new lobby with the given options, independent of any
+    /// player. Replies with the ID of the new lobby, or with the reason the
+    /// lobby could not be created.
+    CreateLobby {
+        options: CreateLobbyOptions,
+        tx: Sender<anyhow::Result<Uuid>>,
     },
     AddPlayerToLobby {
         lobby_id: Uuid,
         player: Player,
     },
+    /// Adds a spectator to the lobby, who receives all lobby broadcasts
+    /// without playing.
+    AddSpectatorToLobby {
+        lobby_id: Uuid,
+        spectator_id: Uuid,
+        spectator_tx: UnboundedSender<BackendMessage>,
+    },
+    RemoveSpectator {
+        lobby_id: Uuid,
+        spectator_id: Uuid,
+    },
+    /// Keeps a player who lost his connection in the lobby for the reconnect
+    /// grace period.
+    DisconnectPlayer {
+        player: Player,
+        lobby_id: Uuid,
+    },
+    /// Removes a disconnected player from the lobby if he did not reconnect
+    /// in time.
+    RemoveDisconnectedPlayer {
+        lobby_id: Uuid,
+        player_id: Uuid,
+    },
+    /// Restores the seat of a disconnected player with the given token and
+    /// replies with the restored player.
+    ReconnectPlayer {
+        lobby_id: Uuid,
+        token: Uuid,
+        player_tx: UnboundedSender<BackendMessage>,
+        tx: Sender<Option<Player>>,
+    },
+    /// Marks a player as active, which postpones his idle kick.
+    RecordActivity {
+        lobby_id: Uuid,
+        player_id: Uuid,
+    },
+    /// Kicks players who stayed idle for too long from waiting lobbies.
+    KickIdlePlayers,
     /// Removes a player from the lobby and broadcasts this information to
     /// already connected players.
     RemovePlayer {
@@ -52,6 +109,8 @@ pub enum AppMessage {
     SendLobbyPlayerCountUpdate {
         lobby_id: Uuid,
     },
+    /// Broadcasts the new lobby status and the optional removal time of an
+    /// empty lobby to all connected clients.
     SendLobbyStatusUpdate {
         lobby_id: Uuid,
     },
@@ -76,31 +135,243 @@ pub enum AppMessage {
     RemoveClient {
         client_id: Uuid,
     },
+    /// Opts a client into receiving game events.
+    SubscribeEvents {
+        client_id: Uuid,
+    },
+    /// Publishes a game event to all subscribed clients.
+    PublishEvent {
+        event: GameEvent,
+    },
     /// Requests to start the game inside a lobby if the provided player is the
-    /// lobby owner.
+    /// lobby owner. Unless forced, all active players have to be ready.
     RequestStart {
         player: Player,
         lobby_id: Uuid,
+        force: bool,
+    },
+    /// Marks a player as ready or not ready for the next game.
+    ToggleReady {
+        player_id: Uuid,
+        lobby_id: Uuid,
+    },
+    /// Aborts the countdown of a lobby that is about to start if the provided
+    /// player is the lobby owner.
+    CancelStart {
+        player: Player,
+        lobby_id: Uuid,
+    },
+    /// Removes a player from a lobby and closes his connection if the
+    /// provided player is the lobby owner.
+    KickPlayer {
+        player: Player,
+        lobby_id: Uuid,
+        player_id: Uuid,
     },
-    /// Starts the game inside a lobby.
+    /// Hands the owner role of a lobby to another active player if the
+    /// provided player is the lobby owner.
+    TransferOwnership {
+        player: Player,
+        lobby_id: Uuid,
+        player_id: Uuid,
+    },
+    /// Renames a lobby and announces the new name to its players and all
+    /// clients if the provided player is the lobby owner.
+    RenameLobby {
+        player: Player,
+        lobby_id: Uuid,
+        name: String,
+    },
+    /// Starts the game inside a lobby if the timer with the given generation
+    /// was not cancelled in the meantime.
     Start {
         lobby_id: Uuid,
+        generation: u64,
     },
-    /// Finishes the game inside a lobby.
+    /// Finishes the game inside a lobby if the timer with the given generation
+    /// was not cancelled in the meantime.
     Finish {
         lobby_id: Uuid,
+        generation: u64,
+    },
+    /// Pauses or resumes the game inside a lobby if the provided player is the
+    /// lobby owner.
+    TogglePause {
+        player: Player,
+        lobby_id: Uuid,
+    },
+    /// Turns the automatic start of a lobby on or off if the provided player
+    /// is the lobby owner.
+    ToggleAutoStart {
+        player: Player,
+        lobby_id: Uuid,
+    },
+    /// Provide a list of all current lobbies.
+    ListLobbies {
+        tx: Sender<BTreeMap<Uuid, LobbyListItem>>,
+    },
+    /// Sends the win leaderboard to all clients.
+    SendLeaderboard,
+    /// Provide the best times per challenge of all persisted rounds.
+    Leaderboard {
+        tx: Sender<Vec<LeaderboardEntry>>,
+    },
+    /// Provide a snapshot of all metrics.
+    Metrics {
+        tx: Sender<MetricsSnapshot>,
     },
     /// Resets the game inside a lobby.
     Reset {
         lobby_id: Uuid,
     },
-    /// Computes the levenshtein distance between the goal file and the current
-    /// state of the player's start file and sets the player's progress.
+    /// Computes the similarity between the goal file and the current state of
+    /// the player's start file outside of the app. While an update of the
+    /// player is being scored, only the newest further update is kept.
     ComputePlayerProgress {
         lobby_id: Uuid,
         player_id: Uuid,
         progress: Vec<u8>,
     },
+    /// Sets the progress scored for a player during the game started at
+    /// `started_at`. The progress is `None` if scoring failed.
+    ApplyPlayerProgress {
+        lobby_id: Uuid,
+        player_id: Uuid,
+        started_at: Option<DateTime<Utc>>,
+        progress: Option<f64>,
+    },
+    /// Tells every connected client, player and spectator that the backend
+    /// shuts down. Replies once all messages are queued.
+    Shutdown {
+        tx: Sender<()>,
+    },
+}
+
+impl AppMessage {
+    /// # Span
+    ///
+    /// Returns a span carrying the IDs of the lobby and the player the
+    /// message concerns, so that every event logged while handling it can be
+    /// attributed.
+    fn span(&self) -> Span {
+        let (lobby_id, player_id) = match self {
+            AppMessage::AddPlayerToLobby { lobby_id, player }
+            | AppMessage::DisconnectPlayer { player, lobby_id }
+            | AppMessage::RemovePlayer { player, lobby_id }
+            | AppMessage::SendMessage {
+                player, lobby_id, ..
+            }
+            | AppMessage::RequestStart {
+                player, lobby_id, ..
+            }
+            | AppMessage::CancelStart { player, lobby_id }
+            | AppMessage::KickPlayer {
+                player, lobby_id, ..
+            }
+            | AppMessage::TransferOwnership {
+                player, lobby_id, ..
+            }
+            | AppMessage::RenameLobby {
+                player, lobby_id, ..
+            }
+            | AppMessage::TogglePause { player, lobby_id }
+            | AppMessage::ToggleAutoStart { player, lobby_id } => {
+                (Some(lobby_id), Some(&player.id))
+            }
+            AppMessage::AddSpectatorToLobby {
+                lobby_id,
+                spectator_id: player_id,
+                ..
+            }
+            | AppMessage::RemoveSpectator {
+                lobby_id,
+                spectator_id: player_id,
+            }
+            | AppMessage::RemoveDisconnectedPlayer {
+                lobby_id,
+                player_id,
+            }
+            | AppMessage::RecordActivity {
+                lobby_id,
+                player_id,
+            }
+            | AppMessage::ToggleReady {
+                player_id,
+                lobby_id,
+            }
+            | AppMessage::ComputePlayerProgress {
+                lobby_id,
+                player_id,
+                ..
+            }
+            | AppMessage::ApplyPlayerProgress {
+                lobby_id,
+                player_id,
+                ..
+            } => (Some(lobby_id), Some(player_id)),
+            AppMessage::ReconnectPlayer { lobby_id, .. }
+            | AppMessage::AddLobby { lobby_id }
+            | AppMessage::SendLobbyPlayerCountUpdate { lobby_id }
+            | AppMessage::SendLobbyStatusUpdate { lobby_id }
+            | AppMessage::RemoveLobby { lobby_id }
+            | AppMessage::Start { lobby_id, .. }
+            | AppMessage::Finish { lobby_id, .. }
+            | AppMessage::Reset { lobby_id } => (Some(lobby_id), None),
+            _ => (None, None),
+        };
+        info_span!(
+            "app_message",
+            lobby_id = lobby_id.map(field::display),
+            player_id = player_id.map(field::display)
+        )
+    }
+}
+
+/// # Sweep idle players
+///
+/// Tells the app to kick idle players at every `IDLE_SWEEP_INTERVAL`. Stops
+/// once the app is gone.
+pub async fn sweep_idle_players(app_tx: UnboundedSender<AppMessage>) {
+    let mut interval = tokio::time::interval(IDLE_SWEEP_INTERVAL);
+    loop {
+        interval.tick().await;
+        if app_tx.send(AppMessage::KickIdlePlayers).is_err() {
+            break;
+        }
+    }
+}
+
+/// # Score progress
+///
+/// Computes the progress of a player of the given lobby on the blocking thread
+/// pool, so large files do not hold up the app, and hands the result back to
+/// the app.
+fn score_progress(
+    app_tx: UnboundedSender<AppMessage>,
+    lobby: &Lobby,
+    player_id: Uuid,
+    progress: Vec<u8>,
+) {
+    let lobby_id = lobby.id;
+    let started_at = lobby.started_at;
+    let goal_file = lobby.challenge_files.goal_file.clone();
+    let normalize_line_endings = lobby.normalize_line_endings;
+    let scoring_mode = lobby.scoring_mode;
+    tokio::spawn(async move {
+        let progress = tokio::task::spawn_blocking(move || {
+            compute_progress(&goal_file, &progress, normalize_line_endings, scoring_mode)
+        })
+        .await;
+        if let Err(ref e) = progress {
+            error!("Unable to compute the progress of player {player_id}: {e}");
+        }
+        let _ = app_tx.send(AppMessage::ApplyPlayerProgress {
+            lobby_id,
+            player_id,
+            started_at,
+            progress: progress.ok(),
+        });
+    });
 }
 
 /// # Handle app message
@@ -109,66 +380,195 @@ pub enum AppMessage {
 /// handled in here which allows us to avoid the use of `Mutex` entirely.
 pub async fn handle_app_message(mut app: App) {
     while let Some(msg) = app.rx.recv().await {
+        app.handle_message(msg);
+    }
+}
+
+impl App {
+    /// # Handle message
+    ///
+    /// Applies a single `AppMessage` to the app state. Never waits, so the app
+    /// can also be driven message by message without the receiving loop.
+    pub fn handle_message(&mut self, msg: AppMessage) {
+        let _span = msg.span().entered();
         match msg {
-            AppMessage::ProvideLobbyInformation { tx, join_mode } => {
-                let Ok(lobby_id) = app.get_lobby_id(join_mode) else {
-                    error!("Unable to retrieve lobby ID by join mode.");
-                    continue;
+            AppMessage::ProvideLobbyInformation {
+                tx,
+                join_mode,
+                options,
+            } => {
+                let lobby_id = match self.get_lobby_id(join_mode, options) {
+                    Ok(lobby_id) => lobby_id,
+                    Err(e) => {
+                        error!("Unable to retrieve lobby ID by join mode: {e}");
+                        if e.is::<ServerFull>() {
+                            let _ = tx.send(Err(BackendMessage::ServerFull));
+                        } else if e.is::<LobbyNotFound>() {
+                            let _ = tx.send(Err(BackendMessage::LobbyNotFound));
+                        } else if let Some(InvalidLobbyOptions(reason)) = e.downcast_ref() {
+                            let _ =
+                                tx.send(Err(BackendMessage::InvalidLobbyOptions(reason.clone())));
+                        }
+                        return;
+                    }
                 };
-                let Some(lobby) = app.lobbies.get(&lobby_id) else {
+                let Some(lobby) = self.lobbies.get(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
                 let lobby_information = lobby.to_information();
-                let _ = tx.send(lobby_information);
+                let _ = tx.send(Ok(lobby_information));
+            }
+            AppMessage::CreateLobby { options, tx } => {
+                let result = self.create_lobby(options);
+                if let Err(ref e) = result {
+                    warn!("Unable to create lobby: {e}");
+                }
+                let _ = tx.send(result);
             }
             AppMessage::AddPlayerToLobby { lobby_id, player } => {
-                let Some(lobby) = app.lobbies.get_mut(&lobby_id) else {
+                // The lobby might have been removed after the player fetched
+                // its information.
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    let _ = player.tx.send(BackendMessage::LobbyNotFound);
+                    return;
                 };
-                lobby.add_player(player, &app.tx);
+                lobby.add_player(player, &self.tx);
+            }
+            AppMessage::RecordActivity {
+                lobby_id,
+                player_id,
+            } => {
+                let Some(player) = self
+                    .lobbies
+                    .get_mut(&lobby_id)
+                    .and_then(|lobby| lobby.players.get_mut(&player_id))
+                else {
+                    return;
+                };
+                player.last_active_at = Instant::now();
+            }
+            AppMessage::KickIdlePlayers => {
+                for lobby in self.lobbies.values_mut() {
+                    lobby.kick_idle_players(&self.tx);
+                }
             }
             AppMessage::RemovePlayer { player, lobby_id } => {
-                let Some(lobby) = app.lobbies.get_mut(&lobby_id) else {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
-                lobby.remove_player(player, &app.tx);
+                lobby.remove_player(player, &self.tx);
+            }
+            AppMessage::DisconnectPlayer { player, lobby_id } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    return;
+                };
+                lobby.disconnect_player(player.id, &self.tx);
+            }
+            AppMessage::RemoveDisconnectedPlayer {
+                lobby_id,
+                player_id,
+            } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    return;
+                };
+                let Some(player) = lobby.players.get(&player_id) else {
+                    return;
+                };
+                // The player might have reconnected and lost his connection
+                // again in the meantime, which postpones the removal.
+                if player
+                    .removal_time
+                    .is_some_and(|removal_time| removal_time <= Utc::now())
+                {
+                    let player = player.clone();
+                    lobby.remove_player(player, &self.tx);
+                }
+            }
+            AppMessage::ReconnectPlayer {
+                lobby_id,
+                token,
+                player_tx,
+                tx,
+            } => {
+                let player = self
+                    .lobbies
+                    .get_mut(&lobby_id)
+                    .and_then(|lobby| lobby.reconnect_player(token, player_tx));
+                let _ = tx.send(player);
+            }
+            AppMessage::AddSpectatorToLobby {
+                lobby_id,
+                spectator_id,
+                spectator_tx,
+            } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    let _ = spectator_tx.send(BackendMessage::CloseConnection);
+                    return;
+                };
+                lobby.add_spectator(spectator_id, spectator_tx, &self.tx);
+            }
+            AppMessage::RemoveSpectator {
+                lobby_id,
+                spectator_id,
+            } => {
+                // The lobby might have been removed while being watched.
+                if let Some(lobby) = self.lobbies.get_mut(&lobby_id) {
+                    lobby.remove_spectator(spectator_id, &self.tx);
+                }
             }
             AppMessage::SendMessage {
                 player,
                 message,
                 lobby_id,
             } => {
-                let Some(lobby) = app.lobbies.get(&lobby_id) else {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
                 lobby.send_message(player, message.clone());
             }
 
+            AppMessage::Shutdown { tx } => {
+                info!(
+                    "Closing connections of {} clients and {} lobbies.",
+                    self.clients.len(),
+                    self.lobbies.len()
+                );
+                for client in self.clients.values() {
+                    let _ = client.send(BackendMessage::CloseConnection);
+                }
+                for lobby in self.lobbies.values() {
+                    lobby.broadcast(BackendMessage::CloseConnection);
+                }
+                let _ = tx.send(());
+            }
+
             AppMessage::LobbyFull { player_tx } => {
                 let message = BackendMessage::LobbyFull;
                 let _ = player_tx.send(message);
             }
 
             AppMessage::CurrentLobbies { client_id } => {
-                let Some(client) = app.clients.get(&client_id) else {
+                let Some(client) = self.clients.get(&client_id) else {
                     error!("Client with ID {} was not found.", client_id);
-                    continue;
+                    return;
                 };
-                let lobbies = app.get_current_lobbies();
+                let lobbies = self.get_current_lobbies();
                 let message = BackendMessage::CurrentLobbies(lobbies);
                 let _ = client.send(message);
             }
             AppMessage::AddLobby { lobby_id } => {
-                if let Err(e) = app.send_lobby_list_information(lobby_id) {
+                if let Err(e) = self.send_lobby_list_information(lobby_id) {
                     error!("Error sending lobby list information: {e}");
                 }
             }
             AppMessage::RemoveLobby { lobby_id } => {
-                if let Err(e) = app.remove_lobby(lobby_id) {
+                if let Err(e) = self.remove_lobby(lobby_id) {
                     error!("Error removing lobby: {e}");
                 }
             }
@@ -177,104 +577,475 @@ pub async fn handle_app_message(mut app: App) {
                 client_id,
                 client_tx,
             } => {
-                app.clients.insert(client_id, client_tx);
-                let _ = app.tx.send(AppMessage::SendConnectionCounts);
+                let _ = client_tx.send(BackendMessage::Leaderboard(
+                    self.leaderboard.standings.clone(),
+                ));
+                self.clients.insert(client_id, client_tx);
+                let _ = self.tx.send(AppMessage::SendConnectionCounts);
                 info!(
                     "Added client with ID {}. Client count is {}.",
                     client_id,
-                    app.clients.len()
+                    self.clients.len()
                 );
             }
             AppMessage::RemoveClient { client_id } => {
-                app.clients.remove(&client_id);
-                let _ = app.tx.send(AppMessage::SendConnectionCounts);
+                self.clients.remove(&client_id);
+                self.event_subscribers.remove(&client_id);
+                let _ = self.tx.send(AppMessage::SendConnectionCounts);
                 info!(
                     "Removed client with ID {}. Client count is {}.",
                     client_id,
-                    app.clients.len()
+                    self.clients.len()
                 );
             }
+            AppMessage::SubscribeEvents { client_id } => {
+                if !self.clients.contains_key(&client_id) {
+                    error!("Client with ID {} was not found.", client_id);
+                    return;
+                }
+                self.event_subscribers.insert(client_id);
+                info!(
+                    "Client with ID {} subscribed to events. Subscriber count is {}.",
+                    client_id,
+                    self.event_subscribers.len()
+                );
+            }
+            AppMessage::PublishEvent { event } => {
+                self.publish_event(event);
+            }
             AppMessage::SendConnectionCounts => {
-                let clients = app.clients.len();
-                let players = app.lobbies.values().map(|lobby| lobby.players.len()).sum();
-                let message = BackendMessage::ConnectionCounts { clients, players };
+                let clients = self.clients.len();
+                let players = self.lobbies.values().map(|lobby| lobby.players.len()).sum();
+                let spectators = self
+                    .lobbies
+                    .values()
+                    .map(|lobby| lobby.spectators.len())
+                    .sum();
+                let message = BackendMessage::ConnectionCounts {
+                    clients,
+                    players,
+                    spectators,
+                    lobbies: self.lobbies.len(),
+                };
 
                 // Send counts to all clients.
-                for client in app.clients.values() {
+                for client in self.clients.values() {
                     let _ = client.send(message.clone());
                 }
 
                 // Send counts to all players.
-                for lobby in app.lobbies.values() {
+                for lobby in self.lobbies.values() {
                     lobby.broadcast(message.clone());
                 }
             }
-            AppMessage::RequestStart { player, lobby_id } => {
-                let Some(lobby) = app.lobbies.get_mut(&lobby_id) else {
+            AppMessage::ToggleReady {
+                player_id,
+                lobby_id,
+            } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
-                // Start the game inside the lobby if the player is the
-                // lobby owner.
-                if lobby.owner.is_some_and(|owner_id| owner_id.eq(&player.id))
-                    && lobby.status == LobbyStatus::WaitingForPlayers
-                {
-                    // Change the lobby status and tell clients about it.
-                    lobby.status = LobbyStatus::AboutToStart(Utc::now() + LOBBY_START_TIMER);
-                    let _ = app
-                        .tx
-                        .send(AppMessage::SendLobbyStatusUpdate { lobby_id: lobby.id });
-                    // Tell players in the lobby about the status update.
-                    lobby.broadcast(BackendMessage::StatusUpdate {
-                        status: lobby.status.clone(),
-                    });
+                // Players only get ready for a game that has not started yet,
+                // but may back out of an automatic start.
+                let is_auto_countdown =
+                    lobby.auto_started && matches!(lobby.status, LobbyStatus::AboutToStart(_));
+                if lobby.status != LobbyStatus::WaitingForPlayers && !is_auto_countdown {
+                    return;
+                }
+                let Some(player) = lobby.players.get_mut(&player_id) else {
+                    error!(
+                        "Player with ID {} was not found in lobby {}.",
+                        player_id, lobby.name
+                    );
+                    return;
+                };
+                if player.waiting {
+                    warn!(
+                        "Waiting player {} tried to get ready in lobby {}.",
+                        player.name, lobby.name
+                    );
+                    return;
+                }
+                player.ready = !player.ready;
+                let ready = player.ready;
+                lobby.broadcast(BackendMessage::UpdatePlayerReady { player_id, ready });
+
+                if is_auto_countdown && !ready {
+                    info!("Cancelled the automatic start of lobby {}.", lobby.name);
+                    lobby.cancel_countdown(&self.tx);
+                } else if lobby.is_ready_for_auto_start(self.config.solo_auto_start) {
+                    info!("All players of lobby {} are ready, starting.", lobby.name);
+                    lobby.auto_started = true;
+                    lobby.start_countdown(&self.tx);
+                }
+            }
+            AppMessage::RequestStart {
+                player,
+                lobby_id,
+                force,
+            } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    return;
+                };
+                // Only the lobby owner starts the game.
+                if !lobby.owner.is_some_and(|owner_id| owner_id.eq(&player.id)) {
+                    warn!(
+                        "Player {} tried to start lobby {} without being the owner.",
+                        player.name, lobby.name
+                    );
+                    return;
+                }
+                if lobby.status != LobbyStatus::WaitingForPlayers {
+                    return;
+                }
+                // Unless the owner forces the start, wait for all active
+                // players to be ready.
+                let all_ready = lobby
+                    .players
+                    .values()
+                    .filter(|player| !player.waiting)
+                    .all(|player| player.ready);
+                if !force && !all_ready {
+                    let _ = player.tx.send(BackendMessage::SendMessage(
+                        "Not all players are ready yet.".to_string(),
+                    ));
+                    return;
+                }
+                lobby.start_countdown(&self.tx);
+            }
+            AppMessage::CancelStart { player, lobby_id } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    return;
+                };
+                if !lobby.owner.is_some_and(|owner_id| owner_id.eq(&player.id)) {
+                    warn!(
+                        "Player {} tried to cancel the start of lobby {} without being the owner.",
+                        player.name, lobby.name
+                    );
+                    return;
+                }
+                let LobbyStatus::AboutToStart(_) = lobby.status else {
+                    return;
+                };
+                lobby.cancel_countdown(&self.tx);
+                info!("Cancelled the start of lobby {}.", lobby.name);
+            }
+            AppMessage::ToggleAutoStart { player, lobby_id } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    return;
+                };
+                if !lobby.owner.is_some_and(|owner_id| owner_id.eq(&player.id)) {
+                    warn!(
+                        "Player {} tried to toggle the automatic start of lobby {} without being the owner.",
+                        player.name, lobby.name
+                    );
+                    return;
+                }
+                lobby.auto_start = !lobby.auto_start;
+                info!(
+                    "Player {} turned the automatic start of lobby {} {}.",
+                    player.name,
+                    lobby.name,
+                    if lobby.auto_start { "on" } else { "off" }
+                );
+                lobby.broadcast(BackendMessage::UpdateAutoStart {
+                    auto_start: lobby.auto_start,
+                });
 
-                    // Wait for a duration of `LOBBY_START_TIMER` and tell
-                    // the application to start the lobby.
-                    let app_tx = app.tx.clone();
-                    let lobby_id = lobby.id;
-                    tokio::spawn(async move {
-                        tokio::time::sleep(LOBBY_START_TIMER).await;
-                        let _ = app_tx.send(AppMessage::Start { lobby_id });
+                // Everyone might be ready already, or still count down to an
+                // automatic start that is no longer wanted.
+                if lobby.is_ready_for_auto_start(self.config.solo_auto_start) {
+                    lobby.auto_started = true;
+                    lobby.start_countdown(&self.tx);
+                } else if lobby.auto_started && !lobby.auto_start {
+                    lobby.cancel_countdown(&self.tx);
+                }
+            }
+            AppMessage::KickPlayer {
+                player,
+                lobby_id,
+                player_id,
+            } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    return;
+                };
+                if !lobby.owner.is_some_and(|owner_id| owner_id.eq(&player.id)) {
+                    warn!(
+                        "Player {} tried to kick a player from lobby {} without being the owner.",
+                        player.name, lobby.name
+                    );
+                    return;
+                }
+                // The owner leaves by disconnecting instead.
+                if player_id.eq(&player.id) {
+                    return;
+                }
+                let Some(kicked_player) = lobby.players.get(&player_id).cloned() else {
+                    warn!(
+                        "Player with ID {} was not found in lobby {}.",
+                        player_id, lobby.name
+                    );
+                    return;
+                };
+                info!(
+                    "Player {} kicked player {} from lobby {}.",
+                    player.name, kicked_player.name, lobby.name
+                );
+                let _ = kicked_player.tx.send(BackendMessage::SendMessage(
+                    "You were kicked from the lobby.".to_string(),
+                ));
+                let _ = kicked_player.tx.send(BackendMessage::CloseConnection);
+                lobby.remove_player(kicked_player, &self.tx);
+            }
+            AppMessage::TransferOwnership {
+                player,
+                lobby_id,
+                player_id,
+            } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    return;
+                };
+                if !lobby.owner.is_some_and(|owner_id| owner_id.eq(&player.id)) {
+                    warn!(
+                        "Player {} tried to transfer the ownership of lobby {} without being the owner.",
+                        player.name, lobby.name
+                    );
+                    return;
+                }
+                // Only hand the lobby to players taking part in the game who
+                // are currently connected.
+                let Some(new_owner) = lobby
+                    .players
+                    .get(&player_id)
+                    .filter(|new_owner| !new_owner.waiting && new_owner.removal_time.is_none())
+                else {
+                    warn!(
+                        "Player {} tried to transfer the ownership of lobby {} to an unavailable player {}.",
+                        player.name, lobby.name, player_id
+                    );
+                    return;
+                };
+                info!(
+                    "Player {} transferred the ownership of lobby {} to player {}.",
+                    player.name, lobby.name, new_owner.name
+                );
+                lobby.owner = Some(player_id);
+                lobby.broadcast(BackendMessage::AssignOwner { id: player_id });
+            }
+            AppMessage::RenameLobby {
+                player,
+                lobby_id,
+                name,
+            } => {
+                let Some(lobby) = self.lobbies.get(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    return;
+                };
+                if !lobby.owner.is_some_and(|owner_id| owner_id.eq(&player.id)) {
+                    warn!(
+                        "Player {} tried to rename lobby {} without being the owner.",
+                        player.name, lobby.name
+                    );
+                    return;
+                }
+                let Some(name) = sanitize_name(&name, MAX_LOBBY_NAME_LENGTH) else {
+                    warn!(
+                        "Player {} tried to rename lobby {} to an empty name.",
+                        player.name, lobby.name
+                    );
+                    return;
+                };
+                // Keeping the current name must not number it.
+                if name == lobby.name {
+                    return;
+                }
+                let name = self.unique_lobby_name(name);
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    return;
+                };
+                info!(
+                    "Player {} renamed lobby {} to {}.",
+                    player.name, lobby.name, name
+                );
+                lobby.name = name.clone();
+                lobby.broadcast_chat_message(format!(
+                    "{} renamed the lobby to {}.",
+                    player.name, name
+                ));
+                lobby.broadcast(BackendMessage::UpdateLobbyName {
+                    id: lobby_id,
+                    name: name.clone(),
+                });
+                for client in self.clients.values() {
+                    let _ = client.send(BackendMessage::UpdateLobbyName {
+                        id: lobby_id,
+                        name: name.clone(),
                     });
                 }
             }
-            AppMessage::Start { lobby_id } => {
-                let Some(lobby) = app.lobbies.get_mut(&lobby_id) else {
+            AppMessage::Start {
+                lobby_id,
+                generation,
+            } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
+                // The timer was cancelled or replaced by another one.
+                if generation != lobby.start_timer_generation {
+                    return;
+                }
                 let LobbyStatus::AboutToStart(_) = lobby.status else {
                     warn!(
                         "Tried to start lobby {} with {} players that was not about to start.",
                         lobby.name,
                         lobby.players.len()
                     );
-                    continue;
+                    return;
                 };
-                lobby.status = LobbyStatus::InProgress(Utc::now() + MAX_LOBBY_PLAY_TIME);
+                lobby.status =
+                    LobbyStatus::InProgress(Utc::now() + self.config.max_lobby_play_time());
+                lobby.started_at = Some(Utc::now());
+                lobby.auto_started = false;
+
+                // Seed the progress of all active players with the progress of
+                // the untouched start file, which may already partially match
+                // the goal file.
+                let initial_progress = compute_progress(
+                    &lobby.challenge_files.goal_file,
+                    &lobby.challenge_files.start_file,
+                    lobby.normalize_line_endings,
+                    lobby.scoring_mode,
+                );
+                for player in lobby.players.values_mut().filter(|player| !player.waiting) {
+                    player.reset_round(initial_progress);
+                }
+                lobby.players.values().for_each(|player| {
+                    lobby.broadcast(BackendMessage::UpdatePlayerProgress {
+                        player_id: player.id,
+                        progress: player.progress,
+                        edits_per_minute: player.edits_per_minute,
+                    });
+                });
+
                 // Tell clients about the started lobby.
-                let _ = app
+                let _ = self
                     .tx
                     .send(AppMessage::SendLobbyStatusUpdate { lobby_id: lobby.id });
+                let _ = self.tx.send(AppMessage::PublishEvent {
+                    event: GameEvent::StatusChanged {
+                        lobby_id: lobby.id,
+                        status: lobby.status.clone(),
+                    },
+                });
                 // Tell players in the lobby about the status update.
                 lobby.broadcast(BackendMessage::StatusUpdate {
                     status: lobby.status.clone(),
                 });
 
-                // Put the lobby in `LobbyStatus::Finish` after two minutes.
-                let app_tx = app.tx.clone();
-                tokio::spawn(async move {
-                    tokio::time::sleep(MAX_LOBBY_PLAY_TIME).await;
-                    let _ = app_tx.send(AppMessage::Finish { lobby_id });
+                // Put the lobby in `LobbyStatus::Finish` after the configured game
+                // duration.
+                lobby.schedule_finish(self.config.max_lobby_play_time(), &self.tx);
+            }
+            AppMessage::TogglePause { player, lobby_id } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    return;
+                };
+                if !lobby.owner.is_some_and(|owner_id| owner_id.eq(&player.id)) {
+                    warn!(
+                        "Player {} tried to pause lobby {} without being the owner.",
+                        player.name, lobby.name
+                    );
+                    return;
+                }
+                match lobby.status {
+                    LobbyStatus::InProgress(finish_time) => {
+                        // Keep the remaining play time and stop the finish
+                        // timer.
+                        let resumed_remaining = finish_time
+                            .signed_duration_since(Utc::now())
+                            .to_std()
+                            .unwrap_or_default();
+                        lobby.cancel_finish();
+                        lobby.status = LobbyStatus::Paused { resumed_remaining };
+                        info!("Paused lobby {}.", lobby.name);
+                    }
+                    LobbyStatus::Paused { resumed_remaining } => {
+                        lobby.status = LobbyStatus::InProgress(Utc::now() + resumed_remaining);
+                        lobby.schedule_finish(resumed_remaining, &self.tx);
+                        info!("Resumed lobby {}.", lobby.name);
+                    }
+                    _ => return,
+                }
+                // Tell clients about the paused or resumed lobby.
+                let _ = self
+                    .tx
+                    .send(AppMessage::SendLobbyStatusUpdate { lobby_id: lobby.id });
+                let _ = self.tx.send(AppMessage::PublishEvent {
+                    event: GameEvent::StatusChanged {
+                        lobby_id: lobby.id,
+                        status: lobby.status.clone(),
+                    },
+                });
+                // Tell players in the lobby about the status update.
+                lobby.broadcast(BackendMessage::StatusUpdate {
+                    status: lobby.status.clone(),
                 });
             }
+            AppMessage::ListLobbies { tx } => {
+                let _ = tx.send(self.get_current_lobbies());
+            }
+            AppMessage::SendLeaderboard => {
+                for client in self.clients.values() {
+                    let _ = client.send(BackendMessage::Leaderboard(
+                        self.leaderboard.standings.clone(),
+                    ));
+                }
+            }
+            AppMessage::Metrics { tx } => {
+                let snapshot = MetricsSnapshot {
+                    lobbies: self.lobbies.len(),
+                    players: self.lobbies.values().map(|lobby| lobby.players.len()).sum(),
+                    clients: self.clients.len(),
+                    spectators: self
+                        .lobbies
+                        .values()
+                        .map(|lobby| lobby.spectators.len())
+                        .sum(),
+                    lobbies_created: self.metrics.lobbies_created,
+                    games_finished: self.metrics.games_finished,
+                    messages_broadcast: self.metrics.messages_broadcast(),
+                };
+                let _ = tx.send(snapshot);
+            }
+            AppMessage::Leaderboard { tx } => {
+                let entries = match self
+                    .database
+                    .as_ref()
+                    .map(|database| database.leaderboard())
+                {
+                    Some(Ok(entries)) => entries,
+                    Some(Err(e)) => {
+                        error!("Error reading leaderboard from database: {e}");
+                        vec![]
+                    }
+                    None => vec![],
+                };
+                let _ = tx.send(entries);
+            }
             AppMessage::SendLobbyPlayerCountUpdate { lobby_id } => {
-                let Some(lobby) = app.lobbies.get(&lobby_id) else {
+                let Some(lobby) = self.lobbies.get(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
-                for client in app.clients.values() {
+                for client in self.clients.values() {
                     let _ = client.send(BackendMessage::UpdateLobbyPlayerCount {
                         id: lobby_id,
                         player_count: lobby.players.len(),
@@ -282,67 +1053,133 @@ pub async fn handle_app_message(mut app: App) {
                 }
             }
             AppMessage::SendLobbyStatusUpdate { lobby_id } => {
-                let Some(lobby) = app.lobbies.get(&lobby_id) else {
+                let Some(lobby) = self.lobbies.get(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
-                for client in app.clients.values() {
+                for client in self.clients.values() {
                     let _ = client.send(BackendMessage::UpdateLobbyStatus {
                         id: lobby_id,
                         status: lobby.status.clone(),
+                        removal_time: lobby.removal_time,
                     });
                 }
             }
-            AppMessage::Finish { lobby_id } => {
-                let Some(lobby) = app.lobbies.get_mut(&lobby_id) else {
+            AppMessage::Finish {
+                lobby_id,
+                generation,
+            } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
+                // The timer was cancelled or replaced by another one.
+                if generation != lobby.finish_timer_generation {
+                    return;
+                }
                 let LobbyStatus::InProgress(_) = lobby.status else {
-                    continue;
+                    return;
                 };
-                lobby.status = LobbyStatus::Finish(Utc::now() + LOBBY_FINISH_TIME);
+                let finished_at = Utc::now();
+                lobby.status = LobbyStatus::Finish(finished_at + self.config.lobby_finish_time());
+                self.metrics.games_finished += 1;
+
+                // Persist the results of this round if a database is configured.
+                let round = lobby.to_round_result(finished_at);
+                if let (Some(database), Some(round)) = (self.database.as_mut(), round.as_ref()) {
+                    if let Err(e) = database.insert_round(round) {
+                        error!("Error persisting results of lobby {}: {e}", lobby.name);
+                    }
+                }
                 // Tell clients about the finished lobby.
-                let _ = app
+                let _ = self
                     .tx
                     .send(AppMessage::SendLobbyStatusUpdate { lobby_id: lobby.id });
-                // Tell players in the lobby about the status update.
+                let _ = self.tx.send(AppMessage::PublishEvent {
+                    event: GameEvent::StatusChanged {
+                        lobby_id: lobby.id,
+                        status: lobby.status.clone(),
+                    },
+                });
+                // Tell players in the lobby about the status update and the
+                // final standings.
                 lobby.broadcast(BackendMessage::StatusUpdate {
                     status: lobby.status.clone(),
                 });
+                if let Some(round) = round {
+                    let standings = round
+                        .players
+                        .into_iter()
+                        .map(|player| FinalStanding {
+                            player_id: player.player_id,
+                            player_name: player.player_name,
+                            rank: player.rank,
+                            progress: player.progress,
+                            finish_time_ms: player.finish_time_ms,
+                        })
+                        .collect();
+                    lobby.broadcast(BackendMessage::FinalStandings(standings));
+                }
 
-                // Put the lobby in `LobbyStatus::WaitingForPlayers` after two minutes.
-                let app_tx = app.tx.clone();
+                // Put the lobby in `LobbyStatus::WaitingForPlayers` once the
+                // finish time is over.
+                let app_tx = self.tx.clone();
+                let finish_time = self.config.lobby_finish_time();
                 tokio::spawn(async move {
-                    tokio::time::sleep(LOBBY_FINISH_TIME).await;
+                    tokio::time::sleep(finish_time).await;
                     let _ = app_tx.send(AppMessage::Reset { lobby_id });
                 });
             }
             AppMessage::Reset { lobby_id } => {
-                let Some(lobby) = app.lobbies.get_mut(&lobby_id) else {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
 
-                // Reset all players progress.
+                // Reset all players progress. Players have to get ready for
+                // the next game again. Waiting players take part from now on.
+                // Nobody can be active while waiting or during a pause, so the
+                // idle timeout starts over.
+                let mut promoted_players = vec![];
                 for player in lobby.players.values_mut() {
-                    player.progress = 0.0;
+                    player.reset_round(0.0);
+                    player.ready = false;
+                    player.last_active_at = Instant::now();
+                    if player.waiting {
+                        player.waiting = false;
+                        promoted_players.push(player.id);
+                    }
+                }
+                lobby.started_at = None;
+                for player_id in promoted_players {
+                    lobby.broadcast(BackendMessage::PromotePlayer { player_id });
                 }
 
                 lobby.players.values().for_each(|player| {
-                    // Tell players in the lobby about the progress reset of each
-                    // player.
+                    // Tell players in the lobby about the progress and ready
+                    // reset of each player.
                     lobby.broadcast(BackendMessage::UpdatePlayerProgress {
                         player_id: player.id,
                         progress: player.progress,
+                        edits_per_minute: player.edits_per_minute,
+                    });
+                    lobby.broadcast(BackendMessage::UpdatePlayerReady {
+                        player_id: player.id,
+                        ready: player.ready,
                     });
                 });
 
                 lobby.status = LobbyStatus::WaitingForPlayers;
                 // Tell clients about the reset lobby.
-                let _ = app
+                let _ = self
                     .tx
                     .send(AppMessage::SendLobbyStatusUpdate { lobby_id: lobby.id });
+                let _ = self.tx.send(AppMessage::PublishEvent {
+                    event: GameEvent::StatusChanged {
+                        lobby_id: lobby.id,
+                        status: lobby.status.clone(),
+                    },
+                });
                 // Tell players in the lobby about the status update.
                 lobby.broadcast(BackendMessage::StatusUpdate {
                     status: lobby.status.clone(),
@@ -353,21 +1190,17 @@ pub async fn handle_app_message(mut app: App) {
                 player_id,
                 progress,
             } => {
-                let Some(lobby) = app.lobbies.get_mut(&lobby_id) else {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
                     error!("Lobby with ID {} was not found.", lobby_id);
-                    continue;
+                    return;
                 };
-                let finished_player_count = lobby
-                    .players
-                    .values()
-                    .filter(|player| player.progress == 1.0)
-                    .count();
+                let progress_size_limit = lobby.progress_size_limit();
                 let Some(player) = lobby.players.get_mut(&player_id) else {
                     error!(
                         "Player with ID {} was not found in lobby {}.",
                         player_id, lobby.name
                     );
-                    continue;
+                    return;
                 };
 
                 if player.waiting {
@@ -375,7 +1208,7 @@ pub async fn handle_app_message(mut app: App) {
                         "Waiting player {} tried to progress in lobby {}.",
                         player.name, lobby.name
                     );
-                    continue;
+                    return;
                 }
 
                 // We only allow players to progress when the lobby is currently
@@ -385,37 +1218,107 @@ pub async fn handle_app_message(mut app: App) {
                         "Player {} tried to progress in lobby {} that is not in progress.",
                         player.name, lobby.name
                     );
-                    continue;
+                    return;
                 };
-                let goal_file = match std::str::from_utf8(&lobby.challenge_files.goal_file) {
-                    Ok(goal_file) => goal_file,
-                    Err(e) => {
-                        error!("Error converting goal file bytes to string: {e}");
-                        continue;
-                    }
+                // Keep the progress of finished players at 100% if configured.
+                if player.finished_at.is_some() && self.config.freeze_finished_progress {
+                    return;
+                }
+                // Refuse to score payloads that would take too long to
+                // compare with the goal file.
+                if progress.len() > progress_size_limit {
+                    warn!(
+                        "Player {} sent {} bytes of progress in lobby {}, exceeding the limit of {} bytes.",
+                        player.name,
+                        progress.len(),
+                        lobby.name,
+                        progress_size_limit
+                    );
+                    return;
+                }
+                // Score at most one update per player at a time. Later updates
+                // supersede each other, so the newest edit is always scored.
+                if player.scoring {
+                    player.pending_progress = Some(progress);
+                    return;
+                }
+                player.scoring = true;
+                score_progress(self.tx.clone(), lobby, player_id, progress);
+            }
+            AppMessage::ApplyPlayerProgress {
+                lobby_id,
+                player_id,
+                started_at,
+                progress,
+            } => {
+                let Some(lobby) = self.lobbies.get_mut(&lobby_id) else {
+                    error!("Lobby with ID {} was not found.", lobby_id);
+                    return;
                 };
-                let player_file = match std::str::from_utf8(&progress) {
-                    Ok(player_file) => player_file,
-                    Err(e) => {
-                        error!("Error converting player file bytes to string: {e}");
-                        continue;
-                    }
+                let finished_player_count = lobby
+                    .players
+                    .values()
+                    .filter(|player| player.finished_at.is_some())
+                    .count();
+                let Some(player) = lobby.players.get_mut(&player_id) else {
+                    error!(
+                        "Player with ID {} was not found in lobby {}.",
+                        player_id, lobby.name
+                    );
+                    return;
                 };
-
-                // Compute the levenshtein distance between goal and player
-                // file.
-                let progress = normalized_levenshtein(goal_file, player_file);
+                player.scoring = false;
+                // Score the update that arrived in the meantime next.
+                if let Some(progress) = player.pending_progress.take() {
+                    let _ = self.tx.send(AppMessage::ComputePlayerProgress {
+                        lobby_id,
+                        player_id,
+                        progress,
+                    });
+                }
+                let Some(progress) = progress else {
+                    return;
+                };
+                // The game might have ended or restarted while scoring.
+                if started_at != lobby.started_at {
+                    return;
+                }
+                let LobbyStatus::InProgress(_) = lobby.status else {
+                    return;
+                };
+                if player.finished_at.is_some() && self.config.freeze_finished_progress {
+                    return;
+                }
                 player.progress = progress;
+                player.record_edit(lobby.started_at);
+                let edits_per_minute = player.edits_per_minute;
+                let announce_activity = player.announce_activity();
+                let mut finished_after = None;
+                let newly_finished = progress.eq(&1.0) && player.finished_at.is_none();
+                if newly_finished {
+                    let finished_at = Utc::now();
+                    player.finished_at = Some(finished_at);
+                    player.position = Some(finished_player_count + 1);
+                    finished_after = Some(Duration::default());
 
-                // If a player won we reduce the lobby lifetime and tell all
-                // players about it.
-                if progress.eq(&1.0) {
-                    lobby.status = LobbyStatus::InProgress(Utc::now() + REDUCED_LOBBY_PLAY_TIME);
-                    let app_tx = app.tx.clone();
-                    tokio::spawn(async move {
-                        tokio::time::sleep(REDUCED_LOBBY_PLAY_TIME).await;
-                        let _ = app_tx.send(AppMessage::Finish { lobby_id });
-                    });
+                    // Remember the result across games. The first player to
+                    // finish wins the game.
+                    if let Some(started_at) = lobby.started_at {
+                        let time = finished_at - started_at;
+                        let won = finished_player_count == 0;
+                        self.leaderboard
+                            .record_finish(&player.name, time.num_milliseconds(), won);
+                        let _ = self.tx.send(AppMessage::SendLeaderboard);
+                        finished_after = time.to_std().ok();
+                    }
+                }
+
+                // If a player finished we reduce the lobby lifetime and tell
+                // all players about it. Reaching the goal again after further
+                // edits is not announced again.
+                if newly_finished {
+                    lobby.status =
+                        LobbyStatus::InProgress(Utc::now() + self.config.reduced_lobby_play_time());
 
                     // Tell players that the player finished.
                     let message = format!(
@@ -423,12 +1326,34 @@ pub async fn handle_app_message(mut app: App) {
                         player.name,
                         finished_player_count + 1
                     );
-                    lobby.broadcast(BackendMessage::SendMessage(message));
+                    lobby.broadcast_chat_message(message);
+                    lobby.schedule_finish(self.config.reduced_lobby_play_time(), &self.tx);
 
                     // Tell players in the lobby about the status update.
                     lobby.broadcast(BackendMessage::StatusUpdate {
                         status: lobby.status.clone(),
                     });
+                    if let Some(duration) = finished_after {
+                        lobby.broadcast(BackendMessage::PlayerFinished {
+                            player_id,
+                            position: finished_player_count + 1,
+                            duration,
+                        });
+                    }
+
+                    let _ = self.tx.send(AppMessage::PublishEvent {
+                        event: GameEvent::PlayerFinished {
+                            lobby_id,
+                            player_id,
+                            position: finished_player_count + 1,
+                        },
+                    });
+                    let _ = self.tx.send(AppMessage::PublishEvent {
+                        event: GameEvent::StatusChanged {
+                            lobby_id,
+                            status: lobby.status.clone(),
+                        },
+                    });
                 }
 
                 // Tell players in the lobby about the progress update of this
@@ -436,7 +1361,11 @@ pub async fn handle_app_message(mut app: App) {
                 lobby.broadcast(BackendMessage::UpdatePlayerProgress {
                     player_id,
                     progress,
+                    edits_per_minute,
                 });
+                if announce_activity {
+                    lobby.broadcast(BackendMessage::PlayerActive { player_id });
+                }
             }
         }
     }
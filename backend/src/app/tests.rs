@@ -0,0 +1,1061 @@
+use std::{
+    collections::BTreeSet,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver},
+    oneshot,
+};
+use uuid::Uuid;
+
+use common::{BackendMessage, CreateLobbyOptions, JoinMode, LobbyInformation, LobbyStatus};
+
+use super::{
+    message::{handle_app_message, AppMessage},
+    App,
+};
+use crate::{
+    args::ServerConfig, challenge::ChallengeCatalog, constants::IDLE_KICK_TIMEOUT,
+    leaderboard::Leaderboard, lobby::ScoringMode, player::Player,
+};
+
+/// Creates an app without database, leaderboard file and challenge catalog.
+fn new_app(config: ServerConfig) -> App {
+    let (tx, rx) = unbounded_channel();
+    App::new(
+        tx,
+        rx,
+        None,
+        Leaderboard::default(),
+        ScoringMode::default(),
+        ChallengeCatalog::default(),
+        config,
+    )
+}
+
+/// Creates a player whose backend messages end up in the returned receiver.
+fn new_player(name: &str) -> (Player, UnboundedReceiver<BackendMessage>) {
+    let (tx, rx) = unbounded_channel();
+    (Player::new(tx, Some(name.to_string())), rx)
+}
+
+/// Handles all messages the app sent to itself, including the ones sent
+/// while handling them.
+fn process(app: &mut App) {
+    while let Ok(msg) = app.rx.try_recv() {
+        app.handle_message(msg);
+    }
+}
+
+/// Sends a message to the app and handles it along with all follow-up
+/// messages.
+fn send(app: &mut App, msg: AppMessage) {
+    app.handle_message(msg);
+    process(app);
+}
+
+/// Returns all messages a player or client received so far.
+fn received(rx: &mut UnboundedReceiver<BackendMessage>) -> Vec<BackendMessage> {
+    let mut messages = vec![];
+    while let Ok(msg) = rx.try_recv() {
+        messages.push(msg);
+    }
+    messages
+}
+
+/// Creates a lobby via `JoinMode::Create` and returns its ID.
+fn create_lobby(app: &mut App) -> Uuid {
+    let lobby_id = app
+        .get_lobby_id(JoinMode::Create, CreateLobbyOptions::default())
+        .expect("lobby should be created");
+    process(app);
+    lobby_id
+}
+
+/// Adds a new player to the lobby and returns him along with his receiver.
+fn join(app: &mut App, lobby_id: Uuid, name: &str) -> (Player, UnboundedReceiver<BackendMessage>) {
+    let (player, rx) = new_player(name);
+    send(
+        app,
+        AppMessage::AddPlayerToLobby {
+            lobby_id,
+            player: player.clone(),
+        },
+    );
+    (player, rx)
+}
+
+/// Asks the app for the lobby information matching the join mode. The reply
+/// is ready once this returns.
+fn provide_lobby_information(
+    app: &mut App,
+    join_mode: JoinMode,
+) -> oneshot::Receiver<Result<LobbyInformation, BackendMessage>> {
+    let (tx, rx) = oneshot::channel();
+    send(
+        app,
+        AppMessage::ProvideLobbyInformation {
+            tx,
+            join_mode,
+            options: CreateLobbyOptions::default(),
+        },
+    );
+    rx
+}
+
+fn owner_assignments(messages: &[BackendMessage]) -> Vec<Uuid> {
+    messages
+        .iter()
+        .filter_map(|msg| match msg {
+            BackendMessage::AssignOwner { id } => Some(*id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn create_join_mode_creates_lobby() {
+    let mut app = new_app(ServerConfig::default());
+    let client_id = Uuid::new_v4();
+    let (client_tx, mut client_rx) = unbounded_channel();
+    send(
+        &mut app,
+        AppMessage::AddClient {
+            client_id,
+            client_tx,
+        },
+    );
+
+    let lobby_id = create_lobby(&mut app);
+
+    let lobby = &app.lobbies[&lobby_id];
+    assert_eq!(lobby.status, LobbyStatus::WaitingForPlayers);
+    assert!(lobby.players.is_empty());
+    assert_eq!(lobby.owner, None);
+    // Clients learn about the new lobby.
+    assert!(received(&mut client_rx)
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::AddLobby(id, _) if *id == lobby_id)));
+}
+
+#[tokio::test]
+async fn first_player_becomes_owner() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+
+    let (first, mut first_rx) = join(&mut app, lobby_id, "First");
+    let (second, mut second_rx) = join(&mut app, lobby_id, "Second");
+
+    assert_eq!(app.lobbies[&lobby_id].owner, Some(first.id));
+    let first_messages = received(&mut first_rx);
+    assert_eq!(owner_assignments(&first_messages), vec![first.id]);
+    assert!(first_messages
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::AddPlayer(player) if player.id == second.id)));
+    // The second player is told his ID, but not made the owner.
+    let second_messages = received(&mut second_rx);
+    assert!(owner_assignments(&second_messages).is_empty());
+    assert!(second_messages
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::ProvidePlayerId { id, .. } if *id == second.id)));
+}
+
+#[tokio::test]
+async fn owner_is_reassigned_when_owner_leaves() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (first, _first_rx) = join(&mut app, lobby_id, "First");
+    let (second, mut second_rx) = join(&mut app, lobby_id, "Second");
+    received(&mut second_rx);
+
+    send(
+        &mut app,
+        AppMessage::RemovePlayer {
+            player: first.clone(),
+            lobby_id,
+        },
+    );
+
+    let lobby = &app.lobbies[&lobby_id];
+    assert!(!lobby.players.contains_key(&first.id));
+    assert_eq!(lobby.owner, Some(second.id));
+    let second_messages = received(&mut second_rx);
+    assert!(second_messages
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::RemovePlayer(id) if *id == first.id)));
+    assert_eq!(owner_assignments(&second_messages), vec![second.id]);
+}
+
+#[tokio::test]
+async fn last_player_leaving_schedules_lobby_removal() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (player, _rx) = join(&mut app, lobby_id, "Player");
+
+    send(&mut app, AppMessage::RemovePlayer { player, lobby_id });
+
+    let lobby = &app.lobbies[&lobby_id];
+    assert_eq!(lobby.owner, None);
+    assert!(lobby.removal_time.is_some());
+}
+
+#[tokio::test]
+async fn empty_lobby_removal_uses_configured_grace_period() {
+    let mut app = new_app(ServerConfig {
+        empty_lobby_lifetime: 300,
+        ..ServerConfig::default()
+    });
+    let lobby_id = create_lobby(&mut app);
+    let (player, _rx) = join(&mut app, lobby_id, "Player");
+
+    let left_at = Utc::now();
+    send(&mut app, AppMessage::RemovePlayer { player, lobby_id });
+
+    let removal_time = app.lobbies[&lobby_id].removal_time.unwrap();
+    let grace_period = removal_time - left_at;
+    assert!(grace_period > chrono::Duration::seconds(299));
+    assert!(grace_period <= chrono::Duration::seconds(301));
+}
+
+#[tokio::test]
+async fn quickplay_picks_fullest_non_full_lobby() {
+    let mut app = new_app(ServerConfig::default());
+    let emptier_lobby_id = create_lobby(&mut app);
+    let fuller_lobby_id = create_lobby(&mut app);
+    let full_lobby_id = create_lobby(&mut app);
+    app.lobbies.get_mut(&full_lobby_id).unwrap().max_players = 3;
+    let mut receivers = vec![];
+    for (lobby_id, player_count) in [
+        (emptier_lobby_id, 1),
+        (fuller_lobby_id, 2),
+        (full_lobby_id, 3),
+    ] {
+        for i in 0..player_count {
+            receivers.push(join(&mut app, lobby_id, &format!("Player {i}")));
+        }
+    }
+
+    let lobby_information = provide_lobby_information(&mut app, JoinMode::Quickplay)
+        .try_recv()
+        .expect("app should reply")
+        .expect("quickplay should find a lobby");
+
+    assert_eq!(lobby_information.id, fuller_lobby_id);
+    assert_eq!(app.lobbies.len(), 3);
+}
+
+#[tokio::test]
+async fn quickplay_creates_lobby_without_candidates() {
+    let mut app = new_app(ServerConfig::default());
+    let full_lobby_id = create_lobby(&mut app);
+    app.lobbies.get_mut(&full_lobby_id).unwrap().max_players = 2;
+    let _first = join(&mut app, full_lobby_id, "First");
+    let _second = join(&mut app, full_lobby_id, "Second");
+
+    let lobby_information = provide_lobby_information(&mut app, JoinMode::Quickplay)
+        .try_recv()
+        .expect("app should reply")
+        .expect("quickplay should create a lobby");
+
+    assert_ne!(lobby_information.id, full_lobby_id);
+    assert_eq!(app.lobbies.len(), 2);
+}
+
+#[tokio::test]
+async fn joining_full_lobby_is_refused() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    app.lobbies.get_mut(&lobby_id).unwrap().max_players = 2;
+    let _first = join(&mut app, lobby_id, "First");
+    let _second = join(&mut app, lobby_id, "Second");
+
+    let (third, mut third_rx) = join(&mut app, lobby_id, "Third");
+
+    assert!(!app.lobbies[&lobby_id].players.contains_key(&third.id));
+    assert!(received(&mut third_rx)
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::LobbyFull)));
+}
+
+#[tokio::test]
+async fn joining_unknown_lobby_is_refused() {
+    let mut app = new_app(ServerConfig::default());
+
+    let result = provide_lobby_information(
+        &mut app,
+        JoinMode::Join {
+            lobby_id: Uuid::new_v4(),
+        },
+    )
+    .try_recv()
+    .expect("app should reply");
+
+    assert!(matches!(result, Err(BackendMessage::LobbyNotFound)));
+    assert!(app.lobbies.is_empty());
+}
+
+/// Puts the lobby in progress as if the start countdown ran out.
+fn start_game(app: &mut App, lobby_id: Uuid) {
+    let lobby = app.lobbies.get_mut(&lobby_id).unwrap();
+    lobby.status = LobbyStatus::InProgress(Utc::now() + Duration::from_secs(60));
+    lobby.started_at = Some(Utc::now());
+}
+
+/// Waits for the app to receive the next message and handles it along with
+/// all follow-up messages.
+async fn handle_next(app: &mut App) {
+    let msg = app.rx.recv().await.expect("app should receive a message");
+    send(app, msg);
+}
+
+#[tokio::test]
+async fn oversized_progress_is_rejected_without_scoring() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (player, mut rx) = join(&mut app, lobby_id, "Player");
+    start_game(&mut app, lobby_id);
+    received(&mut rx);
+
+    let progress_size_limit = app.lobbies[&lobby_id].progress_size_limit();
+    send(
+        &mut app,
+        AppMessage::ComputePlayerProgress {
+            lobby_id,
+            player_id: player.id,
+            progress: vec![b'a'; progress_size_limit + 1],
+        },
+    );
+
+    let player = &app.lobbies[&lobby_id].players[&player.id];
+    assert!(!player.scoring);
+    assert_eq!(player.edits, 0);
+    assert!(app.rx.try_recv().is_err());
+    assert!(!received(&mut rx)
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::UpdatePlayerProgress { .. })));
+}
+
+#[tokio::test]
+async fn progress_is_scored_outside_the_app() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (player, mut rx) = join(&mut app, lobby_id, "Player");
+    start_game(&mut app, lobby_id);
+    received(&mut rx);
+    let goal_file = app.lobbies[&lobby_id].challenge_files.goal_file.clone();
+
+    send(
+        &mut app,
+        AppMessage::ComputePlayerProgress {
+            lobby_id,
+            player_id: player.id,
+            progress: goal_file,
+        },
+    );
+    assert!(app.lobbies[&lobby_id].players[&player.id].scoring);
+    handle_next(&mut app).await;
+
+    let scored_player = &app.lobbies[&lobby_id].players[&player.id];
+    assert!(!scored_player.scoring);
+    assert_eq!(scored_player.progress, 1.0);
+    assert_eq!(scored_player.position, Some(1));
+    assert!(received(&mut rx).iter().any(|msg| matches!(
+        msg,
+        BackendMessage::UpdatePlayerProgress { player_id, progress, .. }
+            if *player_id == player.id && *progress == 1.0
+    )));
+}
+
+#[tokio::test]
+async fn only_newest_progress_is_scored_next() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (player, _rx) = join(&mut app, lobby_id, "Player");
+    start_game(&mut app, lobby_id);
+    let goal_file = app.lobbies[&lobby_id].challenge_files.goal_file.clone();
+
+    for progress in [b"first".to_vec(), b"second".to_vec(), goal_file.clone()] {
+        send(
+            &mut app,
+            AppMessage::ComputePlayerProgress {
+                lobby_id,
+                player_id: player.id,
+                progress,
+            },
+        );
+    }
+    let pending_progress = &app.lobbies[&lobby_id].players[&player.id].pending_progress;
+    assert_eq!(pending_progress.as_ref(), Some(&goal_file));
+
+    // Scoring the first update starts scoring the newest one.
+    handle_next(&mut app).await;
+    let scoring_player = &app.lobbies[&lobby_id].players[&player.id];
+    assert!(scoring_player.scoring);
+    assert!(scoring_player.pending_progress.is_none());
+    assert!(scoring_player.progress < 1.0);
+
+    handle_next(&mut app).await;
+    let scored_player = &app.lobbies[&lobby_id].players[&player.id];
+    assert!(!scored_player.scoring);
+    assert_eq!(scored_player.progress, 1.0);
+    assert_eq!(scored_player.edits, 2);
+}
+
+#[tokio::test]
+async fn progress_of_previous_game_is_dropped() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (player, _rx) = join(&mut app, lobby_id, "Player");
+    start_game(&mut app, lobby_id);
+
+    send(
+        &mut app,
+        AppMessage::ComputePlayerProgress {
+            lobby_id,
+            player_id: player.id,
+            progress: b"edit".to_vec(),
+        },
+    );
+    // A new game starts while the update is scored.
+    app.lobbies.get_mut(&lobby_id).unwrap().started_at = Some(Utc::now() + Duration::from_secs(1));
+    handle_next(&mut app).await;
+
+    let player = &app.lobbies[&lobby_id].players[&player.id];
+    assert!(!player.scoring);
+    assert_eq!(player.edits, 0);
+}
+
+#[tokio::test]
+async fn players_joining_mid_countdown_wait_for_next_game() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (active, _active_rx) = join(&mut app, lobby_id, "Active");
+    app.lobbies.get_mut(&lobby_id).unwrap().status =
+        LobbyStatus::AboutToStart(Utc::now() + Duration::from_secs(5));
+    let (late, mut late_rx) = join(&mut app, lobby_id, "Late");
+    assert!(app.lobbies[&lobby_id].players[&late.id].waiting);
+    start_game(&mut app, lobby_id);
+    let goal_file = app.lobbies[&lobby_id].challenge_files.goal_file.clone();
+
+    // Progress of the waiting player is never scored.
+    send(
+        &mut app,
+        AppMessage::ComputePlayerProgress {
+            lobby_id,
+            player_id: late.id,
+            progress: goal_file.clone(),
+        },
+    );
+    assert!(app.rx.try_recv().is_err());
+    let late_player = &app.lobbies[&lobby_id].players[&late.id];
+    assert!(!late_player.scoring);
+    assert_eq!(late_player.progress, 0.0);
+    assert_eq!(late_player.position, None);
+
+    // The active player finishes first.
+    send(
+        &mut app,
+        AppMessage::ComputePlayerProgress {
+            lobby_id,
+            player_id: active.id,
+            progress: goal_file,
+        },
+    );
+    handle_next(&mut app).await;
+    assert_eq!(app.lobbies[&lobby_id].players[&active.id].position, Some(1));
+
+    // Waiting players are left out of the final standings.
+    let generation = app.lobbies[&lobby_id].finish_timer_generation;
+    received(&mut late_rx);
+    send(
+        &mut app,
+        AppMessage::Finish {
+            lobby_id,
+            generation,
+        },
+    );
+    let standings = received(&mut late_rx)
+        .into_iter()
+        .find_map(|msg| match msg {
+            BackendMessage::FinalStandings(standings) => Some(standings),
+            _ => None,
+        })
+        .expect("final standings should be broadcast");
+    let ranked_players: Vec<Uuid> = standings.iter().map(|s| s.player_id).collect();
+    assert_eq!(ranked_players, vec![active.id]);
+
+    // The waiting player takes part in the next game.
+    send(&mut app, AppMessage::Reset { lobby_id });
+    assert!(!app.lobbies[&lobby_id].players[&late.id].waiting);
+    assert!(received(&mut late_rx).iter().any(|msg| matches!(
+        msg,
+        BackendMessage::PromotePlayer { player_id } if *player_id == late.id
+    )));
+}
+
+#[tokio::test]
+async fn taken_lobby_names_get_a_number() {
+    let mut app = new_app(ServerConfig::default());
+    let first_lobby_id = create_lobby(&mut app);
+    app.lobbies.get_mut(&first_lobby_id).unwrap().name = "Acme".to_string();
+
+    assert_eq!(app.unique_lobby_name("Other".to_string()), "Other");
+    assert_eq!(app.unique_lobby_name("Acme".to_string()), "Acme #2");
+
+    let second_lobby_id = create_lobby(&mut app);
+    app.lobbies.get_mut(&second_lobby_id).unwrap().name = "Acme #2".to_string();
+    assert_eq!(app.unique_lobby_name("Acme".to_string()), "Acme #3");
+}
+
+#[tokio::test]
+async fn created_lobbies_have_distinct_names() {
+    let mut app = new_app(ServerConfig::default());
+    for _ in 0..app.config.max_lobbies {
+        create_lobby(&mut app);
+    }
+
+    let names: BTreeSet<_> = app.lobbies.values().map(|lobby| &lobby.name).collect();
+    assert_eq!(names.len(), app.lobbies.len());
+}
+
+#[tokio::test]
+async fn quickplay_skips_lobbies_not_waiting_for_players() {
+    let mut app = new_app(ServerConfig::default());
+    let about_to_start_lobby_id = create_lobby(&mut app);
+    let in_progress_lobby_id = create_lobby(&mut app);
+    let full_lobby_id = create_lobby(&mut app);
+    app.lobbies.get_mut(&full_lobby_id).unwrap().max_players = 2;
+    let mut receivers = vec![];
+    for lobby_id in [about_to_start_lobby_id, in_progress_lobby_id, full_lobby_id] {
+        receivers.push(join(&mut app, lobby_id, "First"));
+        receivers.push(join(&mut app, lobby_id, "Second"));
+    }
+    app.lobbies
+        .get_mut(&about_to_start_lobby_id)
+        .unwrap()
+        .status = LobbyStatus::AboutToStart(Utc::now() + Duration::from_secs(10));
+    start_game(&mut app, in_progress_lobby_id);
+
+    let lobby_information = provide_lobby_information(&mut app, JoinMode::Quickplay)
+        .try_recv()
+        .expect("app should reply")
+        .expect("quickplay should create a lobby");
+
+    assert!(
+        ![about_to_start_lobby_id, in_progress_lobby_id, full_lobby_id]
+            .contains(&lobby_information.id)
+    );
+    assert_eq!(lobby_information.status, LobbyStatus::WaitingForPlayers);
+    assert_eq!(app.lobbies.len(), 4);
+}
+
+#[tokio::test]
+async fn creating_lobby_beyond_cap_is_refused() {
+    let mut app = new_app(ServerConfig {
+        max_lobbies: 2,
+        ..ServerConfig::default()
+    });
+    let first_lobby_id = create_lobby(&mut app);
+    let _second_lobby_id = create_lobby(&mut app);
+
+    let result = provide_lobby_information(&mut app, JoinMode::Create)
+        .try_recv()
+        .expect("app should reply");
+
+    assert!(matches!(result, Err(BackendMessage::ServerFull)));
+    assert_eq!(app.lobbies.len(), 2);
+    // Existing lobbies stay usable.
+    let (player, _rx) = join(&mut app, first_lobby_id, "Player");
+    assert!(app.lobbies[&first_lobby_id]
+        .players
+        .contains_key(&player.id));
+}
+
+#[tokio::test]
+async fn quickplay_on_full_server_is_refused() {
+    let mut app = new_app(ServerConfig {
+        max_lobbies: 1,
+        ..ServerConfig::default()
+    });
+    let lobby_id = create_lobby(&mut app);
+    start_game(&mut app, lobby_id);
+
+    let result = provide_lobby_information(&mut app, JoinMode::Quickplay)
+        .try_recv()
+        .expect("app should reply");
+
+    assert!(matches!(result, Err(BackendMessage::ServerFull)));
+    assert_eq!(app.lobbies.len(), 1);
+}
+
+fn status_updates(messages: &[BackendMessage]) -> Vec<LobbyStatus> {
+    messages
+        .iter()
+        .filter_map(|msg| match msg {
+            BackendMessage::StatusUpdate { status } => Some(status.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn owner_cancels_countdown() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, mut owner_rx) = join(&mut app, lobby_id, "Owner");
+    let (player, _player_rx) = join(&mut app, lobby_id, "Player");
+    send(
+        &mut app,
+        AppMessage::RequestStart {
+            player: owner.clone(),
+            lobby_id,
+            force: true,
+        },
+    );
+    assert!(matches!(
+        app.lobbies[&lobby_id].status,
+        LobbyStatus::AboutToStart(_)
+    ));
+    received(&mut owner_rx);
+
+    // Only the owner cancels the countdown.
+    send(&mut app, AppMessage::CancelStart { player, lobby_id });
+    assert!(matches!(
+        app.lobbies[&lobby_id].status,
+        LobbyStatus::AboutToStart(_)
+    ));
+
+    let start_timer_generation = app.lobbies[&lobby_id].start_timer_generation;
+    send(
+        &mut app,
+        AppMessage::CancelStart {
+            player: owner,
+            lobby_id,
+        },
+    );
+
+    let lobby = &app.lobbies[&lobby_id];
+    assert_eq!(lobby.status, LobbyStatus::WaitingForPlayers);
+    assert_ne!(lobby.start_timer_generation, start_timer_generation);
+    assert_eq!(
+        status_updates(&received(&mut owner_rx)),
+        vec![LobbyStatus::WaitingForPlayers]
+    );
+}
+
+#[tokio::test]
+async fn cancelling_without_countdown_does_nothing() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, mut owner_rx) = join(&mut app, lobby_id, "Owner");
+    received(&mut owner_rx);
+
+    send(
+        &mut app,
+        AppMessage::CancelStart {
+            player: owner,
+            lobby_id,
+        },
+    );
+
+    assert_eq!(
+        app.lobbies[&lobby_id].status,
+        LobbyStatus::WaitingForPlayers
+    );
+    assert!(status_updates(&received(&mut owner_rx)).is_empty());
+}
+
+#[tokio::test]
+async fn non_owner_start_is_refused_before_ready_check() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (_owner, _owner_rx) = join(&mut app, lobby_id, "Owner");
+    let (player, mut player_rx) = join(&mut app, lobby_id, "Player");
+    received(&mut player_rx);
+
+    for force in [false, true] {
+        send(
+            &mut app,
+            AppMessage::RequestStart {
+                player: player.clone(),
+                lobby_id,
+                force,
+            },
+        );
+    }
+
+    assert_eq!(
+        app.lobbies[&lobby_id].status,
+        LobbyStatus::WaitingForPlayers
+    );
+    // Not being the owner is not answered with the missing ready check.
+    assert!(!received(&mut player_rx)
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::SendMessage(_))));
+}
+
+#[tokio::test]
+async fn owner_start_waits_for_ready_players() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, mut owner_rx) = join(&mut app, lobby_id, "Owner");
+    let (_player, _player_rx) = join(&mut app, lobby_id, "Player");
+    received(&mut owner_rx);
+
+    send(
+        &mut app,
+        AppMessage::RequestStart {
+            player: owner,
+            lobby_id,
+            force: false,
+        },
+    );
+
+    assert_eq!(
+        app.lobbies[&lobby_id].status,
+        LobbyStatus::WaitingForPlayers
+    );
+    assert!(received(&mut owner_rx).iter().any(|msg| matches!(
+        msg,
+        BackendMessage::SendMessage(message) if message == "Not all players are ready yet."
+    )));
+}
+
+/// Lets a player of the lobby appear idle for longer than the kick timeout.
+fn make_idle(app: &mut App, lobby_id: Uuid, player_id: Uuid) {
+    let player = app
+        .lobbies
+        .get_mut(&lobby_id)
+        .unwrap()
+        .players
+        .get_mut(&player_id)
+        .unwrap();
+    player.last_active_at = Instant::now() - IDLE_KICK_TIMEOUT;
+}
+
+#[tokio::test]
+async fn idle_players_are_kicked_from_waiting_lobbies() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (idle, mut idle_rx) = join(&mut app, lobby_id, "Idle");
+    let (active, mut active_rx) = join(&mut app, lobby_id, "Active");
+    make_idle(&mut app, lobby_id, idle.id);
+    received(&mut idle_rx);
+    received(&mut active_rx);
+
+    send(&mut app, AppMessage::KickIdlePlayers);
+
+    assert!(received(&mut idle_rx)
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::CloseConnection)));
+    assert!(!received(&mut active_rx)
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::CloseConnection)));
+    let lobby = &app.lobbies[&lobby_id];
+    assert!(!lobby.players.contains_key(&idle.id));
+    assert!(lobby.players.contains_key(&active.id));
+}
+
+#[tokio::test]
+async fn idle_players_are_not_kicked_mid_game() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (idle, mut idle_rx) = join(&mut app, lobby_id, "Idle");
+    start_game(&mut app, lobby_id);
+    make_idle(&mut app, lobby_id, idle.id);
+    received(&mut idle_rx);
+
+    send(&mut app, AppMessage::KickIdlePlayers);
+
+    assert!(!received(&mut idle_rx)
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::CloseConnection)));
+    assert!(app.lobbies[&lobby_id].players.contains_key(&idle.id));
+}
+
+#[tokio::test]
+async fn reset_restarts_idle_timeout() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (player, mut rx) = join(&mut app, lobby_id, "Player");
+    start_game(&mut app, lobby_id);
+    make_idle(&mut app, lobby_id, player.id);
+
+    send(&mut app, AppMessage::Reset { lobby_id });
+    send(&mut app, AppMessage::KickIdlePlayers);
+
+    assert!(!received(&mut rx)
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::CloseConnection)));
+    assert!(app.lobbies[&lobby_id].players.contains_key(&player.id));
+}
+
+#[tokio::test]
+async fn owner_transfers_ownership() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, mut owner_rx) = join(&mut app, lobby_id, "Owner");
+    let (other, mut other_rx) = join(&mut app, lobby_id, "Other");
+    received(&mut owner_rx);
+    received(&mut other_rx);
+
+    send(
+        &mut app,
+        AppMessage::TransferOwnership {
+            player: owner,
+            lobby_id,
+            player_id: other.id,
+        },
+    );
+
+    assert_eq!(app.lobbies[&lobby_id].owner, Some(other.id));
+    assert_eq!(owner_assignments(&received(&mut owner_rx)), vec![other.id]);
+    assert_eq!(owner_assignments(&received(&mut other_rx)), vec![other.id]);
+}
+
+#[tokio::test]
+async fn non_owner_transfer_is_refused() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, mut owner_rx) = join(&mut app, lobby_id, "Owner");
+    let (other, _other_rx) = join(&mut app, lobby_id, "Other");
+    received(&mut owner_rx);
+
+    send(
+        &mut app,
+        AppMessage::TransferOwnership {
+            player: other.clone(),
+            lobby_id,
+            player_id: other.id,
+        },
+    );
+
+    assert_eq!(app.lobbies[&lobby_id].owner, Some(owner.id));
+    assert!(owner_assignments(&received(&mut owner_rx)).is_empty());
+}
+
+#[tokio::test]
+async fn transfer_to_unavailable_player_is_refused() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, mut owner_rx) = join(&mut app, lobby_id, "Owner");
+    let (disconnected, _disconnected_rx) = join(&mut app, lobby_id, "Disconnected");
+    send(
+        &mut app,
+        AppMessage::DisconnectPlayer {
+            player: disconnected.clone(),
+            lobby_id,
+        },
+    );
+    start_game(&mut app, lobby_id);
+    let (waiting, _waiting_rx) = join(&mut app, lobby_id, "Waiting");
+    assert!(app.lobbies[&lobby_id].players[&waiting.id].waiting);
+    received(&mut owner_rx);
+
+    for player_id in [disconnected.id, waiting.id] {
+        send(
+            &mut app,
+            AppMessage::TransferOwnership {
+                player: owner.clone(),
+                lobby_id,
+                player_id,
+            },
+        );
+    }
+
+    assert_eq!(app.lobbies[&lobby_id].owner, Some(owner.id));
+    assert!(owner_assignments(&received(&mut owner_rx)).is_empty());
+}
+
+#[tokio::test]
+async fn non_owner_kick_is_refused() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, mut owner_rx) = join(&mut app, lobby_id, "Owner");
+    let (other, _other_rx) = join(&mut app, lobby_id, "Other");
+    received(&mut owner_rx);
+
+    send(
+        &mut app,
+        AppMessage::KickPlayer {
+            player: other,
+            lobby_id,
+            player_id: owner.id,
+        },
+    );
+
+    assert!(app.lobbies[&lobby_id].players.contains_key(&owner.id));
+    assert!(!received(&mut owner_rx)
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::CloseConnection)));
+}
+
+#[tokio::test]
+async fn owner_cannot_kick_themself() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, mut owner_rx) = join(&mut app, lobby_id, "Owner");
+    received(&mut owner_rx);
+
+    send(
+        &mut app,
+        AppMessage::KickPlayer {
+            player: owner.clone(),
+            lobby_id,
+            player_id: owner.id,
+        },
+    );
+
+    let lobby = &app.lobbies[&lobby_id];
+    assert!(lobby.players.contains_key(&owner.id));
+    assert_eq!(lobby.owner, Some(owner.id));
+    assert!(received(&mut owner_rx).is_empty());
+}
+
+#[tokio::test]
+async fn kicked_player_is_closed_and_removed() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, mut owner_rx) = join(&mut app, lobby_id, "Owner");
+    let (kicked, mut kicked_rx) = join(&mut app, lobby_id, "Kicked");
+    received(&mut owner_rx);
+    received(&mut kicked_rx);
+
+    send(
+        &mut app,
+        AppMessage::KickPlayer {
+            player: owner,
+            lobby_id,
+            player_id: kicked.id,
+        },
+    );
+
+    assert!(!app.lobbies[&lobby_id].players.contains_key(&kicked.id));
+    assert!(matches!(
+        received(&mut kicked_rx).last(),
+        Some(BackendMessage::CloseConnection)
+    ));
+    assert!(received(&mut owner_rx).iter().any(
+        |msg| matches!(msg, BackendMessage::RemovePlayer(player_id) if *player_id == kicked.id)
+    ));
+}
+
+#[tokio::test]
+async fn reconnect_within_grace_period_restores_seat() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (owner, _old_rx) = join(&mut app, lobby_id, "Owner");
+    let (_other, _other_rx) = join(&mut app, lobby_id, "Other");
+    start_game(&mut app, lobby_id);
+    app.lobbies
+        .get_mut(&lobby_id)
+        .unwrap()
+        .players
+        .get_mut(&owner.id)
+        .unwrap()
+        .progress = 0.5;
+    send(
+        &mut app,
+        AppMessage::DisconnectPlayer {
+            player: owner.clone(),
+            lobby_id,
+        },
+    );
+    assert!(app.lobbies[&lobby_id].players[&owner.id]
+        .removal_time
+        .is_some());
+
+    let (player_tx, mut player_rx) = unbounded_channel();
+    let (tx, mut rx) = oneshot::channel();
+    send(
+        &mut app,
+        AppMessage::ReconnectPlayer {
+            lobby_id,
+            token: owner.token,
+            player_tx,
+            tx,
+        },
+    );
+
+    let reconnected = rx
+        .try_recv()
+        .expect("app should reply")
+        .expect("seat should be restored");
+    assert_eq!(reconnected.id, owner.id);
+    let lobby = &app.lobbies[&lobby_id];
+    assert_eq!(lobby.owner, Some(owner.id));
+    assert!(lobby.players[&owner.id].removal_time.is_none());
+    assert_eq!(lobby.players[&owner.id].progress, 0.5);
+    let messages = received(&mut player_rx);
+    assert!(messages.iter().any(|msg| matches!(
+        msg,
+        BackendMessage::SendLobbyInformation(information)
+            if information.owner == Some(owner.id)
+                && information.players[&owner.id].progress == 0.5
+    )));
+    assert!(messages.iter().any(|msg| matches!(
+        msg,
+        BackendMessage::ProvidePlayerId { id, .. } if *id == owner.id
+    )));
+    assert!(owner_assignments(&messages).is_empty());
+}
+
+#[tokio::test]
+async fn reconnect_with_unknown_token_fails() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (_player, _rx) = join(&mut app, lobby_id, "Player");
+    let route = crate::routes::players::routes(app.tx.clone());
+    tokio::spawn(handle_app_message(app));
+
+    let mut client = warp::test::ws()
+        .path(&format!("/players/{lobby_id}?token={}", Uuid::new_v4()))
+        .handshake(route)
+        .await
+        .expect("handshake should succeed");
+
+    let msg = client.recv().await.expect("message should arrive");
+    let msg = serde_json::from_str(msg.to_str().unwrap()).unwrap();
+    assert!(matches!(msg, BackendMessage::ReconnectFailed));
+}
+
+#[tokio::test]
+async fn disconnected_player_is_removed_after_grace_period() {
+    let mut app = new_app(ServerConfig::default());
+    let lobby_id = create_lobby(&mut app);
+    let (player, _rx) = join(&mut app, lobby_id, "Player");
+    let (_other, mut other_rx) = join(&mut app, lobby_id, "Other");
+    send(
+        &mut app,
+        AppMessage::DisconnectPlayer {
+            player: player.clone(),
+            lobby_id,
+        },
+    );
+    let remove = || AppMessage::RemoveDisconnectedPlayer {
+        lobby_id,
+        player_id: player.id,
+    };
+
+    // The seat is kept during the grace period.
+    send(&mut app, remove());
+    assert!(app.lobbies[&lobby_id].players.contains_key(&player.id));
+
+    // Let the grace period run out.
+    app.lobbies
+        .get_mut(&lobby_id)
+        .unwrap()
+        .players
+        .get_mut(&player.id)
+        .unwrap()
+        .removal_time = Some(Utc::now());
+    received(&mut other_rx);
+    send(&mut app, remove());
+
+    assert!(!app.lobbies[&lobby_id].players.contains_key(&player.id));
+    assert!(received(&mut other_rx).iter().any(
+        |msg| matches!(msg, BackendMessage::RemovePlayer(player_id) if *player_id == player.id)
+    ));
+}
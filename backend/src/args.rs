@@ -0,0 +1,148 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::{ArgAction, Parser, ValueEnum};
+
+use crate::{
+    constants::{
+        EMPTY_LOBBY_LIFETIME, LOBBY_FINISH_TIME, MAX_LOBBIES, MAX_LOBBY_PLAY_TIME,
+        NORMALIZE_LINE_ENDINGS, REDUCED_LOBBY_PLAY_TIME,
+    },
+    lobby::ScoringMode,
+};
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Args {
+    /// Path to a SQLite database file the results of finished rounds are
+    /// stored in. Results are not persisted if unset.
+    #[arg(long)]
+    pub db_path: Option<PathBuf>,
+    /// Path to a JSON file the win leaderboard is stored in. The leaderboard
+    /// only lives in memory if unset.
+    #[arg(long)]
+    pub leaderboard_path: Option<PathBuf>,
+    /// How the progress of players is computed in new lobbies.
+    #[arg(long, value_enum, default_value_t = ScoringMode::default())]
+    pub scoring_mode: ScoringMode,
+    /// Path to a directory of challenges. Every challenge is a folder holding
+    /// a `challenge.json` file with `name`, `language`, `description`,
+    /// `start_file` and `goal_file`, and optionally `instructions` and a
+    /// `cursor` with `line` and `column`. Lobbies use an embedded challenge if
+    /// unset or empty.
+    #[arg(long)]
+    pub challenges_dir: Option<PathBuf>,
+    #[command(flatten)]
+    pub server_config: ServerConfig,
+    /// The format of the log output.
+    #[arg(long, env = "LOG_FORMAT", value_enum, default_value_t = LogFormat::default())]
+    pub log_format: LogFormat,
+}
+
+/// # Server config
+///
+/// Limits and timings of the lobbies. Every value can be set via its
+/// environment variable and defaults to the previously hardcoded value.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ServerConfig {
+    /// The maximum number of lobbies that exist at the same time. Creating
+    /// further lobbies is rejected until others are removed.
+    #[arg(long, env = "MAX_LOBBIES", default_value_t = MAX_LOBBIES, value_parser = parse_max_lobbies)]
+    pub max_lobbies: usize,
+    /// Seconds an empty lobby survives before being removed.
+    #[arg(long, env = "EMPTY_LOBBY_LIFETIME", default_value_t = EMPTY_LOBBY_LIFETIME.as_secs(), value_parser = parse_seconds)]
+    pub empty_lobby_lifetime: u64,
+    /// Seconds a lobby stays in the finish state.
+    #[arg(long, env = "LOBBY_FINISH_TIME", default_value_t = LOBBY_FINISH_TIME.as_secs(), value_parser = parse_seconds)]
+    pub lobby_finish_time: u64,
+    /// Seconds a game is in progress at most.
+    #[arg(long, env = "MAX_LOBBY_PLAY_TIME", default_value_t = MAX_LOBBY_PLAY_TIME.as_secs(), value_parser = parse_seconds)]
+    pub max_lobby_play_time: u64,
+    /// Seconds a game continues after the first player finished.
+    #[arg(long, env = "REDUCED_LOBBY_PLAY_TIME", default_value_t = REDUCED_LOBBY_PLAY_TIME.as_secs(), value_parser = parse_seconds)]
+    pub reduced_lobby_play_time: u64,
+    /// Whether to ignore edits of players who already reached the goal, so
+    /// their progress stays at 100%.
+    #[arg(long, env = "FREEZE_FINISHED_PROGRESS")]
+    pub freeze_finished_progress: bool,
+    /// Whether lobbies with automatic start enabled also start for a single
+    /// ready player.
+    #[arg(long, env = "SOLO_AUTO_START")]
+    pub solo_auto_start: bool,
+    /// Whether to convert CRLF line endings of goal and player files to LF
+    /// before computing the progress. Disable it to require exact matches.
+    #[arg(long, env = "NORMALIZE_LINE_ENDINGS", default_value_t = NORMALIZE_LINE_ENDINGS, action = ArgAction::Set)]
+    pub normalize_line_endings: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            max_lobbies: MAX_LOBBIES,
+            empty_lobby_lifetime: EMPTY_LOBBY_LIFETIME.as_secs(),
+            lobby_finish_time: LOBBY_FINISH_TIME.as_secs(),
+            max_lobby_play_time: MAX_LOBBY_PLAY_TIME.as_secs(),
+            reduced_lobby_play_time: REDUCED_LOBBY_PLAY_TIME.as_secs(),
+            freeze_finished_progress: false,
+            solo_auto_start: false,
+            normalize_line_endings: NORMALIZE_LINE_ENDINGS,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn empty_lobby_lifetime(&self) -> Duration {
+        Duration::from_secs(self.empty_lobby_lifetime)
+    }
+
+    pub fn lobby_finish_time(&self) -> Duration {
+        Duration::from_secs(self.lobby_finish_time)
+    }
+
+    pub fn max_lobby_play_time(&self) -> Duration {
+        Duration::from_secs(self.max_lobby_play_time)
+    }
+
+    pub fn reduced_lobby_play_time(&self) -> Duration {
+        Duration::from_secs(self.reduced_lobby_play_time)
+    }
+}
+
+/// # Log format
+///
+/// Determines how log events are written to stdout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line including the fields of the current spans,
+    /// e.g., lobby and player ID. Meant for log aggregation.
+    Json,
+}
+
+/// # Parse max lobbies
+///
+/// Parses the maximum number of lobbies, which has to allow at least one
+/// lobby.
+fn parse_max_lobbies(value: &str) -> Result<usize, String> {
+    let max_lobbies = value
+        .parse::<usize>()
+        .map_err(|e| format!("Invalid number of lobbies: {e}"))?;
+    if max_lobbies == 0 {
+        return Err("The backend has to allow at least one lobby.".to_string());
+    }
+    Ok(max_lobbies)
+}
+
+/// # Parse seconds
+///
+/// Parses a duration in seconds, which has to be positive.
+fn parse_seconds(value: &str) -> Result<u64, String> {
+    let seconds = value
+        .parse::<u64>()
+        .map_err(|e| format!("Invalid number of seconds: {e}"))?;
+    if seconds == 0 {
+        return Err("The duration has to be at least one second.".to_string());
+    }
+    Ok(seconds)
+}
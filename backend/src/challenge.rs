@@ -0,0 +1,135 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use common::{ChallengeFiles, ContentType, CursorPosition};
+
+/// The file describing a challenge inside its folder.
+const CHALLENGE_FILE: &str = "challenge.json";
+
+/// # Challenge description
+///
+/// The content of a `challenge.json` file. Start and goal file are given
+/// relative to the challenge folder.
+#[derive(Debug, Deserialize)]
+struct ChallengeDescription {
+    name: String,
+    language: String,
+    /// Defaults to the extension of the start file.
+    extension: Option<String>,
+    description: String,
+    start_file: String,
+    goal_file: String,
+    instructions: Option<String>,
+    /// The position the editor opens the start file at.
+    cursor: Option<CursorPosition>,
+}
+
+/// # Challenge catalog
+///
+/// All challenges new lobbies can be created with, identified by the name of
+/// their folder.
+#[derive(Clone, Debug, Default)]
+pub struct ChallengeCatalog {
+    pub challenges: BTreeMap<String, ChallengeFiles>,
+}
+
+impl ChallengeCatalog {
+    /// # Load challenge catalog
+    ///
+    /// Loads every folder inside `path` containing a `challenge.json` file.
+    /// Folders that can not be loaded are skipped.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut challenges = BTreeMap::new();
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let Some(id) = path.file_name().and_then(|name| name.to_str()) else {
+                warn!("Skipping challenge with invalid name {}.", path.display());
+                continue;
+            };
+            match load_challenge(&path) {
+                Ok(challenge) => {
+                    info!("Loaded challenge {} ({}).", id, challenge.name);
+                    challenges.insert(id.to_string(), challenge);
+                }
+                Err(e) => warn!("Skipping challenge {}: {e}", path.display()),
+            }
+        }
+        Ok(Self { challenges })
+    }
+
+    /// # Get challenge
+    ///
+    /// Returns the challenge with the given ID. Without an ID, the first
+    /// challenge of the catalog is returned, or the embedded default challenge
+    /// if the catalog is empty.
+    pub fn get(&self, id: Option<&str>) -> Result<ChallengeFiles> {
+        match id {
+            Some(id) => self
+                .challenges
+                .get(id)
+                .cloned()
+                .ok_or_else(|| anyhow!("Challenge {} does not exist.", id)),
+            None => Ok(self
+                .challenges
+                .values()
+                .next()
+                .cloned()
+                .unwrap_or_else(default_challenge)),
+        }
+    }
+}
+
+fn load_challenge(path: &Path) -> Result<ChallengeFiles> {
+    let description = fs::read_to_string(path.join(CHALLENGE_FILE))?;
+    let description: ChallengeDescription = serde_json::from_str(&description)?;
+    let extension = description.extension.unwrap_or_else(|| {
+        Path::new(&description.start_file)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+    let start_file = fs::read(path.join(description.start_file))?;
+    let goal_file = fs::read(path.join(description.goal_file))?;
+    Ok(ChallengeFiles {
+        name: description.name,
+        language: description.language,
+        extension,
+        description: description.description,
+        content_type: ContentType::detect(&[&start_file, &goal_file]),
+        start_file,
+        goal_file,
+        instructions: description.instructions,
+        cursor: description.cursor,
+    })
+}
+
+/// # Default challenge
+///
+/// The challenge embedded into the backend. Used whenever no challenge
+/// catalog is configured.
+pub fn default_challenge() -> ChallengeFiles {
+    let start_file =
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/start.rs")).to_vec();
+    let goal_file = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/goal.rs")).to_vec();
+
+    ChallengeFiles {
+        name: "Hello, world!".to_string(),
+        language: "Rust".to_string(),
+        extension: "rs".to_string(),
+        description: "Greet the world six times instead of the dude.".to_string(),
+        content_type: ContentType::Text,
+        start_file,
+        goal_file,
+        instructions: Some(
+            "Rename the function to `main`, greet the world and copy the function six times."
+                .to_string(),
+        ),
+        cursor: Some(CursorPosition { line: 1, column: 4 }),
+    }
+}
@@ -1,12 +1,45 @@
 use std::time::Duration;
 
-/// Empty lobbies survive 30 seconds before being removed.
+/// Empty lobbies survive 30 seconds before being removed by default.
 pub static EMPTY_LOBBY_LIFETIME: Duration = Duration::from_secs(30);
-/// Lobbies start ten seconds after a start request.
+/// Lobbies start ten seconds after a start request, unless they were created
+/// with a different countdown.
 pub static LOBBY_START_TIMER: Duration = Duration::from_secs(10);
-/// Lobbies are up to two minutes in progress.
+/// At most 100 lobbies exist at the same time, unless configured otherwise.
+pub static MAX_LOBBIES: usize = 100;
+/// Request bodies creating a lobby are at most four kilobytes.
+pub static MAX_CREATE_LOBBY_BODY_SIZE: u64 = 4 * 1024;
+/// Lobbies are in progress for the configured game duration, which defaults
+/// to two minutes.
 pub static MAX_LOBBY_PLAY_TIME: Duration = Duration::from_secs(60 * 2);
-/// After one player finished, the lobby play time is reduced.
+/// After one player finished, the lobby play time is reduced to ten seconds
+/// by default.
 pub static REDUCED_LOBBY_PLAY_TIME: Duration = Duration::from_secs(10);
-/// Lobbies are ten seconds in the finish state.
+/// Lobbies are ten seconds in the finish state by default.
 pub static LOBBY_FINISH_TIME: Duration = Duration::from_secs(10);
+/// Players who lost their connection keep their seat for 15 seconds.
+pub static RECONNECT_GRACE_PERIOD: Duration = Duration::from_secs(15);
+/// Lobbies normalize line endings (CRLF to LF) of goal and player files before
+/// computing the progress by default.
+pub static NORMALIZE_LINE_ENDINGS: bool = true;
+/// On shutdown, the backend waits one second for close messages to reach all
+/// connections.
+pub static SHUTDOWN_DRAIN_TIME: Duration = Duration::from_secs(1);
+/// Players send at most five chat messages within three seconds.
+pub static CHAT_RATE_LIMIT_MESSAGES: usize = 5;
+pub static CHAT_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(3);
+/// Players who send nothing for five minutes while their lobby waits for
+/// players lose their seat.
+pub static IDLE_KICK_TIMEOUT: Duration = Duration::from_secs(60 * 5);
+/// Lobbies are checked for idle players every 30 seconds.
+pub static IDLE_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+/// Lobbies keep the last 50 chat messages for players joining later.
+pub static CHAT_HISTORY_LENGTH: usize = 50;
+/// Progress payloads are at most four times the size of the goal file, but
+/// always allowed up to 16 kilobytes and never beyond 256 kilobytes.
+pub static PROGRESS_SIZE_FACTOR: usize = 4;
+pub static MIN_PROGRESS_SIZE_LIMIT: usize = 16 * 1024;
+pub static MAX_PROGRESS_SIZE_LIMIT: usize = 256 * 1024;
+/// The activity of a player is announced to his lobby at most once per
+/// second.
+pub static PLAYER_ACTIVE_INTERVAL: Duration = Duration::from_secs(1);
@@ -0,0 +1,133 @@
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// # Database
+///
+/// Persists the results of finished rounds in a SQLite database.
+#[derive(Debug)]
+pub struct Database {
+    connection: Connection,
+}
+
+/// The result of a single player in a finished round.
+#[derive(Debug)]
+pub struct PlayerResult {
+    pub player_id: Uuid,
+    pub player_name: String,
+    /// The position of the player in the round, starting at one.
+    pub rank: usize,
+    pub progress: f64,
+    /// The time the player needed to reach the goal file. Is `None` if the
+    /// player did not finish.
+    pub finish_time_ms: Option<i64>,
+}
+
+/// The results of a finished round inside a lobby.
+#[derive(Debug)]
+pub struct RoundResult {
+    pub lobby_id: Uuid,
+    pub challenge: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub players: Vec<PlayerResult>,
+}
+
+/// The best finish time of a player for a challenge.
+#[derive(Debug, Serialize)]
+pub struct LeaderboardEntry {
+    pub challenge: String,
+    pub player_name: String,
+    pub best_time_ms: i64,
+}
+
+impl Database {
+    /// # Open database
+    ///
+    /// Opens the SQLite database at the given path and creates the tables if
+    /// they do not exist yet.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS rounds (
+                id INTEGER PRIMARY KEY,
+                lobby_id TEXT NOT NULL,
+                challenge TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                finished_at TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS results (
+                round_id INTEGER NOT NULL REFERENCES rounds(id),
+                player_id TEXT NOT NULL,
+                player_name TEXT NOT NULL,
+                rank INTEGER NOT NULL,
+                progress REAL NOT NULL,
+                finish_time_ms INTEGER
+            );",
+        )?;
+        Ok(Self { connection })
+    }
+
+    /// # Insert round
+    ///
+    /// Stores the results of a finished round.
+    pub fn insert_round(&mut self, round: &RoundResult) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO rounds (lobby_id, challenge, started_at, finished_at)
+            VALUES (?1, ?2, ?3, ?4)",
+            params![
+                round.lobby_id.to_string(),
+                round.challenge,
+                round.started_at.to_rfc3339(),
+                round.finished_at.to_rfc3339(),
+            ],
+        )?;
+        let round_id = transaction.last_insert_rowid();
+        for player in round.players.iter() {
+            transaction.execute(
+                "INSERT INTO results
+                (round_id, player_id, player_name, rank, progress, finish_time_ms)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    round_id,
+                    player.player_id.to_string(),
+                    player.player_name,
+                    player.rank,
+                    player.progress,
+                    player.finish_time_ms,
+                ],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// # Leaderboard
+    ///
+    /// Returns the best finish time of every player per challenge, fastest
+    /// first.
+    pub fn leaderboard(&self) -> Result<Vec<LeaderboardEntry>> {
+        let mut statement = self.connection.prepare(
+            "SELECT rounds.challenge, results.player_name, MIN(results.finish_time_ms) AS best
+            FROM results JOIN rounds ON rounds.id = results.round_id
+            WHERE results.finish_time_ms IS NOT NULL
+            GROUP BY rounds.challenge, results.player_name
+            ORDER BY rounds.challenge, best",
+        )?;
+        let entries = statement
+            .query_map([], |row| {
+                Ok(LeaderboardEntry {
+                    challenge: row.get(0)?,
+                    player_name: row.get(1)?,
+                    best_time_ms: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+}
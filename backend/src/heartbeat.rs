@@ -0,0 +1,117 @@
+use anyhow::Result;
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use tokio::{sync::mpsc::UnboundedReceiver, time::timeout};
+use tracing::{error, warn};
+use warp::filters::ws::{Message, WebSocket};
+
+use common::{
+    constants::{HEARTBEAT_INTERVAL, HEARTBEAT_TIMEOUT},
+    BackendMessage,
+};
+
+/// # Forward backend messages
+///
+/// Forwards messages received through the application channel to the WS
+/// connection and pings the connection at the heartbeat interval. A
+/// `BackendMessage::CloseConnection` is sent as close frame. Stops once the
+/// connection was closed, the channel closes or a ping can not be sent
+/// anymore.
+pub async fn forward_backend_messages(
+    mut to_ws: SplitSink<WebSocket, Message>,
+    mut rx: UnboundedReceiver<BackendMessage>,
+) {
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    loop {
+        tokio::select! {
+            msg = rx.recv() => {
+                let Some(msg) = msg else {
+                    break;
+                };
+                if matches!(msg, BackendMessage::CloseConnection) {
+                    if let Err(e) = to_ws.send(Message::close()).await {
+                        warn!("Error closing websocket: {e}");
+                    }
+                    break;
+                }
+                if let Err(e) = send_backend_message(&mut to_ws, &msg).await {
+                    error!("Error sending message via websocket: {e}");
+                }
+            }
+            _ = heartbeat.tick() => {
+                if let Err(e) = to_ws.send(Message::ping(vec![])).await {
+                    warn!("Stop pinging connection: {e}");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn send_backend_message(
+    to_ws: &mut SplitSink<WebSocket, Message>,
+    msg: &BackendMessage,
+) -> Result<()> {
+    let text = serde_json::to_string(msg)?;
+    to_ws.send(Message::text(text)).await?;
+    Ok(())
+}
+
+/// # Next message
+///
+/// Waits for the next message of the WS connection, skipping heartbeat
+/// frames. Returns `None` once the connection closed or stayed silent for
+/// longer than the heartbeat allows.
+pub async fn next_message(from_ws: &mut SplitStream<WebSocket>) -> Option<Message> {
+    loop {
+        let msg = match timeout(HEARTBEAT_INTERVAL + HEARTBEAT_TIMEOUT, from_ws.next()).await {
+            Ok(Some(Ok(msg))) => msg,
+            Ok(_) => return None,
+            Err(_) => {
+                warn!("Connection missed its heartbeat.");
+                return None;
+            }
+        };
+        if msg.is_ping() || msg.is_pong() {
+            continue;
+        }
+        return Some(msg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::unbounded_channel;
+    use warp::{filters::ws::Ws, Filter};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn close_connection_closes_websocket() {
+        let route = warp::ws().map(|ws: Ws| {
+            ws.on_upgrade(|ws| async move {
+                let (to_ws, _from_ws) = ws.split();
+                let (tx, rx) = unbounded_channel();
+                let _ = tx.send(BackendMessage::SendMessage("Bye!".to_string()));
+                let _ = tx.send(BackendMessage::CloseConnection);
+                // Keep the channel open to make sure the close message
+                // alone ends the connection.
+                forward_backend_messages(to_ws, rx).await;
+                drop(tx);
+            })
+        });
+        let mut client = warp::test::ws()
+            .handshake(route)
+            .await
+            .expect("handshake should succeed");
+
+        let msg = client.recv().await.expect("message should arrive");
+        assert_eq!(msg.to_str(), Ok(r#"{"SendMessage":"Bye!"}"#));
+        client
+            .recv_closed()
+            .await
+            .expect("connection should be closed");
+    }
+}
@@ -0,0 +1,64 @@
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::PathBuf};
+
+use anyhow::Result;
+use tracing::error;
+
+use common::Standing;
+
+/// # Leaderboard
+///
+/// Keeps the wins and best completion times of all players by name. If a
+/// path is configured, the standings are loaded from and saved to a JSON file,
+/// so they survive restarts of the backend.
+#[derive(Debug, Default)]
+pub struct Leaderboard {
+    pub standings: BTreeMap<String, Standing>,
+    path: Option<PathBuf>,
+}
+
+impl Leaderboard {
+    /// # Load leaderboard
+    ///
+    /// Reads the standings from the given JSON file. A missing file results in
+    /// an empty leaderboard, which is saved to the file on the first update.
+    pub fn load(path: Option<PathBuf>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let standings = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            standings,
+            path: Some(path),
+        })
+    }
+
+    /// # Record finish
+    ///
+    /// Updates the standing of a player who reached the goal file after
+    /// `time_ms` milliseconds. Only the first player to finish a game wins it.
+    pub fn record_finish(&mut self, player_name: &str, time_ms: i64, won: bool) {
+        let standing = self.standings.entry(player_name.to_string()).or_default();
+        if won {
+            standing.wins += 1;
+        }
+        if standing.best_time_ms.is_none_or(|best| time_ms < best) {
+            standing.best_time_ms = Some(time_ms);
+        }
+
+        if let Err(e) = self.save() {
+            error!("Error saving leaderboard: {e}");
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        fs::write(path, serde_json::to_string_pretty(&self.standings)?)?;
+        Ok(())
+    }
+}
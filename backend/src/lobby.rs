@@ -1,16 +1,52 @@
-use std::collections::BTreeMap;
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{BTreeMap, VecDeque},
+    sync::{
+        atomic::{self, AtomicU64},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
 use fake::{faker::company::en::CompanyName, Fake};
+use strsim::{generic_levenshtein, normalized_levenshtein};
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use common::{
-    constants::MAX_LOBBY_SIZE, BackendMessage, ChallengeFiles, LobbyInformation, LobbyListItem,
-    LobbyStatus,
+    constants::MAX_LOBBY_SIZE, BackendMessage, ChallengeFiles, GameEvent, LobbyInformation,
+    LobbyListItem, LobbyStatus,
 };
 
-use crate::{app::message::AppMessage, constants::EMPTY_LOBBY_LIFETIME, player::Player};
+use crate::{
+    app::message::AppMessage,
+    challenge::default_challenge,
+    constants::{
+        CHAT_HISTORY_LENGTH, EMPTY_LOBBY_LIFETIME, IDLE_KICK_TIMEOUT, LOBBY_START_TIMER,
+        MAX_PROGRESS_SIZE_LIMIT, MIN_PROGRESS_SIZE_LIMIT, NORMALIZE_LINE_ENDINGS,
+        PROGRESS_SIZE_FACTOR, RECONNECT_GRACE_PERIOD,
+    },
+    database::{PlayerResult, RoundResult},
+    player::Player,
+};
+
+/// # Scoring mode
+///
+/// Determines how the similarity between a player's file and the goal file is
+/// turned into progress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ScoringMode {
+    /// Normalized levenshtein distance over the whole file.
+    #[default]
+    Levenshtein,
+    /// Longest common subsequence over lines. Edits only affect the score of
+    /// the lines they touch and indentation-only edits count partially.
+    Lines,
+}
 
 #[derive(Clone, Debug)]
 pub struct Lobby {
@@ -22,29 +58,72 @@ pub struct Lobby {
     /// player is assigned.
     pub owner: Option<Uuid>,
     pub players: BTreeMap<Uuid, Player>,
+    /// The number of players the lobby holds.
+    pub max_players: usize,
+    /// The time between a start request and the start of the game.
+    pub start_timer: Duration,
+    /// All clients watching the lobby. Spectators receive every broadcast but
+    /// never take part in a game.
+    pub spectators: BTreeMap<Uuid, UnboundedSender<BackendMessage>>,
     pub challenge_files: ChallengeFiles,
     pub status: LobbyStatus,
+    /// Holds the time an empty lobby is removed. This is only set while the
+    /// lobby has no players.
+    pub removal_time: Option<DateTime<Utc>>,
+    /// How long the lobby survives without players.
+    pub empty_lobby_lifetime: Duration,
+    /// Whether to ignore differences in line endings when computing the
+    /// progress of players.
+    pub normalize_line_endings: bool,
+    /// How the progress of players is computed.
+    pub scoring_mode: ScoringMode,
+    /// Identifies the currently scheduled finish timer. Increasing it cancels
+    /// all pending finish timers.
+    pub finish_timer_generation: u64,
+    /// Identifies the currently scheduled start timer. Increasing it cancels
+    /// all pending start timers.
+    pub start_timer_generation: u64,
+    /// Whether the game starts on its own once all active players are ready.
+    /// Toggled by the owner.
+    pub auto_start: bool,
+    /// Whether the running start countdown was triggered by the automatic
+    /// start. Such a countdown is cancelled once a player is no longer ready.
+    pub auto_started: bool,
+    /// The time the current round started. This is only set while a round is
+    /// running or finished.
+    pub started_at: Option<DateTime<Utc>>,
+    /// The most recent public chat messages, including join and leave
+    /// notices. Joining players receive them as chat history.
+    pub chat_history: VecDeque<String>,
+    /// Counts the broadcast messages of all lobbies for the metrics.
+    pub messages_broadcast: Arc<AtomicU64>,
 }
 
 impl Default for Lobby {
     fn default() -> Self {
-        let start_file =
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/start.rs")).to_vec();
-        let goal_file =
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/goal.rs")).to_vec();
-
-        let challenge_files = ChallengeFiles {
-            start_file,
-            goal_file,
-        };
+        let challenge_files = default_challenge();
         let id = Uuid::new_v4();
         Self {
             id,
             name: CompanyName().fake(),
             owner: None,
             players: BTreeMap::new(),
+            max_players: MAX_LOBBY_SIZE,
+            start_timer: LOBBY_START_TIMER,
+            spectators: BTreeMap::new(),
             challenge_files,
             status: LobbyStatus::WaitingForPlayers,
+            removal_time: None,
+            empty_lobby_lifetime: EMPTY_LOBBY_LIFETIME,
+            normalize_line_endings: NORMALIZE_LINE_ENDINGS,
+            scoring_mode: ScoringMode::default(),
+            finish_timer_generation: 0,
+            start_timer_generation: 0,
+            auto_start: false,
+            auto_started: false,
+            started_at: None,
+            chat_history: VecDeque::new(),
+            messages_broadcast: Arc::default(),
         }
     }
 }
@@ -52,7 +131,7 @@ impl Default for Lobby {
 impl Lobby {
     /// # Broadcast message
     ///
-    /// Sends a message to every player inside the lobby.
+    /// Sends a message to every player and spectator inside the lobby.
     pub fn broadcast(&self, msg: BackendMessage) {
         for Player {
             id: _,
@@ -60,17 +139,38 @@ impl Lobby {
             tx,
             progress: _,
             waiting: _,
+            token: _,
+            removal_time: _,
+            finished_at: _,
+            position: _,
+            edits: _,
+            edits_per_minute: _,
+            ready: _,
+            chat_sent_at: _,
+            last_active_at: _,
+            activity_announced_at: _,
+            scoring: _,
+            pending_progress: _,
         } in self.players.values()
         {
             let _ = tx.send(msg.clone());
         }
+        for tx in self.spectators.values() {
+            let _ = tx.send(msg.clone());
+        }
+        self.messages_broadcast
+            .fetch_add(1, atomic::Ordering::Relaxed);
     }
 
     pub fn to_list_item(&self) -> LobbyListItem {
         LobbyListItem {
             name: self.name.clone(),
             player_count: self.players.len(),
+            max_players: self.max_players,
+            challenge: self.challenge_files.name.clone(),
+            language: self.challenge_files.language.clone(),
             status: self.status.clone(),
+            removal_time: self.removal_time,
         }
     }
 
@@ -86,17 +186,59 @@ impl Lobby {
             owner: self.owner,
             players,
             challenge_files: self.challenge_files.clone(),
+            auto_start: self.auto_start,
         }
     }
 
+    /// # To round result
+    ///
+    /// Collects the results of all active players of the current round. Players
+    /// who reached the goal file are ranked by their finish time, all others
+    /// by their progress. Returns `None` if the round was never started.
+    pub fn to_round_result(&self, finished_at: DateTime<Utc>) -> Option<RoundResult> {
+        let started_at = self.started_at?;
+        let mut players = self
+            .players
+            .values()
+            .filter(|player| !player.waiting)
+            .collect::<Vec<_>>();
+        players.sort_by(|a, b| match (a.finished_at, b.finished_at) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => b.progress.total_cmp(&a.progress),
+        });
+        let players = players
+            .into_iter()
+            .enumerate()
+            .map(|(index, player)| PlayerResult {
+                player_id: player.id,
+                player_name: player.name.clone(),
+                rank: index + 1,
+                progress: player.progress,
+                finish_time_ms: player
+                    .finished_at
+                    .map(|time| (time - started_at).num_milliseconds()),
+            })
+            .collect();
+        Some(RoundResult {
+            lobby_id: self.id,
+            challenge: self.challenge_files.name.clone(),
+            started_at,
+            finished_at,
+            players,
+        })
+    }
+
     /// # Add player
     ///
     /// Adds a player to the lobby. If the lobby is full, tell the player about
     /// that and prevent the addition. If the player successfully joined the
-    /// lobby tell him the lobby name.
+    /// lobby tell him the lobby name. Players joining after a start was
+    /// requested, e.g., during the countdown, wait for the next game.
     pub fn add_player(&mut self, mut player: Player, app_tx: &UnboundedSender<AppMessage>) {
         // Return early if the lobby is full.
-        if self.players.len() >= MAX_LOBBY_SIZE {
+        if self.players.len() >= self.max_players {
             warn!(
                 "Tried to add player {} to full lobby {}.",
                 player.name, self.name
@@ -112,9 +254,20 @@ impl Lobby {
         if self.status != LobbyStatus::WaitingForPlayers {
             player.waiting = true;
         }
+        // Catch the player up on the conversation before he joined.
+        if !self.chat_history.is_empty() {
+            let history = self.chat_history.iter().cloned().collect();
+            let _ = player.tx.send(BackendMessage::ChatHistory(history));
+        }
+        if player.waiting {
+            let _ = player.tx.send(BackendMessage::SendMessage(
+                "The game already started. You will take part in the next one.".to_string(),
+            ));
+        }
         // Add the player to the actual player room.
         self.players.insert(player.id, player.clone());
         info!("Added player {} to lobby {}.", player.name, self.name);
+        self.record_chat_message(format!("{} joined!", player.name));
 
         // Tell connected players about this new player.
         let message = BackendMessage::AddPlayer(player.to_common_player());
@@ -127,6 +280,21 @@ impl Lobby {
         // Tell everyone about the update in connections.
         let _ = app_tx.send(AppMessage::SendConnectionCounts);
 
+        let _ = app_tx.send(AppMessage::PublishEvent {
+            event: GameEvent::PlayerJoined {
+                lobby_id: self.id,
+                player_id: player.id,
+                name: player.name.clone(),
+                waiting: player.waiting,
+            },
+        });
+
+        // The lobby is not empty anymore, so cancel its pending removal and
+        // tell the clients about it.
+        if self.removal_time.take().is_some() {
+            let _ = app_tx.send(AppMessage::SendLobbyStatusUpdate { lobby_id: self.id });
+        }
+
         // If the new player is the only player in the lobby, assign the owner
         // role.
         if self.players.len() == 1 {
@@ -139,9 +307,97 @@ impl Lobby {
         }
 
         // Tell the player about his own ID.
+        let _ = player.tx.send(BackendMessage::ProvidePlayerId {
+            id: player.id,
+            token: player.token,
+        });
+    }
+
+    /// # Disconnect player
+    ///
+    /// Keeps a player who lost his connection inside the lobby for the grace
+    /// period, so he is able to reconnect. Afterwards, the app removes him.
+    pub fn disconnect_player(&mut self, player_id: Uuid, app_tx: &UnboundedSender<AppMessage>) {
+        let Some(player) = self.players.get_mut(&player_id) else {
+            return;
+        };
+        info!(
+            "Player {} lost the connection to lobby {}.",
+            player.name, self.name
+        );
+        player.removal_time = Some(Utc::now() + RECONNECT_GRACE_PERIOD);
+
+        let app_tx = app_tx.clone();
+        let lobby_id = self.id;
+        tokio::spawn(async move {
+            tokio::time::sleep(RECONNECT_GRACE_PERIOD).await;
+            let _ = app_tx.send(AppMessage::RemoveDisconnectedPlayer {
+                lobby_id,
+                player_id,
+            });
+        });
+    }
+
+    /// # Reconnect player
+    ///
+    /// Restores the seat of a disconnected player identified by his token and
+    /// replaces his communication channel. Tells the player about the current
+    /// state of the lobby, which includes its owner. Returns `None` if there is
+    /// no such player.
+    pub fn reconnect_player(
+        &mut self,
+        token: Uuid,
+        player_tx: UnboundedSender<BackendMessage>,
+    ) -> Option<Player> {
+        let information = self.to_information();
+        let player = self
+            .players
+            .values_mut()
+            .find(|player| player.token.eq(&token) && player.removal_time.is_some())?;
+        player.removal_time = None;
+        player.tx = player_tx;
+        player.last_active_at = Instant::now();
+        info!("Player {} reconnected to lobby {}.", player.name, self.name);
+
         let _ = player
             .tx
-            .send(BackendMessage::ProvidePlayerId { id: player.id });
+            .send(BackendMessage::SendLobbyInformation(information));
+        let _ = player.tx.send(BackendMessage::ProvidePlayerId {
+            id: player.id,
+            token: player.token,
+        });
+        Some(player.clone())
+    }
+
+    /// # Kick idle players
+    ///
+    /// Removes connected players who stayed silent for longer than
+    /// `IDLE_KICK_TIMEOUT` and closes their connection. Only applies while the
+    /// lobby waits for players, so nobody is kicked mid-game.
+    pub fn kick_idle_players(&mut self, app_tx: &UnboundedSender<AppMessage>) {
+        if self.status != LobbyStatus::WaitingForPlayers {
+            return;
+        }
+        let idle_players: Vec<Player> = self
+            .players
+            .values()
+            .filter(|player| {
+                player.removal_time.is_none()
+                    && player.last_active_at.elapsed() >= IDLE_KICK_TIMEOUT
+            })
+            .cloned()
+            .collect();
+        for player in idle_players {
+            info!(
+                "Kicked idle player {} from lobby {}.",
+                player.name, self.name
+            );
+            let _ = player.tx.send(BackendMessage::SendMessage(
+                "You were removed from the lobby for inactivity.".to_string(),
+            ));
+            let _ = player.tx.send(BackendMessage::CloseConnection);
+            self.remove_player(player, app_tx);
+        }
     }
 
     /// # Remove player
@@ -152,6 +408,7 @@ impl Lobby {
             return;
         };
         info!("Removed player {} from lobby {}.", player.name, self.name);
+        self.record_chat_message(format!("{} left!", player.name));
         // Tell connected players about the removal of this player.
         let message = BackendMessage::RemovePlayer(player.id);
         self.broadcast(message);
@@ -172,6 +429,13 @@ impl Lobby {
         // Tell everyone about the update in connections.
         let _ = app_tx.send(AppMessage::SendConnectionCounts);
 
+        let _ = app_tx.send(AppMessage::PublishEvent {
+            event: GameEvent::PlayerLeft {
+                lobby_id: self.id,
+                player_id: player.id,
+            },
+        });
+
         // Now, if the lobby is empty, tell the app to remove this lobby.
         if self.players.is_empty() {
             let app_tx = app_tx.clone();
@@ -179,26 +443,202 @@ impl Lobby {
 
             // Remove the owner, as there are no players in the lobby.
             self.owner = None;
-            // Also, reset the status and tell the clients about it.
-            self.status = LobbyStatus::WaitingForPlayers;
+            // Also, reset the status, schedule the removal and tell the
+            // clients about it.
+            if self.status != LobbyStatus::WaitingForPlayers {
+                self.status = LobbyStatus::WaitingForPlayers;
+                let _ = app_tx.send(AppMessage::PublishEvent {
+                    event: GameEvent::StatusChanged {
+                        lobby_id: self.id,
+                        status: self.status.clone(),
+                    },
+                });
+            }
+            self.removal_time = Some(Utc::now() + self.empty_lobby_lifetime);
             let _ = app_tx.send(AppMessage::SendLobbyStatusUpdate { lobby_id: self.id });
 
-            // Tell the app to remove the lobby after 30 seconds.
+            // Tell the app to remove the lobby once its lifetime is over.
+            let lifetime = self.empty_lobby_lifetime;
             tokio::spawn(async move {
-                tokio::time::sleep(EMPTY_LOBBY_LIFETIME).await;
+                tokio::time::sleep(lifetime).await;
                 let _ = app_tx.send(AppMessage::RemoveLobby { lobby_id });
             });
         }
     }
 
+    /// # Add spectator
+    ///
+    /// Adds a spectator to the lobby. Spectators are not limited by the lobby
+    /// size and do not prevent the removal of an empty lobby.
+    pub fn add_spectator(
+        &mut self,
+        spectator_id: Uuid,
+        spectator_tx: UnboundedSender<BackendMessage>,
+        app_tx: &UnboundedSender<AppMessage>,
+    ) {
+        self.spectators.insert(spectator_id, spectator_tx);
+        info!(
+            "Added spectator {} to lobby {}. Spectator count is {}.",
+            spectator_id,
+            self.name,
+            self.spectators.len()
+        );
+        let _ = app_tx.send(AppMessage::SendConnectionCounts);
+    }
+
+    /// # Remove spectator
+    ///
+    /// Removes a spectator from the lobby if he exists.
+    pub fn remove_spectator(&mut self, spectator_id: Uuid, app_tx: &UnboundedSender<AppMessage>) {
+        if self.spectators.remove(&spectator_id).is_none() {
+            return;
+        }
+        info!(
+            "Removed spectator {} from lobby {}. Spectator count is {}.",
+            spectator_id,
+            self.name,
+            self.spectators.len()
+        );
+        let _ = app_tx.send(AppMessage::SendConnectionCounts);
+    }
+
+    /// # Schedule finish
+    ///
+    /// Tells the app to finish the game after the given delay. Cancels all
+    /// previously scheduled finish timers.
+    pub fn schedule_finish(&mut self, delay: Duration, app_tx: &UnboundedSender<AppMessage>) {
+        self.cancel_finish();
+        let app_tx = app_tx.clone();
+        let lobby_id = self.id;
+        let generation = self.finish_timer_generation;
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _ = app_tx.send(AppMessage::Finish {
+                lobby_id,
+                generation,
+            });
+        });
+    }
+
+    /// # Progress size limit
+    ///
+    /// The largest progress payload the lobby scores. Depends on the size of
+    /// the goal file, as computing the progress gets expensive for large
+    /// payloads.
+    pub fn progress_size_limit(&self) -> usize {
+        self.challenge_files
+            .goal_file
+            .len()
+            .saturating_mul(PROGRESS_SIZE_FACTOR)
+            .clamp(MIN_PROGRESS_SIZE_LIMIT, MAX_PROGRESS_SIZE_LIMIT)
+    }
+
+    /// # Start countdown
+    ///
+    /// Lets the lobby count down to the start of the game. Tells clients and
+    /// players about the new status and schedules the start.
+    pub fn start_countdown(&mut self, app_tx: &UnboundedSender<AppMessage>) {
+        self.status = LobbyStatus::AboutToStart(Utc::now() + self.start_timer);
+        self.announce_status(app_tx);
+        self.schedule_start(app_tx);
+    }
+
+    /// # Cancel countdown
+    ///
+    /// Aborts the start countdown and lets the lobby wait for players again.
+    pub fn cancel_countdown(&mut self, app_tx: &UnboundedSender<AppMessage>) {
+        self.cancel_start();
+        self.auto_started = false;
+        self.status = LobbyStatus::WaitingForPlayers;
+        self.announce_status(app_tx);
+    }
+
+    /// # Announce status
+    ///
+    /// Tells clients, event subscribers and the players inside the lobby about
+    /// its current status.
+    fn announce_status(&self, app_tx: &UnboundedSender<AppMessage>) {
+        let _ = app_tx.send(AppMessage::SendLobbyStatusUpdate { lobby_id: self.id });
+        let _ = app_tx.send(AppMessage::PublishEvent {
+            event: GameEvent::StatusChanged {
+                lobby_id: self.id,
+                status: self.status.clone(),
+            },
+        });
+        self.broadcast(BackendMessage::StatusUpdate {
+            status: self.status.clone(),
+        });
+    }
+
+    /// # Is ready for auto start
+    ///
+    /// Whether the automatic start is on and all active players are ready.
+    /// Without `solo`, at least two active players are required.
+    pub fn is_ready_for_auto_start(&self, solo: bool) -> bool {
+        let active_players = self
+            .players
+            .values()
+            .filter(|player| !player.waiting)
+            .collect::<Vec<_>>();
+        let min_players = if solo { 1 } else { 2 };
+        self.auto_start
+            && self.status == LobbyStatus::WaitingForPlayers
+            && active_players.len() >= min_players
+            && active_players.iter().all(|player| player.ready)
+    }
+
+    /// # Schedule start
+    ///
+    /// Tells the app to start the game once the start timer of the lobby ran
+    /// out. Cancels all previously scheduled start timers.
+    pub fn schedule_start(&mut self, app_tx: &UnboundedSender<AppMessage>) {
+        self.cancel_start();
+        let app_tx = app_tx.clone();
+        let lobby_id = self.id;
+        let generation = self.start_timer_generation;
+        let start_timer = self.start_timer;
+        tokio::spawn(async move {
+            tokio::time::sleep(start_timer).await;
+            let _ = app_tx.send(AppMessage::Start {
+                lobby_id,
+                generation,
+            });
+        });
+    }
+
+    /// # Cancel start
+    ///
+    /// Cancels all scheduled start timers.
+    pub fn cancel_start(&mut self) {
+        self.start_timer_generation += 1;
+    }
+
+    /// # Cancel finish
+    ///
+    /// Cancels all scheduled finish timers.
+    pub fn cancel_finish(&mut self) {
+        self.finish_timer_generation += 1;
+    }
+
     /// # Send message
     ///
     /// Broadcasts a message from a player to all connnected players if the
-    /// player exists.
-    pub fn send_message(&self, player: Player, message: String) {
-        if let Some(player) = self.players.get(&player.id) {
-            let message = BackendMessage::SendMessage(format!("{}: {message}", player.name));
-            self.broadcast(message);
+    /// player exists and did not exceed the chat rate limit.
+    pub fn send_message(&mut self, player: Player, message: String) {
+        if let Some(player) = self.players.get_mut(&player.id) {
+            // Only tell the spamming player about dropped messages.
+            if !player.allow_chat_message() {
+                warn!(
+                    "Dropped chat message of player {} in lobby {}.",
+                    player.name, self.name
+                );
+                let _ = player.tx.send(BackendMessage::SendMessage(
+                    "You are sending messages too fast.".to_string(),
+                ));
+                return;
+            }
+            let message = format!("{}: {message}", player.name);
+            self.broadcast_chat_message(message);
         } else {
             error!(
                 "Player {} was not found in lobby {}.",
@@ -206,4 +646,226 @@ impl Lobby {
             );
         }
     }
+
+    /// # Broadcast chat message
+    ///
+    /// Sends a public chat message to everyone inside the lobby and keeps it
+    /// in the chat history.
+    pub fn broadcast_chat_message(&mut self, message: String) {
+        self.record_chat_message(message.clone());
+        self.broadcast(BackendMessage::SendMessage(message));
+    }
+
+    /// # Record chat message
+    ///
+    /// Appends a message to the chat history and drops the oldest messages
+    /// beyond `CHAT_HISTORY_LENGTH`.
+    fn record_chat_message(&mut self, message: String) {
+        self.chat_history.push_back(message);
+        while self.chat_history.len() > CHAT_HISTORY_LENGTH {
+            self.chat_history.pop_front();
+        }
+    }
+}
+
+/// # Compute progress
+///
+/// Computes the similarity between the goal file and the given state of a
+/// player's start file according to the scoring mode. The result lies within
+/// `[0.0, 1.0]` and is exactly `1.0` only if both files are equal. If
+/// `normalize_line_endings` is set, CRLF line endings of both files are
+/// converted to LF beforehand.
+///
+/// Files that are not valid UTF-8 are compared byte-wise instead of
+/// character-wise, so progress is computed for arbitrary files.
+pub fn compute_progress(
+    goal_file: &[u8],
+    player_file: &[u8],
+    normalize_line_endings: bool,
+    scoring_mode: ScoringMode,
+) -> f64 {
+    let (goal_file, player_file) = if normalize_line_endings {
+        (
+            Cow::Owned(convert_line_endings(goal_file)),
+            Cow::Owned(convert_line_endings(player_file)),
+        )
+    } else {
+        (Cow::Borrowed(goal_file), Cow::Borrowed(player_file))
+    };
+    match scoring_mode {
+        ScoringMode::Levenshtein => {
+            match (
+                std::str::from_utf8(&goal_file),
+                std::str::from_utf8(&player_file),
+            ) {
+                (Ok(goal_file), Ok(player_file)) => normalized_levenshtein(goal_file, player_file),
+                _ => byte_similarity(&goal_file, &player_file),
+            }
+        }
+        // Lines are split at the newline byte, which works for any encoding.
+        ScoringMode::Lines => line_similarity(&goal_file, &player_file),
+    }
+}
+
+/// Converts CRLF line endings to LF.
+fn convert_line_endings(file: &[u8]) -> Vec<u8> {
+    let mut converted = Vec::with_capacity(file.len());
+    for (i, byte) in file.iter().enumerate() {
+        if *byte == b'\r' && file.get(i + 1) == Some(&b'\n') {
+            continue;
+        }
+        converted.push(*byte);
+    }
+    converted
+}
+
+/// # Byte similarity
+///
+/// Computes the normalized levenshtein distance over bytes. Used for files
+/// that are not valid UTF-8.
+fn byte_similarity(a: &[u8], b: &[u8]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let distance = generic_levenshtein(&a.to_vec(), &b.to_vec());
+    1.0 - distance as f64 / a.len().max(b.len()) as f64
+}
+
+/// # Line similarity
+///
+/// Computes the longest common subsequence of lines of both files and
+/// normalizes it by the total line count. Equal lines count fully, lines that
+/// only differ in surrounding whitespace count by their byte similarity.
+/// Splitting keeps empty lines, so only equal files score `1.0`.
+fn line_similarity(a: &[u8], b: &[u8]) -> f64 {
+    let a = a.split(|byte| *byte == b'\n').collect::<Vec<_>>();
+    let b = b.split(|byte| *byte == b'\n').collect::<Vec<_>>();
+
+    // Keep only the previous row of the dynamic programming table.
+    let mut previous = vec![0.0; b.len() + 1];
+    let mut current = vec![0.0; b.len() + 1];
+    for line_a in a.iter() {
+        for (j, line_b) in b.iter().enumerate() {
+            let skipped = f64::max(previous[j + 1], current[j]);
+            current[j + 1] = if line_a == line_b {
+                previous[j] + 1.0
+            } else if line_a.trim_ascii() == line_b.trim_ascii() {
+                skipped.max(previous[j] + byte_similarity(line_a, line_b))
+            } else {
+                skipped
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    let common_lines = previous[b.len()];
+
+    2.0 * common_lines / (a.len() + b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_files_score_full_progress() {
+        let goal_file = b"fn main() {}\n";
+        for scoring_mode in [ScoringMode::Levenshtein, ScoringMode::Lines] {
+            assert_eq!(
+                compute_progress(goal_file, goal_file, true, scoring_mode),
+                1.0
+            );
+            assert_eq!(compute_progress(b"", b"", true, scoring_mode), 1.0);
+        }
+    }
+
+    #[test]
+    fn empty_file_scores_no_progress() {
+        assert_eq!(
+            compute_progress(b"goal", b"", true, ScoringMode::Levenshtein),
+            0.0
+        );
+    }
+
+    #[test]
+    fn partial_edits_score_partial_progress() {
+        let goal_file = b"abcdefghij";
+        let one_edit = compute_progress(goal_file, b"abcdefghiX", true, ScoringMode::Levenshtein);
+        let two_edits = compute_progress(goal_file, b"abcdefghXX", true, ScoringMode::Levenshtein);
+        assert!((one_edit - 0.9).abs() < f64::EPSILON);
+        assert!(two_edits < one_edit);
+    }
+
+    #[test]
+    fn invalid_utf8_is_scored_byte_wise() {
+        let goal_file = b"\xff\xfe\x00\x01";
+        let player_file = b"\xff\xfe\x00\x02";
+        let progress = compute_progress(goal_file, player_file, true, ScoringMode::Levenshtein);
+        assert_eq!(progress, 0.75);
+        assert_eq!(
+            compute_progress(goal_file, goal_file, true, ScoringMode::Levenshtein),
+            1.0
+        );
+        // A valid goal file does not prevent scoring an invalid player file.
+        let progress = compute_progress(b"ab", b"a\xff", true, ScoringMode::Levenshtein);
+        assert_eq!(progress, 0.5);
+    }
+
+    #[test]
+    fn crlf_progress_matches_lf_goal() {
+        let goal_file = b"fn main() {\n    println!(\"Hello\");\n}\n";
+        let player_file = b"fn main() {\r\n    println!(\"Hello\");\r\n}\r\n";
+        for scoring_mode in [ScoringMode::Levenshtein, ScoringMode::Lines] {
+            assert_eq!(
+                compute_progress(goal_file, player_file, true, scoring_mode),
+                1.0
+            );
+            assert!(compute_progress(goal_file, player_file, false, scoring_mode) < 1.0);
+        }
+    }
+
+    #[test]
+    fn line_scoring_counts_untouched_lines() {
+        let goal_file = b"a\nb\nc\nd";
+        // One of four lines changed.
+        let progress = compute_progress(goal_file, b"a\nb\nx\nd", true, ScoringMode::Lines);
+        assert_eq!(progress, 0.75);
+        // Reordered lines keep most of their score.
+        let progress = compute_progress(goal_file, b"b\na\nc\nd", true, ScoringMode::Lines);
+        assert_eq!(progress, 0.75);
+        // Nothing in common.
+        let progress = compute_progress(goal_file, b"w\nx\ny\nz", true, ScoringMode::Lines);
+        assert_eq!(progress, 0.0);
+    }
+
+    #[test]
+    fn line_scoring_counts_indentation_edits_partially() {
+        let goal_file = b"fn main() {\n    run();\n}";
+        let player_file = b"fn main() {\nrun();\n}";
+        let lines = compute_progress(goal_file, player_file, true, ScoringMode::Lines);
+        let replaced = compute_progress(
+            goal_file,
+            b"fn main() {\nstop();\n}",
+            true,
+            ScoringMode::Lines,
+        );
+        assert!(lines > replaced);
+        assert!(lines < 1.0);
+    }
+
+    #[test]
+    fn both_modes_stay_within_bounds() {
+        let pairs: [(&[u8], &[u8]); 4] = [
+            (b"", b""),
+            (b"let a = 1;", b""),
+            (b"", b"let a = 1;"),
+            (b"let a = 1;\nlet b = 2;", b"let b = 2;\nlet a = 1;\n"),
+        ];
+        for scoring_mode in [ScoringMode::Levenshtein, ScoringMode::Lines] {
+            for (goal_file, player_file) in pairs {
+                let progress = compute_progress(goal_file, player_file, true, scoring_mode);
+                assert!((0.0..=1.0).contains(&progress));
+                assert_eq!(progress == 1.0, goal_file == player_file);
+            }
+        }
+    }
 }
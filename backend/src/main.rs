@@ -1,29 +1,101 @@
+use clap::Parser;
 use routes::lobbies;
-use tokio::sync::mpsc::unbounded_channel;
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedSender},
+    oneshot,
+};
+use tracing::{error, info, warn};
 use warp::{reply, Filter};
 
 use crate::{
     app::{
-        message::{handle_app_message, AppMessage},
+        message::{handle_app_message, sweep_idle_players, AppMessage},
         App,
     },
-    routes::{clients, players},
+    args::{Args, LogFormat},
+    challenge::ChallengeCatalog,
+    constants::SHUTDOWN_DRAIN_TIME,
+    database::Database,
+    leaderboard::Leaderboard,
+    routes::{clients, players, spectators},
 };
 
 mod app;
+mod args;
+mod challenge;
 mod constants;
+mod database;
+mod heartbeat;
+mod leaderboard;
 mod lobby;
+mod metrics;
 mod player;
 mod routes;
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt::init();
+    let args = Args::parse();
+    match args.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::init(),
+        LogFormat::Json => tracing_subscriber::fmt().json().init(),
+    }
+
+    // Open the results database if requested.
+    let database = match args.db_path {
+        Some(path) => match Database::open(&path) {
+            Ok(database) => {
+                info!("Persisting round results to {}.", path.display());
+                Some(database)
+            }
+            Err(e) => {
+                error!("Unable to open database at {}: {e}", path.display());
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // Load the win leaderboard.
+    let leaderboard = match Leaderboard::load(args.leaderboard_path) {
+        Ok(leaderboard) => leaderboard,
+        Err(e) => {
+            error!("Unable to load leaderboard: {e}");
+            return;
+        }
+    };
+
+    // Load the challenge catalog if requested.
+    let catalog = match args.challenges_dir {
+        Some(path) => match ChallengeCatalog::load(&path) {
+            Ok(catalog) => {
+                info!(
+                    "Loaded {} challenges from {}.",
+                    catalog.challenges.len(),
+                    path.display()
+                );
+                catalog
+            }
+            Err(e) => {
+                error!("Unable to load challenges from {}: {e}", path.display());
+                return;
+            }
+        },
+        None => ChallengeCatalog::default(),
+    };
 
     // Setup app, communication channel and message handler.
     let (app_tx, app_rx) = unbounded_channel();
-    let app = App::new(app_tx.clone(), app_rx);
+    let app = App::new(
+        app_tx.clone(),
+        app_rx,
+        database,
+        leaderboard,
+        args.scoring_mode,
+        catalog,
+        args.server_config,
+    );
     tokio::spawn(handle_app_message(app));
+    tokio::spawn(sweep_idle_players(app_tx.clone()));
 
     let health = warp::path("health").map(reply);
 
@@ -31,8 +103,71 @@ async fn main() {
     let player_routes = players::routes(app_tx.clone());
     let client_routes = clients::routes(app_tx.clone());
     let lobby_routes = lobbies::routes(app_tx.clone());
+    let leaderboard_routes = routes::leaderboard::routes(app_tx.clone());
+    let metrics_routes = routes::metrics::routes(app_tx.clone());
+    let spectator_routes = spectators::routes(app_tx.clone());
 
     // Serve routes.
-    let routes = health.or(client_routes.or(player_routes.or(lobby_routes)));
-    warp::serve(routes).run(([0, 0, 0, 0], 3030)).await;
+    let routes = health
+        .or(client_routes)
+        .or(player_routes)
+        .or(spectator_routes)
+        .or(lobby_routes)
+        .or(leaderboard_routes)
+        .or(metrics_routes);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let (_, server) =
+        warp::serve(routes).bind_with_graceful_shutdown(([0, 0, 0, 0], 3030), async {
+            let _ = shutdown_rx.await;
+        });
+    let server = tokio::spawn(server);
+
+    shutdown(app_tx).await;
+    let _ = shutdown_tx.send(());
+    // Connections that ignore the close message are dropped with the runtime.
+    if tokio::time::timeout(SHUTDOWN_DRAIN_TIME, server)
+        .await
+        .is_err()
+    {
+        warn!("Some connections did not close in time.");
+    }
+}
+
+/// # Shutdown
+///
+/// Waits until the process receives Ctrl-C or SIGTERM. Afterwards, all
+/// connections are told to close, so clients can cleanly switch into their
+/// offline state.
+async fn shutdown(app_tx: UnboundedSender<AppMessage>) {
+    wait_for_signal().await;
+    info!("Shutting down.");
+
+    let (tx, rx) = oneshot::channel();
+    if app_tx.send(AppMessage::Shutdown { tx }).is_ok() && rx.await.is_ok() {
+        // Give the connections a moment to deliver the close messages.
+        tokio::time::sleep(SHUTDOWN_DRAIN_TIME).await;
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            error!("Unable to listen for SIGTERM: {e}");
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
 }
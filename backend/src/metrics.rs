@@ -0,0 +1,98 @@
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// # Metrics
+///
+/// Counters exposed via `GET /metrics`. Gauges, e.g., the number of lobbies,
+/// are read from the app state when taking a snapshot instead.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub lobbies_created: u64,
+    pub games_finished: u64,
+    /// Shared with every lobby, which counts its own broadcasts.
+    pub messages_broadcast: Arc<AtomicU64>,
+}
+
+/// # Metrics snapshot
+///
+/// The state of all metrics at one point in time.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsSnapshot {
+    pub lobbies: usize,
+    pub players: usize,
+    pub clients: usize,
+    pub spectators: usize,
+    pub lobbies_created: u64,
+    pub games_finished: u64,
+    pub messages_broadcast: u64,
+}
+
+impl Metrics {
+    pub fn messages_broadcast(&self) -> u64 {
+        self.messages_broadcast.load(Ordering::Relaxed)
+    }
+}
+
+impl MetricsSnapshot {
+    /// # To Prometheus
+    ///
+    /// Renders the snapshot in the Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let metrics = [
+            (
+                "keyglide_lobbies",
+                "gauge",
+                "Number of active lobbies.",
+                self.lobbies as u64,
+            ),
+            (
+                "keyglide_players",
+                "gauge",
+                "Number of players inside lobbies.",
+                self.players as u64,
+            ),
+            (
+                "keyglide_clients",
+                "gauge",
+                "Number of clients browsing the lobby list.",
+                self.clients as u64,
+            ),
+            (
+                "keyglide_spectators",
+                "gauge",
+                "Number of clients watching a lobby.",
+                self.spectators as u64,
+            ),
+            (
+                "keyglide_lobbies_created_total",
+                "counter",
+                "Number of lobbies created since startup.",
+                self.lobbies_created,
+            ),
+            (
+                "keyglide_games_finished_total",
+                "counter",
+                "Number of games finished since startup.",
+                self.games_finished,
+            ),
+            (
+                "keyglide_messages_broadcast_total",
+                "counter",
+                "Number of messages broadcast to lobbies since startup.",
+                self.messages_broadcast,
+            ),
+        ];
+        let mut text = String::new();
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(text, "# HELP {name} {help}");
+            let _ = writeln!(text, "# TYPE {name} {kind}");
+            let _ = writeln!(text, "{name} {value}");
+        }
+        text
+    }
+}
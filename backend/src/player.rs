@@ -1,8 +1,13 @@
+use std::{collections::VecDeque, time::Instant};
+
+use chrono::{DateTime, Utc};
 use fake::{faker::name::raw::Name, locales::EN, Fake};
 use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
 
-use common::BackendMessage;
+use common::{constants::MAX_PLAYER_NAME_LENGTH, BackendMessage};
+
+use crate::constants::{CHAT_RATE_LIMIT_MESSAGES, CHAT_RATE_LIMIT_WINDOW, PLAYER_ACTIVE_INTERVAL};
 
 #[derive(Clone, Debug)]
 pub struct Player {
@@ -11,17 +16,129 @@ pub struct Player {
     pub tx: UnboundedSender<BackendMessage>,
     pub progress: f64,
     pub waiting: bool,
+    /// Identifies the player when reconnecting after an accidental
+    /// disconnect. Only the player himself knows about it.
+    pub token: Uuid,
+    /// Holds the time a disconnected player is removed. This is only set
+    /// while the player is disconnected.
+    pub removal_time: Option<DateTime<Utc>>,
+    /// The time the player reached the goal file in the current round.
+    pub finished_at: Option<DateTime<Utc>>,
+    /// The position the player finished the current round in, starting at
+    /// one.
+    pub position: Option<usize>,
+    /// The number of progress updates in the current round.
+    pub edits: u32,
+    /// The approximate number of edits per minute in the current round.
+    pub edits_per_minute: f64,
+    /// Whether the player is ready for the next game.
+    pub ready: bool,
+    /// The send times of the most recent chat messages of the player, oldest
+    /// first. Holds at most `CHAT_RATE_LIMIT_MESSAGES` entries.
+    pub chat_sent_at: VecDeque<Instant>,
+    /// The time the player last sent a message. Idle players are kicked while
+    /// the lobby waits for players.
+    pub last_active_at: Instant,
+    /// The time the activity of the player was last announced to his lobby.
+    pub activity_announced_at: Option<Instant>,
+    /// Whether a progress update of the player is currently being scored.
+    pub scoring: bool,
+    /// The newest progress update received while scoring, which is scored
+    /// next. Replaced by every further update.
+    pub pending_progress: Option<Vec<u8>>,
 }
 
 impl Player {
-    pub fn new(tx: UnboundedSender<BackendMessage>) -> Self {
+    /// Uses the desired name if it is still non-empty after sanitizing and
+    /// falls back to a random name otherwise.
+    pub fn new(tx: UnboundedSender<BackendMessage>, name: Option<String>) -> Self {
+        let name = name
+            .as_deref()
+            .and_then(|name| sanitize_name(name, MAX_PLAYER_NAME_LENGTH))
+            .unwrap_or_else(|| Name(EN).fake());
         Self {
             id: Uuid::new_v4(),
-            name: Name(EN).fake(),
+            name,
             tx,
             progress: 0.0,
             waiting: false,
+            token: Uuid::new_v4(),
+            removal_time: None,
+            finished_at: None,
+            position: None,
+            edits: 0,
+            edits_per_minute: 0.0,
+            ready: false,
+            chat_sent_at: VecDeque::new(),
+            last_active_at: Instant::now(),
+            activity_announced_at: None,
+            scoring: false,
+            pending_progress: None,
+        }
+    }
+
+    /// # Allow chat message
+    ///
+    /// Records a chat message of the player unless he already sent
+    /// `CHAT_RATE_LIMIT_MESSAGES` messages within `CHAT_RATE_LIMIT_WINDOW`.
+    /// Returns whether the message may be sent.
+    pub fn allow_chat_message(&mut self) -> bool {
+        let now = Instant::now();
+        if self.chat_sent_at.len() >= CHAT_RATE_LIMIT_MESSAGES {
+            if self
+                .chat_sent_at
+                .front()
+                .is_some_and(|sent_at| now.duration_since(*sent_at) < CHAT_RATE_LIMIT_WINDOW)
+            {
+                return false;
+            }
+            self.chat_sent_at.pop_front();
+        }
+        self.chat_sent_at.push_back(now);
+        true
+    }
+
+    /// # Announce activity
+    ///
+    /// Returns whether the activity of the player should be announced to his
+    /// lobby, i.e., whether the last announcement is at least
+    /// `PLAYER_ACTIVE_INTERVAL` ago.
+    pub fn announce_activity(&mut self) -> bool {
+        let now = Instant::now();
+        if self
+            .activity_announced_at
+            .is_some_and(|announced_at| now.duration_since(announced_at) < PLAYER_ACTIVE_INTERVAL)
+        {
+            return false;
         }
+        self.activity_announced_at = Some(now);
+        true
+    }
+
+    /// # Record edit
+    ///
+    /// Counts a progress update of the player and recomputes his edits per
+    /// minute since the start of the game.
+    pub fn record_edit(&mut self, started_at: Option<DateTime<Utc>>) {
+        self.edits += 1;
+        let Some(started_at) = started_at else {
+            return;
+        };
+        // Avoid huge rates right after the start of the game.
+        let elapsed_seconds = (Utc::now() - started_at).num_seconds().max(1) as f64;
+        self.edits_per_minute = f64::from(self.edits) * 60.0 / elapsed_seconds;
+    }
+
+    /// # Reset round
+    ///
+    /// Resets all statistics of the player gathered in the current round.
+    pub fn reset_round(&mut self, progress: f64) {
+        self.progress = progress;
+        self.finished_at = None;
+        self.position = None;
+        self.edits = 0;
+        self.edits_per_minute = 0.0;
+        self.pending_progress = None;
     }
 
     pub fn to_common_player(&self) -> common::Player {
@@ -29,7 +146,66 @@ impl Player {
             id: self.id,
             name: self.name.clone(),
             progress: self.progress,
+            edits_per_minute: self.edits_per_minute,
             waiting: self.waiting,
+            ready: self.ready,
+            position: self.position,
+        }
+    }
+}
+
+/// # Sanitize name
+///
+/// Strips control characters and surrounding whitespace from the desired
+/// player or lobby name and cuts it to `max_length` characters. Returns
+/// `None` if nothing is left.
+pub fn sanitize_name(name: &str, max_length: usize) -> Option<String> {
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    let name: String = name
+        .trim()
+        .chars()
+        .take(max_length)
+        .collect::<String>()
+        .trim_end()
+        .to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+
+    fn new_player() -> Player {
+        let (tx, _rx) = unbounded_channel();
+        Player::new(tx, Some("Player".to_string()))
+    }
+
+    #[test]
+    fn chat_burst_is_limited() {
+        let mut player = new_player();
+        for _ in 0..CHAT_RATE_LIMIT_MESSAGES {
+            assert!(player.allow_chat_message());
+        }
+        assert!(!player.allow_chat_message());
+        assert_eq!(player.chat_sent_at.len(), CHAT_RATE_LIMIT_MESSAGES);
+    }
+
+    #[test]
+    fn chat_is_allowed_again_after_window() {
+        let mut player = new_player();
+        for _ in 0..CHAT_RATE_LIMIT_MESSAGES {
+            assert!(player.allow_chat_message());
+        }
+        assert!(!player.allow_chat_message());
+
+        // Pretend the burst happened one window ago.
+        let sent_at = Instant::now() - CHAT_RATE_LIMIT_WINDOW;
+        for time in player.chat_sent_at.iter_mut() {
+            *time = sent_at;
         }
+        assert!(player.allow_chat_message());
+        assert_eq!(player.chat_sent_at.len(), CHAT_RATE_LIMIT_MESSAGES);
     }
 }
@@ -1,3 +1,29 @@
+use tracing::warn;
+use warp::filters::ws::Message;
+
+use common::ClientMessage;
+
 pub(crate) mod clients;
+pub(crate) mod leaderboard;
 pub(crate) mod lobbies;
+pub(crate) mod metrics;
 pub(crate) mod players;
+pub(crate) mod spectators;
+
+/// # Parse client message
+///
+/// Deserializes a WS message into a `ClientMessage`. Logs and skips binary
+/// frames and malformed text instead of failing.
+pub fn parse_client_message(msg: &Message) -> Option<ClientMessage> {
+    let Ok(text) = msg.to_str() else {
+        warn!("Ignored non-text message from client.");
+        return None;
+    };
+    match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => Some(message),
+        Err(e) => {
+            warn!("Ignored malformed client message: {e}");
+            None
+        }
+    }
+}
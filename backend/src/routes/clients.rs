@@ -1,15 +1,15 @@
-use anyhow::Result;
-use common::BackendMessage;
-use futures_util::{future::ready, SinkExt, StreamExt};
+use common::ClientMessage;
+use futures_util::StreamExt;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
-use tracing::error;
+use tracing::warn;
 use uuid::Uuid;
-use warp::{
-    filters::ws::{Message, WebSocket},
-    Filter,
-};
+use warp::{filters::ws::WebSocket, Filter};
 
-use crate::app::message::AppMessage;
+use crate::{
+    app::message::AppMessage,
+    heartbeat::{forward_backend_messages, next_message},
+    routes::parse_client_message,
+};
 
 pub fn routes(
     app_tx: UnboundedSender<AppMessage>,
@@ -29,16 +29,8 @@ pub fn routes(
 pub async fn handle_connection(ws: WebSocket, app_tx: UnboundedSender<AppMessage>) {
     let (to_ws, mut from_ws) = ws.split();
 
-    // Typecast the websocket sending part to use `BackendMessage directly`.
-    let mut to_ws = to_ws.with(|msg: BackendMessage| {
-        let res: Result<Message, warp::Error> = Ok(Message::text(
-            serde_json::to_string(&msg).expect("Converting message to JSON"),
-        ));
-        ready(res)
-    });
-
     // Register the new client connection.
-    let (client_tx, mut client_rx) = unbounded_channel();
+    let (client_tx, client_rx) = unbounded_channel();
     let client_id = Uuid::new_v4();
     let _ = app_tx.send(AppMessage::AddClient {
         client_id,
@@ -47,20 +39,25 @@ pub async fn handle_connection(ws: WebSocket, app_tx: UnboundedSender<AppMessage
     // Tell the client about all current lobbies.
     let _ = app_tx.send(AppMessage::CurrentLobbies { client_id });
 
-    // If the client closes his WS connection this task will signal the app to
-    // remove him from the current clients.
+    // Handle incoming client messages. If the client closes his WS connection
+    // this task will signal the app to remove him from the current clients.
     tokio::spawn(async move {
-        while from_ws.next().await.is_some() {}
+        while let Some(msg) = next_message(&mut from_ws).await {
+            if msg.is_close() {
+                break;
+            }
+            match parse_client_message(&msg) {
+                Some(ClientMessage::SubscribeEvents) => {
+                    let _ = app_tx.send(AppMessage::SubscribeEvents { client_id });
+                }
+                Some(message) => warn!("Client sent unsupported message: {:?}", message),
+                None => {}
+            }
+        }
         let _ = app_tx.send(AppMessage::RemoveClient { client_id });
     });
 
     // Forward messages received through the applicaton channel to the client
     // WS connection.
-    tokio::spawn(async move {
-        while let Some(msg) = client_rx.recv().await {
-            if let Err(e) = to_ws.send(msg).await {
-                error!("Error sending message via websocket: {e}");
-            }
-        }
-    });
+    tokio::spawn(forward_backend_messages(to_ws, client_rx));
 }
@@ -0,0 +1,33 @@
+use std::convert::Infallible;
+
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use warp::Filter;
+
+use crate::app::message::AppMessage;
+
+pub fn routes(
+    app_tx: UnboundedSender<AppMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    // Allow warp route handlers to take in the app sending channel as input.
+    let app_tx = warp::any().map(move || app_tx.clone());
+
+    warp::path!("leaderboard")
+        .and(warp::get())
+        .and(app_tx)
+        .and_then(leaderboard)
+}
+
+/// # Leaderboard
+///
+/// Replies with the best time of every player per challenge. The list is empty
+/// if no results database is configured.
+pub async fn leaderboard(
+    app_tx: UnboundedSender<AppMessage>,
+) -> Result<impl warp::Reply, Infallible> {
+    let (tx, rx) = oneshot::channel();
+
+    let _ = app_tx.send(AppMessage::Leaderboard { tx });
+    let entries = rx.await.unwrap_or_default();
+
+    Ok(warp::reply::json(&entries))
+}
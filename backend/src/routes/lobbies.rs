@@ -1,11 +1,16 @@
 use std::convert::Infallible;
 
+use serde_json::json;
 use tokio::sync::{mpsc::UnboundedSender, oneshot};
-use warp::Filter;
+use tracing::error;
+use warp::{http::StatusCode, Filter};
 
-use common::JoinMode;
+use common::{BackendMessage, CreateLobbyOptions, JoinMode};
 
-use crate::app::message::AppMessage;
+use crate::{
+    app::{message::AppMessage, InvalidLobbyOptions, ServerFull},
+    constants::MAX_CREATE_LOBBY_BODY_SIZE,
+};
 
 pub fn routes(
     app_tx: UnboundedSender<AppMessage>,
@@ -13,19 +18,124 @@ pub fn routes(
     // Allow warp route handlers to take in the app sending channel as input.
     let app_tx = warp::any().map(move || app_tx.clone());
 
-    warp::path!("lobbies" / JoinMode)
+    let list = warp::path!("lobbies")
+        .and(warp::get())
+        .and(app_tx.clone())
+        .and_then(list_lobbies);
+
+    let create = warp::path!("lobbies")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(MAX_CREATE_LOBBY_BODY_SIZE))
+        .and(warp::body::json::<CreateLobbyOptions>())
+        .and(app_tx.clone())
+        .and_then(create_lobby);
+
+    let information = warp::path!("lobbies" / JoinMode)
+        .and(warp::query::<CreateLobbyOptions>())
         .and(app_tx)
-        .and_then(lobby_information)
+        .and_then(lobby_information);
+
+    list.or(create).or(information)
+}
+
+/// # List lobbies
+///
+/// Replies with all current lobbies including their status and player count.
+/// Allows to poll the lobbies without keeping a websocket connection open.
+pub async fn list_lobbies(
+    app_tx: UnboundedSender<AppMessage>,
+) -> Result<impl warp::Reply, Infallible> {
+    let (tx, rx) = oneshot::channel();
+
+    let _ = app_tx.send(AppMessage::ListLobbies { tx });
+    let lobbies = rx.await.unwrap_or_default();
+
+    Ok(warp::reply::json(&lobbies))
+}
+
+/// # Create lobby
+///
+/// Creates a lobby with the options of the JSON body, e.g., to provision
+/// matches from external tooling. Replies with the ID of the new lobby and
+/// status 201. Invalid options are answered with status 400 and a full
+/// server with status 503, both with an error message.
+pub async fn create_lobby(
+    options: CreateLobbyOptions,
+    app_tx: UnboundedSender<AppMessage>,
+) -> Result<impl warp::Reply, Infallible> {
+    let (tx, rx) = oneshot::channel();
+
+    let _ = app_tx.send(AppMessage::CreateLobby { options, tx });
+    let reply = match rx.await {
+        Ok(Ok(lobby_id)) => warp::reply::with_status(
+            warp::reply::json(&json!({ "lobby_id": lobby_id })),
+            StatusCode::CREATED,
+        ),
+        Ok(Err(e)) => {
+            let status = if e.is::<InvalidLobbyOptions>() {
+                StatusCode::BAD_REQUEST
+            } else if e.is::<ServerFull>() {
+                StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            warp::reply::with_status(
+                warp::reply::json(&json!({ "error": e.to_string() })),
+                status,
+            )
+        }
+        Err(e) => {
+            error!("Unable to receive the created lobby: {e}");
+            warp::reply::with_status(
+                warp::reply::json(&json!({ "error": "The lobby could not be created." })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        }
+    };
+
+    Ok(reply)
 }
 
+/// # Lobby information
+///
+/// Replies with the information of the lobby matching the join mode.
+/// Otherwise, replies with the `BackendMessage` explaining why, e.g.,
+/// `BackendMessage::ServerFull` and status 503 if a new lobby would be needed
+/// but the maximum number of lobbies is reached.
 pub async fn lobby_information(
     join_mode: JoinMode,
+    options: CreateLobbyOptions,
     app_tx: UnboundedSender<AppMessage>,
 ) -> Result<impl warp::Reply, Infallible> {
     let (tx, rx) = oneshot::channel();
 
-    let _ = app_tx.send(AppMessage::ProvideLobbyInformation { tx, join_mode });
-    let lobby_information = rx.await.expect("Should receive the lobby name.");
+    let _ = app_tx.send(AppMessage::ProvideLobbyInformation {
+        tx,
+        join_mode,
+        options,
+    });
+    let reply = match rx.await {
+        Ok(Ok(lobby_information)) => {
+            warp::reply::with_status(warp::reply::json(&lobby_information), StatusCode::OK)
+        }
+        Ok(Err(message)) => {
+            let status = match message {
+                BackendMessage::ServerFull => StatusCode::SERVICE_UNAVAILABLE,
+                BackendMessage::LobbyNotFound => StatusCode::NOT_FOUND,
+                BackendMessage::InvalidLobbyOptions(_) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            warp::reply::with_status(warp::reply::json(&message), status)
+        }
+        // The app dropped the request without a reason.
+        Err(e) => {
+            error!("Unable to receive the lobby information: {e}");
+            warp::reply::with_status(
+                warp::reply::json(&json!({ "error": "The lobby information is unavailable." })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        }
+    };
 
-    Ok(warp::reply::json(&lobby_information))
+    Ok(reply)
 }
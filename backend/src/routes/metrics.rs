@@ -0,0 +1,34 @@
+use std::convert::Infallible;
+
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use warp::{http::header::CONTENT_TYPE, Filter};
+
+use crate::app::message::AppMessage;
+
+pub fn routes(
+    app_tx: UnboundedSender<AppMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    // Allow warp route handlers to take in the app sending channel as input.
+    let app_tx = warp::any().map(move || app_tx.clone());
+
+    warp::path!("metrics")
+        .and(warp::get())
+        .and(app_tx)
+        .and_then(metrics)
+}
+
+/// # Metrics
+///
+/// Replies with the current metrics in the Prometheus text format.
+pub async fn metrics(app_tx: UnboundedSender<AppMessage>) -> Result<impl warp::Reply, Infallible> {
+    let (tx, rx) = oneshot::channel();
+
+    let _ = app_tx.send(AppMessage::Metrics { tx });
+    let snapshot = rx.await.unwrap_or_default();
+
+    Ok(warp::reply::with_header(
+        snapshot.to_prometheus(),
+        CONTENT_TYPE,
+        "text/plain; version=0.0.4",
+    ))
+}
@@ -1,20 +1,30 @@
-use anyhow::Result;
-use futures_util::{
-    future::ready,
-    stream::{SplitSink, SplitStream},
-    SinkExt, StreamExt,
+use futures_util::{stream::SplitStream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedSender},
+    oneshot,
 };
-use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
-use tracing::error;
+use tracing::{error, info, warn};
 use uuid::Uuid;
-use warp::{
-    filters::ws::{Message, WebSocket},
-    Filter,
-};
+use warp::{filters::ws::WebSocket, Filter};
 
 use common::{constants::MAX_CHAT_MESSAGE_LENGTH, BackendMessage, ClientMessage};
 
-use crate::{player::Player, AppMessage};
+use crate::{
+    heartbeat::{forward_backend_messages, next_message},
+    player::Player,
+    routes::parse_client_message,
+    AppMessage,
+};
+
+/// Query parameters of the player route.
+#[derive(Debug, Deserialize)]
+pub struct PlayerQuery {
+    /// The token of a previous connection to restore the seat of.
+    token: Option<Uuid>,
+    /// The name the player wants to be displayed with. Ignored on reconnect.
+    name: Option<String>,
+}
 
 pub fn routes(
     app_tx: UnboundedSender<AppMessage>,
@@ -23,35 +33,66 @@ pub fn routes(
     let app_tx = warp::any().map(move || app_tx.clone());
 
     warp::path!("players" / Uuid)
+        .and(warp::query::<PlayerQuery>())
         .and(warp::ws())
         .and(app_tx)
         .map(
-            |lobby_id: Uuid, ws: warp::ws::Ws, app_tx: UnboundedSender<AppMessage>| {
-                ws.on_upgrade(move |ws| handle_join(ws, app_tx, lobby_id))
+            |lobby_id: Uuid,
+             query: PlayerQuery,
+             ws: warp::ws::Ws,
+             app_tx: UnboundedSender<AppMessage>| {
+                ws.on_upgrade(move |ws| handle_join(ws, app_tx, lobby_id, query))
             },
         )
 }
 
-pub async fn handle_join(ws: WebSocket, app_tx: UnboundedSender<AppMessage>, lobby_id: Uuid) {
+pub async fn handle_join(
+    ws: WebSocket,
+    app_tx: UnboundedSender<AppMessage>,
+    lobby_id: Uuid,
+    query: PlayerQuery,
+) {
     let (to_ws, from_ws) = ws.split();
 
-    // Setup player.
+    // Forward messages received through the applicaton channel to the client.
     let (player_tx, player_rx) = unbounded_channel();
-    let player = Player::new(player_tx);
+    tokio::spawn(forward_backend_messages(to_ws, player_rx));
+
+    let player = match query.token {
+        // Try to restore the seat of a disconnected player.
+        Some(token) => {
+            let (tx, rx) = oneshot::channel();
+            let _ = app_tx.send(AppMessage::ReconnectPlayer {
+                lobby_id,
+                token,
+                player_tx: player_tx.clone(),
+                tx,
+            });
+            let Ok(Some(player)) = rx.await else {
+                info!(
+                    "Rejected reconnect to lobby {} with unknown token.",
+                    lobby_id
+                );
+                let _ = player_tx.send(BackendMessage::ReconnectFailed);
+                return;
+            };
+            player
+        }
+        // Try to add a new player to provided lobby.
+        None => {
+            let player = Player::new(player_tx, query.name);
+            let _ = app_tx.send(AppMessage::AddPlayerToLobby {
+                lobby_id,
+                player: player.clone(),
+            });
+            player
+        }
+    };
 
     // Handle incoming client messages.
     tokio::spawn(receive_and_handle_client_message(
-        from_ws,
-        app_tx.clone(),
-        player.clone(),
-        lobby_id,
+        from_ws, app_tx, player, lobby_id,
     ));
-
-    // Try to add the player to provided lobby.
-    let _ = app_tx.send(AppMessage::AddPlayerToLobby { lobby_id, player });
-
-    // Forward messages received through the applicaton channel to the client.
-    tokio::spawn(forward_backend_message(to_ws, player_rx));
 }
 
 async fn receive_and_handle_client_message(
@@ -60,21 +101,28 @@ async fn receive_and_handle_client_message(
     player: Player,
     lobby_id: Uuid,
 ) {
-    while let Some(Ok(msg)) = from_ws.next().await {
+    let mut closed_by_client = false;
+    loop {
+        let msg = tokio::select! {
+            msg = next_message(&mut from_ws) => msg,
+            // The backend closed the connection, e.g., after kicking the
+            // player. The player was already removed from the lobby.
+            _ = player.tx.closed() => None,
+        };
+        let Some(msg) = msg else {
+            break;
+        };
         if msg.is_close() {
+            closed_by_client = true;
             break;
         }
-        let Ok(text) = msg.to_str() else {
-            error!("Received non-text message from client.");
+        let Some(client_message) = parse_client_message(&msg) else {
             continue;
         };
-        let client_message = match serde_json::from_str::<ClientMessage>(text) {
-            Ok(message) => message,
-            Err(e) => {
-                error!("Error deserializing client message: {e}");
-                continue;
-            }
-        };
+        let _ = app_tx.send(AppMessage::RecordActivity {
+            lobby_id,
+            player_id: player.id,
+        });
         let msg = match client_message {
             ClientMessage::SendMessage { message } => {
                 let message_length = message.len();
@@ -94,34 +142,62 @@ async fn receive_and_handle_client_message(
             ClientMessage::RequestStart => AppMessage::RequestStart {
                 player: player.clone(),
                 lobby_id,
+                force: false,
+            },
+            ClientMessage::ForceStart => AppMessage::RequestStart {
+                player: player.clone(),
+                lobby_id,
+                force: true,
+            },
+            ClientMessage::CancelStart => AppMessage::CancelStart {
+                player: player.clone(),
+                lobby_id,
+            },
+            ClientMessage::ToggleReady => AppMessage::ToggleReady {
+                player_id: player.id,
+                lobby_id,
+            },
+            ClientMessage::TogglePause => AppMessage::TogglePause {
+                player: player.clone(),
+                lobby_id,
+            },
+            ClientMessage::ToggleAutoStart => AppMessage::ToggleAutoStart {
+                player: player.clone(),
+                lobby_id,
+            },
+            ClientMessage::KickPlayer { player_id } => AppMessage::KickPlayer {
+                player: player.clone(),
+                lobby_id,
+                player_id,
+            },
+            ClientMessage::TransferOwnership { player_id } => AppMessage::TransferOwnership {
+                player: player.clone(),
+                lobby_id,
+                player_id,
+            },
+            ClientMessage::RenameLobby { name } => AppMessage::RenameLobby {
+                player: player.clone(),
+                lobby_id,
+                name,
             },
             ClientMessage::Progress { progress } => AppMessage::ComputePlayerProgress {
                 lobby_id,
                 player_id: player.id,
                 progress,
             },
+            ClientMessage::SubscribeEvents => {
+                warn!("Player tried to subscribe to events.");
+                continue;
+            }
         };
         let _ = app_tx.send(msg);
     }
     // If the player closes his WS connection remove him from the lobby.
-    let _ = app_tx.send(AppMessage::RemovePlayer { player, lobby_id });
-}
-
-async fn forward_backend_message(
-    to_ws: SplitSink<WebSocket, Message>,
-    mut player_rx: UnboundedReceiver<BackendMessage>,
-) {
-    // Typecast the websocket sending part to use `BackendMessage directly`.
-    let mut to_ws = to_ws.with(|msg: BackendMessage| {
-        let res: Result<Message, warp::Error> = Ok(Message::text(
-            serde_json::to_string(&msg).expect("Converting message to JSON"),
-        ));
-        ready(res)
-    });
-
-    while let Some(msg) = player_rx.recv().await {
-        if let Err(e) = to_ws.send(msg).await {
-            error!("Error sending message via websocket: {e}");
-        }
+    // Otherwise, the connection dropped accidentally and the player is able
+    // to reconnect for a while.
+    if closed_by_client {
+        let _ = app_tx.send(AppMessage::RemovePlayer { player, lobby_id });
+    } else {
+        let _ = app_tx.send(AppMessage::DisconnectPlayer { player, lobby_id });
     }
 }
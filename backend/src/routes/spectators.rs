@@ -0,0 +1,59 @@
+use futures_util::StreamExt;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tracing::warn;
+use uuid::Uuid;
+use warp::{filters::ws::WebSocket, Filter};
+
+use crate::{
+    app::message::AppMessage,
+    heartbeat::{forward_backend_messages, next_message},
+};
+
+pub fn routes(
+    app_tx: UnboundedSender<AppMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    // Allow warp route handlers to take in the app sending channel as input.
+    let app_tx = warp::any().map(move || app_tx.clone());
+
+    warp::path!("spectators" / Uuid)
+        .and(warp::ws())
+        .and(app_tx)
+        .map(
+            |lobby_id: Uuid, ws: warp::ws::Ws, app_tx: UnboundedSender<AppMessage>| {
+                ws.on_upgrade(move |ws| handle_spectate(ws, app_tx, lobby_id))
+            },
+        )
+}
+
+pub async fn handle_spectate(ws: WebSocket, app_tx: UnboundedSender<AppMessage>, lobby_id: Uuid) {
+    let (to_ws, mut from_ws) = ws.split();
+
+    // Register the new spectator inside the lobby.
+    let (spectator_tx, spectator_rx) = unbounded_channel();
+    let spectator_id = Uuid::new_v4();
+    let _ = app_tx.send(AppMessage::AddSpectatorToLobby {
+        lobby_id,
+        spectator_id,
+        spectator_tx,
+    });
+
+    // Spectators only watch, so every incoming message besides the close
+    // frame is ignored. If the spectator closes his WS connection this task
+    // will signal the app to remove him from the lobby.
+    tokio::spawn(async move {
+        while let Some(msg) = next_message(&mut from_ws).await {
+            if msg.is_close() {
+                break;
+            }
+            warn!("Spectator {} sent unsupported message.", spectator_id);
+        }
+        let _ = app_tx.send(AppMessage::RemoveSpectator {
+            lobby_id,
+            spectator_id,
+        });
+    });
+
+    // Forward messages received through the applicaton channel to the
+    // spectator WS connection.
+    tokio::spawn(forward_backend_messages(to_ws, spectator_rx));
+}
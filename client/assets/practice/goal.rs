@@ -0,0 +1,18 @@
+fn main() {
+    println!("Hello, world!");
+}
+fn main() {
+    println!("Hello, world!");
+}
+fn main() {
+    println!("Hello, world!");
+}
+fn main() {
+    println!("Hello, world!");
+}
+fn main() {
+    println!("Hello, world!");
+}
+fn main() {
+    println!("Hello, world!");
+}
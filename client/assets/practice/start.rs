@@ -0,0 +1,3 @@
+fn side() {
+    println!("Hello, dude!");
+}
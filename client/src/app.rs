@@ -1,28 +1,37 @@
-use std::time::{Duration, Instant};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
 
 use anyhow::Result;
-use common::{JoinMode, LobbyStatus};
+use common::{CreateLobbyOptions, JoinMode, LobbyStatus, Standing};
 use futures_util::SinkExt;
-use log::debug;
+use log::{debug, error, warn};
 use ratatui::{
     backend::Backend,
     crossterm::{
         self,
-        event::{self, Event, KeyEvent},
+        event::{self, Event, KeyEvent, MouseEvent},
     },
-    layout::Size,
+    layout::{Direction, Size},
     Terminal,
 };
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use uuid::Uuid;
 
 #[cfg(feature = "audio")]
-use crate::audio::{play_audio, Audio};
+use crate::audio::{Audio, AudioPlayer};
 use crate::{
     config::Config,
+    constants::{EDITOR_RATIO_STEP, LATENCY_PING_INTERVAL},
     schema::{
-        connection::Connection,
+        command_palette::{Command, CommandPalette},
+        connection::{send_ping, Connection},
         focused_component::{ComponentKind, FocusedComponent},
+        join::JoinMessage,
         lobby::{Lobby, LobbyMessage},
+        practice::Practice,
+        session::Session,
         tab::Tab,
     },
     ui,
@@ -43,9 +52,43 @@ pub struct App {
     pub total_clients: usize,
     /// The total number playing users.
     pub total_players: usize,
+    /// The total number of users watching a lobby.
+    pub total_spectators: usize,
+    /// The total number of active lobbies.
+    pub total_lobbies: usize,
+    /// The wins and best times of all players by name.
+    pub leaderboard: BTreeMap<String, Standing>,
     /// The currently focused component has priority over all other elements
     /// when it comes to user inputs.
     pub focused_component: Option<FocusedComponent>,
+    /// The query and selection of the command palette.
+    pub command_palette: CommandPalette,
+    /// The lobby and its index in the lobby list that was selected before the
+    /// backend service disconnected. Reapplied once the lobby list arrives
+    /// after a reconnect.
+    pub last_lobby_selection: Option<(Uuid, usize)>,
+    /// The session stored on the previous launch, offered for reconnecting
+    /// until the user answers.
+    pub last_session: Option<Session>,
+    /// The number of columns the logs are scrolled horizontally.
+    pub logs_offset: u16,
+    /// The number of rows the key binding overlay is scrolled vertically.
+    pub help_offset: u16,
+    /// How editor and goal are arranged. Starts with the configured default
+    /// layout and is kept across lobbies of the session.
+    pub terminal_layout_direction: Direction,
+    /// The share of the terminal area taken by the editor. Starts with the
+    /// configured ratio and is kept across lobbies of the session.
+    pub editor_ratio: f64,
+    /// The round trip time of the latest ping to the backend service.
+    pub latency: Option<Duration>,
+    /// The time the latest pong of the backend service arrived.
+    pub last_pong: Option<Instant>,
+    /// The time the latest ping was sent to the backend service.
+    pub last_ping: Instant,
+    /// Plays sounds without blocking the render loop.
+    #[cfg(feature = "audio")]
+    pub audio_player: AudioPlayer,
 
     pub exit: bool,
 }
@@ -56,24 +99,66 @@ pub enum AppMessage {
     /// Connects to a lobby via the given join mode.
     ConnectToLobby {
         join_mode: JoinMode,
+        options: CreateLobbyOptions,
+    },
+    /// Disconnects the client from the current lobby. The optional notice is
+    /// shown on the join screen.
+    DisconnectLobby {
+        notice: Option<String>,
     },
-    /// Disconnects the client from the current lobby.
-    DisconnectLobby,
+    /// The lobby was removed before the client took its seat. Returns to the
+    /// join screen and tells the user.
+    LobbyNotFound,
     /// Updates the total connection count on the home page.
     ConnectionCounts {
         players: usize,
         clients: usize,
+        spectators: usize,
+        lobbies: usize,
     },
+    /// Updates the leaderboard on the home page.
+    UpdateLeaderboard(BTreeMap<String, Standing>),
     /// The backend connection was closed. The app tries to reconnnect.
     ServiceDisconnected,
     /// The backend is back online.
     ServiceBackOnline,
+    /// The backend service answered a ping after the given round trip time.
+    Latency(Duration),
+    /// Runs a command chosen in the command palette.
+    RunCommand(Command),
+    /// Connects to the backend service and lobby of the last session.
+    RestoreSession(Session),
+    /// Starts a local practice round.
+    StartPractice,
+    /// Ends the practice round and connects to the backend again.
+    EndPractice,
+    /// Plays a sound cue.
+    #[cfg(feature = "audio")]
+    PlayAudio(Audio),
 }
 
 impl App {
     pub async fn new(config: Config, size: Size) -> Result<Self> {
         let (tx, rx) = unbounded_channel();
-        let connection = Connection::new(tx.clone()).await?;
+        let connection = Connection::new(tx.clone(), &config).await?;
+        let terminal_layout_direction = config.general.default_layout.into();
+        let editor_ratio = config.general.editor_ratio;
+
+        // Offer to reconnect if the last session ended inside a lobby or on
+        // another backend service.
+        let last_session = config
+            .general
+            .session_file
+            .as_deref()
+            .and_then(Session::load)
+            .filter(|session| {
+                session.lobby_id.is_some()
+                    || session.service.address != config.general.service.address
+            });
+        let focused_component = last_session
+            .as_ref()
+            .map(|_| FocusedComponent::new(ComponentKind::ReconnectPopup));
+
         let app = App {
             config,
             current_tab: Tab::Home,
@@ -83,7 +168,22 @@ impl App {
             connection,
             total_clients: 0,
             total_players: 0,
-            focused_component: None,
+            total_spectators: 0,
+            total_lobbies: 0,
+            leaderboard: BTreeMap::new(),
+            focused_component,
+            command_palette: CommandPalette::default(),
+            last_lobby_selection: None,
+            last_session,
+            logs_offset: 0,
+            help_offset: 0,
+            terminal_layout_direction,
+            editor_ratio,
+            latency: None,
+            last_pong: None,
+            last_ping: Instant::now(),
+            #[cfg(feature = "audio")]
+            audio_player: AudioPlayer::default(),
             exit: false,
         };
         Ok(app)
@@ -135,6 +235,26 @@ impl App {
         false
     }
 
+    /// # Record session
+    ///
+    /// Stores the backend service and the lobby the user currently plays in,
+    /// if a session file is configured.
+    fn record_session(&self) {
+        let Some(ref path) = self.config.general.session_file else {
+            return;
+        };
+        let lobby = match self.connection {
+            Connection::Lobby(ref lobby) if !lobby.spectating => Some(lobby),
+            _ => None,
+        };
+        let session = Session {
+            service: self.config.general.service.clone(),
+            lobby_id: lobby.map(|lobby| lobby.id),
+            lobby_name: lobby.map(|lobby| lobby.name.clone()),
+        };
+        session.save(path);
+    }
+
     /// # Move to the next tab
     ///
     /// Selects the next tab.
@@ -161,6 +281,35 @@ impl App {
             return Ok(());
         }
 
+        // Jump into the full screen editor and back to the split view.
+        if key.eq(&self
+            .config
+            .key_bindings
+            .miscellaneous
+            .toggle_editor_full_screen)
+            && matches!(self.current_tab, Tab::Play)
+        {
+            return FocusedComponent::toggle_editor_full_screen(self);
+        }
+
+        // Open the command palette with a fresh query.
+        if key.eq(&self.config.key_bindings.miscellaneous.command_palette)
+            && self.focused_component.is_none()
+            && matches!(self.current_tab, Tab::Play)
+        {
+            self.command_palette = CommandPalette::default();
+            self.focused_component = Some(FocusedComponent::new(ComponentKind::CommandPalette));
+            return Ok(());
+        }
+
+        // Open the key binding overlay from the top.
+        if key.eq(&self.config.key_bindings.miscellaneous.help) && self.focused_component.is_none()
+        {
+            self.help_offset = 0;
+            self.focused_component = Some(FocusedComponent::new(ComponentKind::Help));
+            return Ok(());
+        }
+
         // Check whether there is a component focused. Such components receive
         // direct user input and take precedence.
         if self.focused_component.is_some() {
@@ -179,6 +328,27 @@ impl App {
         Ok(())
     }
 
+    /// # Handle mouse event
+    ///
+    /// Forwards mouse events to the lobby list, as long as it is shown and no
+    /// other component is focused.
+    pub async fn on_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
+        let lobbies_shown = matches!(self.current_tab, Tab::Play);
+        let other_focused = self.focused_component.as_ref().is_some_and(|component| {
+            !matches!(
+                component.kind,
+                ComponentKind::Lobbies | ComponentKind::LobbySearch
+            )
+        });
+        if !lobbies_shown || other_focused {
+            return Ok(());
+        }
+        if let Connection::Join(ref mut join) = self.connection {
+            join.handle_mouse_event(mouse).await?;
+        }
+        Ok(())
+    }
+
     async fn handle_key_event_per_tab(&mut self, key: KeyEvent) -> Result<()> {
         match self.current_tab {
             Tab::Home => {}
@@ -188,16 +358,50 @@ impl App {
                         if key.eq(&self.config.key_bindings.join.focus_lobby_list) {
                             self.focused_component =
                                 Some(FocusedComponent::new(ComponentKind::Lobbies));
+                        } else if key.eq(&self.config.key_bindings.join.search) {
+                            self.focused_component =
+                                Some(FocusedComponent::new(ComponentKind::LobbySearch));
+                        } else if key.eq(&self.config.key_bindings.join.practice) {
+                            self.tx.send(AppMessage::StartPractice)?;
                         }
                     }
                     Connection::Lobby(ref mut lobby) => {
                         // Disconnect from existing lobby.
                         if key.eq(&self.config.key_bindings.lobby.disconnect) {
                             lobby.ws_tx.close().await?;
-                            self.connection = Connection::new(self.tx.clone()).await?;
+                            self.connection =
+                                Connection::new(self.tx.clone(), &self.config).await?;
+                            self.record_session();
+                        }
+                        // Abort the countdown as lobby owner.
+                        else if key.eq(&self.config.key_bindings.lobby.cancel_start)
+                            && matches!(lobby.status, LobbyStatus::AboutToStart(_))
+                            && lobby.owner == lobby.local_player
+                            && lobby.local_player.is_some()
+                        {
+                            lobby.tx.send(LobbyMessage::CancelStart)?;
+                        }
+                        // Turn the automatic start on or off as lobby owner.
+                        else if key.eq(&self.config.key_bindings.lobby.toggle_auto_start)
+                            && matches!(
+                                lobby.status,
+                                LobbyStatus::WaitingForPlayers | LobbyStatus::AboutToStart(_)
+                            )
+                            && lobby.owner == lobby.local_player
+                            && lobby.local_player.is_some()
+                        {
+                            lobby.tx.send(LobbyMessage::ToggleAutoStart)?;
+                        }
+                        // Back out of an automatic start by getting unready.
+                        else if key.eq(&self.config.key_bindings.lobby.toggle_ready)
+                            && matches!(lobby.status, LobbyStatus::AboutToStart(_))
+                            && lobby.auto_start
+                            && !lobby.spectating
+                        {
+                            lobby.tx.send(LobbyMessage::ToggleReady)?;
                         }
                         // Whenever a lobby is about to start, ignore all key
-                        // events except the disconnect one.
+                        // events except the disconnect and cancel ones.
                         else if let LobbyStatus::AboutToStart(_) = lobby.status {
                             return Ok(());
                         }
@@ -206,8 +410,10 @@ impl App {
                             self.focused_component =
                                 Some(FocusedComponent::new(ComponentKind::Chat));
                         }
-                        // Focus the editor.
-                        else if key.eq(&self.config.key_bindings.lobby.focus_editor) {
+                        // Focus the editor. Spectators have none.
+                        else if key.eq(&self.config.key_bindings.lobby.focus_editor)
+                            && !lobby.spectating
+                        {
                             self.focused_component =
                                 Some(FocusedComponent::new(ComponentKind::Editor));
                         }
@@ -215,9 +421,60 @@ impl App {
                         else if key.eq(&self.config.key_bindings.lobby.focus_goal) {
                             self.focused_component =
                                 Some(FocusedComponent::new(ComponentKind::Goal));
+                        }
+                        // Compare the editor with the goal. Spectators have no
+                        // editor.
+                        else if key.eq(&self.config.key_bindings.lobby.focus_diff)
+                            && !lobby.spectating
+                        {
+                            self.focused_component =
+                                Some(FocusedComponent::new(ComponentKind::Diff));
+                        }
+                        // Browse the players, e.g., to kick one.
+                        else if key.eq(&self.config.key_bindings.lobby.focus_players) {
+                            if lobby.selected_player.is_none() {
+                                lobby.select_next_player();
+                            }
+                            self.focused_component =
+                                Some(FocusedComponent::new(ComponentKind::Players));
                         } else if key.eq(&self.config.key_bindings.lobby.toggle_terminal_layout) {
                             lobby.toggle_terminal_layout();
                             lobby.resize(self.size.height, self.size.width)?;
+                            self.terminal_layout_direction = lobby.terminal_layout_direction;
+                        }
+                        // Grow or shrink the editor at the expense of the
+                        // goal.
+                        else if key.eq(&self.config.key_bindings.lobby.grow_editor) {
+                            lobby.adjust_editor_ratio(EDITOR_RATIO_STEP);
+                            lobby.resize(self.size.height, self.size.width)?;
+                            self.editor_ratio = lobby.editor_ratio;
+                        } else if key.eq(&self.config.key_bindings.lobby.shrink_editor) {
+                            lobby.adjust_editor_ratio(-EDITOR_RATIO_STEP);
+                            lobby.resize(self.size.height, self.size.width)?;
+                            self.editor_ratio = lobby.editor_ratio;
+                        }
+                        // Ask for confirmation before resetting the editor.
+                        else if key.eq(&self.config.key_bindings.lobby.reset_editor)
+                            && lobby.can_reset_editor()
+                        {
+                            self.focused_component =
+                                Some(FocusedComponent::new(ComponentKind::ResetEditorPopup));
+                        }
+                        // Get ready for the next game. Spectators do not play.
+                        else if key.eq(&self.config.key_bindings.lobby.toggle_ready)
+                            && lobby.status == LobbyStatus::WaitingForPlayers
+                            && !lobby.spectating
+                        {
+                            lobby.tx.send(LobbyMessage::ToggleReady)?;
+                        }
+                        // Replay the latest game in between games.
+                        else if key.eq(&self.config.key_bindings.lobby.replay)
+                            && !matches!(
+                                lobby.status,
+                                LobbyStatus::InProgress(_) | LobbyStatus::Paused { .. }
+                            )
+                        {
+                            lobby.toggle_replay(self.config.general.replay_speed);
                         }
                         // Start the lobby as lobby owner.
                         else if key.eq(&self.config.key_bindings.lobby.start)
@@ -227,6 +484,45 @@ impl App {
                         {
                             lobby.tx.send(LobbyMessage::RequestStart)?;
                         }
+                        // Start the lobby as lobby owner, even if not all
+                        // players are ready.
+                        else if key.eq(&self.config.key_bindings.lobby.force_start)
+                            && lobby.status == LobbyStatus::WaitingForPlayers
+                            && lobby.owner == lobby.local_player
+                            && lobby.local_player.is_some()
+                        {
+                            lobby.tx.send(LobbyMessage::ForceStart)?;
+                        }
+                        // Pause or resume the lobby as lobby owner.
+                        else if key.eq(&self.config.key_bindings.lobby.toggle_pause)
+                            && matches!(
+                                lobby.status,
+                                LobbyStatus::InProgress(_) | LobbyStatus::Paused { .. }
+                            )
+                            && lobby.owner == lobby.local_player
+                            && lobby.local_player.is_some()
+                        {
+                            lobby.tx.send(LobbyMessage::TogglePause)?;
+                        }
+                        // Type a new lobby name as lobby owner.
+                        else if key.eq(&self.config.key_bindings.lobby.rename_lobby)
+                            && lobby.owner == lobby.local_player
+                            && lobby.local_player.is_some()
+                        {
+                            lobby.name_input = lobby.name.clone();
+                            self.focused_component =
+                                Some(FocusedComponent::new(ComponentKind::RenameLobbyPopup));
+                        }
+                        // Jump to the newest chat message.
+                        else if key.eq(&self.config.key_bindings.lobby.scroll_chat_bottom) {
+                            lobby.chat.scroll_to_bottom();
+                        }
+                        // Scroll the chat by a page.
+                        else if key.eq(&self.config.key_bindings.lobby.scroll_chat_page_down) {
+                            lobby.chat.next_page();
+                        } else if key.eq(&self.config.key_bindings.lobby.scroll_chat_page_up) {
+                            lobby.chat.previous_page();
+                        }
                         // Scroll chat down.
                         else if key.eq(&self.config.key_bindings.movement.down) {
                             lobby.chat.next();
@@ -234,10 +530,53 @@ impl App {
                             lobby.chat.previous();
                         }
                     }
-                    Connection::Offline(_) => {}
+                    // Practice while waiting for the backend service.
+                    Connection::Offline(_) => {
+                        if key.eq(&self.config.key_bindings.join.practice) {
+                            self.tx.send(AppMessage::StartPractice)?;
+                        }
+                    }
+                    Connection::Practice(ref mut practice) => {
+                        if key.eq(&self.config.key_bindings.lobby.disconnect) {
+                            self.tx.send(AppMessage::EndPractice)?;
+                        } else if key.eq(&self.config.key_bindings.lobby.focus_editor) {
+                            self.focused_component =
+                                Some(FocusedComponent::new(ComponentKind::Editor));
+                        } else if key.eq(&self.config.key_bindings.lobby.focus_goal) {
+                            self.focused_component =
+                                Some(FocusedComponent::new(ComponentKind::Goal));
+                        } else if key.eq(&self.config.key_bindings.lobby.toggle_terminal_layout) {
+                            practice.toggle_terminal_layout();
+                            practice.resize(self.size.height, self.size.width)?;
+                            self.terminal_layout_direction = practice.terminal_layout_direction;
+                        } else if key.eq(&self.config.key_bindings.lobby.grow_editor) {
+                            practice.adjust_editor_ratio(EDITOR_RATIO_STEP);
+                            practice.resize(self.size.height, self.size.width)?;
+                            self.editor_ratio = practice.editor_ratio;
+                        } else if key.eq(&self.config.key_bindings.lobby.shrink_editor) {
+                            practice.adjust_editor_ratio(-EDITOR_RATIO_STEP);
+                            practice.resize(self.size.height, self.size.width)?;
+                            self.editor_ratio = practice.editor_ratio;
+                        } else if key.eq(&self.config.key_bindings.lobby.reset_editor) {
+                            self.focused_component =
+                                Some(FocusedComponent::new(ComponentKind::ResetEditorPopup));
+                        }
+                    }
+                }
+            }
+            Tab::Logs => {
+                if key.eq(&self.config.key_bindings.logs.focus_logs) {
+                    self.focused_component = Some(FocusedComponent::new(ComponentKind::Logs));
+                }
+            }
+            Tab::Settings => {
+                // Switch between the configured key binding profiles.
+                if key.eq(&self.config.key_bindings.movement.down) {
+                    self.config.cycle_profile(true)?;
+                } else if key.eq(&self.config.key_bindings.movement.up) {
+                    self.config.cycle_profile(false)?;
                 }
             }
-            Tab::Logs => {}
         };
         Ok(())
     }
@@ -246,36 +585,138 @@ impl App {
         debug!("Handle message: {:?}.", msg);
 
         match msg {
-            AppMessage::DisconnectLobby => {
+            AppMessage::DisconnectLobby { notice } => {
+                self.focused_component = None;
+                if let Connection::Lobby(ref mut lobby) = self.connection {
+                    // The connection might already be gone.
+                    let _ = lobby.ws_tx.close().await;
+                    self.connection = Connection::new(self.tx.clone(), &self.config).await?;
+                    if let Connection::Join(ref mut join) = self.connection {
+                        join.notice = notice;
+                    }
+                    self.record_session();
+                }
+            }
+            AppMessage::LobbyNotFound => {
                 self.focused_component = None;
                 if let Connection::Lobby(ref mut lobby) = self.connection {
-                    lobby.ws_tx.close().await?;
-                    self.connection = Connection::new(self.tx.clone()).await?;
+                    warn!("Lobby {} no longer exists.", lobby.name);
+                    let _ = lobby.ws_tx.close().await;
+                    self.connection = Connection::new(self.tx.clone(), &self.config).await?;
+                    if let Connection::Join(ref mut join) = self.connection {
+                        join.notice = Some("Lobby no longer exists.".to_string());
+                    }
+                    self.record_session();
                 }
             }
             AppMessage::ServiceBackOnline => {
-                self.connection = Connection::new(self.tx.clone()).await?;
+                // Let the user finish practicing first.
+                if let Connection::Practice(_) = self.connection {
+                    return Ok(());
+                }
+                self.connection = Connection::new(self.tx.clone(), &self.config).await?;
+                // Forget the latency measured before going offline.
+                self.latency = None;
+                self.last_pong = None;
 
                 #[cfg(feature = "audio")]
-                play_audio(&self.config, Audio::Reconnected)?;
+                self.audio_player.play(&self.config, Audio::Reconnected);
+            }
+            #[cfg(feature = "audio")]
+            AppMessage::PlayAudio(audio) => {
+                self.audio_player.play(&self.config, audio);
             }
             AppMessage::ServiceDisconnected => {
+                // Remember the selected lobby to restore it after reconnecting.
+                if let Connection::Join(ref join) = self.connection {
+                    if let (Some(lobby_id), Some(index)) =
+                        (join.selected_lobby, join.state.selected())
+                    {
+                        self.last_lobby_selection = Some((lobby_id, index));
+                    }
+                }
                 // Make sure to unfocus components on disconnect.
                 self.focused_component = None;
-                self.connection = Connection::new(self.tx.clone()).await?;
+                self.connection = Connection::new(self.tx.clone(), &self.config).await?;
+            }
+            AppMessage::RunCommand(command) => {
+                self.focused_component = command.focus.map(FocusedComponent::new);
+                self.on_key(command.key_event()).await?;
+            }
+            AppMessage::StartPractice => {
+                let practice = Practice::new(
+                    self.size,
+                    self.terminal_layout_direction,
+                    self.editor_ratio,
+                    &self.config,
+                )?;
+                self.connection = Connection::Practice(Box::new(practice));
+                self.focused_component = None;
+            }
+            AppMessage::EndPractice => {
+                self.focused_component = None;
+                self.connection = Connection::new(self.tx.clone(), &self.config).await?;
             }
-            AppMessage::ConnectToLobby { join_mode } => {
-                let lobby = Lobby::new(self.tx.clone(), join_mode, self.size).await?;
-                self.connection = Connection::Lobby(lobby);
+            AppMessage::ConnectToLobby { join_mode, options } => {
+                let lobby = match Lobby::new(
+                    self.tx.clone(),
+                    join_mode,
+                    options,
+                    self.size,
+                    self.terminal_layout_direction,
+                    self.editor_ratio,
+                    &self.config,
+                )
+                .await
+                {
+                    Ok(lobby) => lobby,
+                    // Stay on the join screen and tell the user what went
+                    // wrong.
+                    Err(e) => {
+                        error!("Unable to connect to lobby: {e}");
+                        self.connection = Connection::new(self.tx.clone(), &self.config).await?;
+                        if let Connection::Join(ref mut join) = self.connection {
+                            join.notice = Some(e.to_string());
+                        }
+                        return Ok(());
+                    }
+                };
+                self.connection = Connection::Lobby(Box::new(lobby));
                 self.focused_component = None;
+                self.record_session();
+            }
+            AppMessage::RestoreSession(session) => {
+                self.config.general.service = session.service;
+                self.connection = Connection::new(self.tx.clone(), &self.config).await?;
+                self.current_tab = Tab::Play;
+                if let Some(lobby_id) = session.lobby_id {
+                    self.tx.send(AppMessage::ConnectToLobby {
+                        join_mode: JoinMode::Join { lobby_id },
+                        options: CreateLobbyOptions::default(),
+                    })?;
+                }
             }
-            AppMessage::ConnectionCounts { players, clients } => {
+            AppMessage::ConnectionCounts {
+                players,
+                clients,
+                spectators,
+                lobbies,
+            } => {
                 self.total_clients = clients;
                 self.total_players = players;
+                self.total_spectators = spectators;
+                self.total_lobbies = lobbies;
+            }
+            AppMessage::UpdateLeaderboard(standings) => {
+                self.leaderboard = standings;
             }
             AppMessage::FocusComponent(component) => {
                 self.focused_component = component;
             }
+            AppMessage::Latency(latency) => {
+                self.latency = Some(latency);
+                self.last_pong = Some(Instant::now());
+            }
         }
         Ok(())
     }
@@ -289,7 +730,20 @@ impl App {
             }
             Connection::Join(ref mut join) => {
                 if let Ok(msg) = join.rx.try_recv() {
+                    let is_lobby_list = matches!(msg, JoinMessage::CurrentLobbies(_));
                     join.handle_message(msg).await?;
+
+                    // Restore the selection from before a reconnect.
+                    if is_lobby_list {
+                        if let Some((lobby_id, index)) = self.last_lobby_selection.take() {
+                            join.restore_selection(lobby_id, index);
+                        }
+                    }
+                }
+            }
+            Connection::Practice(ref mut practice) => {
+                if let Ok(msg) = practice.rx.try_recv() {
+                    practice.handle_message(msg)?;
                 }
             }
             Connection::Offline(_) => {}
@@ -308,9 +762,14 @@ impl App {
             Event::Key(key) => {
                 self.on_key(key).await?;
             }
+            Event::Mouse(mouse) => {
+                self.on_mouse(mouse).await?;
+            }
             Event::Resize(cols, rows) => {
-                if let Connection::Lobby(ref mut lobby) = self.connection {
-                    lobby.resize(rows, cols)?;
+                match self.connection {
+                    Connection::Lobby(ref mut lobby) => lobby.resize(rows, cols)?,
+                    Connection::Practice(ref mut practice) => practice.resize(rows, cols)?,
+                    Connection::Join(_) | Connection::Offline(_) => {}
                 }
                 self.size = terminal.size()?;
             }
@@ -320,6 +779,16 @@ impl App {
     }
 
     pub async fn on_tick(&mut self) -> Result<()> {
+        // Measure the latency to the backend service.
+        if self.last_ping.elapsed() >= LATENCY_PING_INTERVAL {
+            self.last_ping = Instant::now();
+            match self.connection {
+                Connection::Join(ref mut join) => send_ping(&mut join.ws_tx).await,
+                Connection::Lobby(ref mut lobby) => send_ping(&mut lobby.ws_tx).await,
+                Connection::Offline(_) | Connection::Practice(_) => {}
+            }
+        }
+
         match self.connection {
             Connection::Join(ref mut join) => {
                 join.on_tick();
@@ -330,7 +799,151 @@ impl App {
             Connection::Offline(ref mut offline) => {
                 offline.on_tick().await?;
             }
+            Connection::Practice(_) => {}
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use common::{ChallengeFiles, ContentType, LobbyInformation};
+    use futures_util::StreamExt;
+    use ratatui::crossterm::event::KeyModifiers;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::args::parse_config_from_file_path;
+
+    /// Loads the bundled configuration with the built-in editor and without
+    /// session file, connecting to the given backend service.
+    fn test_config(address: &str) -> Config {
+        let mut config =
+            parse_config_from_file_path(concat!(env!("CARGO_MANIFEST_DIR"), "/config.toml"))
+                .unwrap();
+        config.general.service.address = address.to_string();
+        config.general.session_file = None;
+        config.general.use_builtin_editor = true;
+        config
+    }
+
+    fn toggle_layout_key(config: &Config) -> KeyEvent {
+        let toggle = &config.key_bindings.lobby.toggle_terminal_layout;
+        KeyEvent::new(toggle.code, toggle.modifiers.unwrap_or(KeyModifiers::NONE))
+    }
+
+    /// Serves the given lobby information over HTTP and accepts every
+    /// WebSocket connection. Returns the address of the fake backend service.
+    async fn serve_lobby(lobby_information: LobbyInformation) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let body = serde_json::to_string(&lobby_information).unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let body = body.clone();
+                tokio::spawn(async move {
+                    let mut start = [0; 12];
+                    let Ok(length) = stream.peek(&mut start).await else {
+                        return;
+                    };
+                    if start[..length].starts_with(b"GET /lobbies") {
+                        let mut request = vec![];
+                        while !request.ends_with(b"\r\n\r\n") {
+                            let Ok(byte) = stream.read_u8().await else {
+                                return;
+                            };
+                            request.push(byte);
+                        }
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                            body.len()
+                        );
+                        let _ = stream.write_all(response.as_bytes()).await;
+                    } else if let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await {
+                        // Keep the connection open until the client leaves.
+                        while let Some(Ok(_)) = ws.next().await {}
+                    }
+                });
+            }
+        });
+        address
+    }
+
+    #[tokio::test]
+    async fn toggled_layout_is_kept_for_next_practice_round() {
+        // Nothing listens on this port, so the app stays offline.
+        let config = test_config("127.0.0.1:1");
+        let toggle = toggle_layout_key(&config);
+
+        let mut app = App::new(config, Size::new(100, 40)).await.unwrap();
+        app.current_tab = Tab::Play;
+        assert_eq!(app.terminal_layout_direction, Direction::Vertical);
+
+        app.handle_message(AppMessage::StartPractice).await.unwrap();
+        app.on_key(toggle).await.unwrap();
+        assert_eq!(app.terminal_layout_direction, Direction::Horizontal);
+
+        app.handle_message(AppMessage::EndPractice).await.unwrap();
+        app.handle_message(AppMessage::StartPractice).await.unwrap();
+        let Connection::Practice(ref practice) = app.connection else {
+            panic!("practice should have started");
+        };
+        assert_eq!(practice.terminal_layout_direction, Direction::Horizontal);
+    }
+
+    #[tokio::test]
+    async fn toggled_layout_is_kept_after_reconnecting() {
+        let lobby_id = Uuid::new_v4();
+        let address = serve_lobby(LobbyInformation {
+            id: lobby_id,
+            name: "Lobby".to_string(),
+            status: LobbyStatus::WaitingForPlayers,
+            owner: None,
+            players: BTreeMap::new(),
+            challenge_files: ChallengeFiles {
+                name: "Challenge".to_string(),
+                language: "Text".to_string(),
+                extension: "txt".to_string(),
+                description: "Change the greeting.".to_string(),
+                content_type: ContentType::default(),
+                start_file: b"Hello".to_vec(),
+                goal_file: b"Hello, world!".to_vec(),
+                instructions: None,
+                cursor: None,
+            },
+            auto_start: false,
+        })
+        .await;
+        let config = test_config(&address);
+        let toggle = toggle_layout_key(&config);
+        let connect = || AppMessage::ConnectToLobby {
+            join_mode: JoinMode::Join { lobby_id },
+            options: CreateLobbyOptions::default(),
+        };
+
+        let mut app = App::new(config, Size::new(100, 40)).await.unwrap();
+        app.current_tab = Tab::Play;
+        app.handle_message(connect()).await.unwrap();
+        let Connection::Lobby(ref lobby) = app.connection else {
+            panic!("lobby should be connected");
+        };
+        assert_eq!(lobby.terminal_layout_direction, Direction::Vertical);
+
+        app.on_key(toggle).await.unwrap();
+        assert_eq!(app.terminal_layout_direction, Direction::Horizontal);
+
+        // Leave the lobby and connect to it again.
+        app.handle_message(AppMessage::DisconnectLobby { notice: None })
+            .await
+            .unwrap();
+        assert!(matches!(app.connection, Connection::Join(_)));
+        app.handle_message(connect()).await.unwrap();
+        let Connection::Lobby(ref lobby) = app.connection else {
+            panic!("lobby should be connected again");
+        };
+        assert_eq!(lobby.terminal_layout_direction, Direction::Horizontal);
+    }
+}
@@ -1,6 +1,4 @@
-use std::time::Duration;
-
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Parser;
 
 use crate::config::Config;
@@ -9,27 +7,43 @@ use crate::config::Config;
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct Args {
-    /// The application tick rate in milliseconds.
-    #[arg(short, long, value_parser = parse_duration, default_value = "35")]
-    pub tick_rate: Duration,
+    /// The application tick rate in milliseconds. Overrides the tick rate of
+    /// the configuration.
+    #[arg(short, long, value_parser = parse_tick_rate)]
+    pub tick_rate: Option<u64>,
     /// Path to a TOML configuration file.
     #[arg(short, long, value_parser = parse_config_from_file_path, default_value = "config.toml")]
     pub config: Config,
     #[arg(short, long, default_value = "keyglide.logs")]
     pub log: String,
+    /// Name of the key binding profile to activate on startup.
+    #[arg(short, long)]
+    pub profile: Option<String>,
+    /// Disable audio playback regardless of the configuration.
+    #[cfg(feature = "audio")]
+    #[arg(long)]
+    pub no_audio: bool,
+    /// Only parse and validate the configuration, then exit without starting
+    /// the application.
+    #[arg(long)]
+    pub check_config: bool,
 }
 
-fn parse_duration(arg: &str) -> Result<std::time::Duration, std::num::ParseIntError> {
+fn parse_tick_rate(arg: &str) -> Result<u64> {
     let milliseconds = arg.parse()?;
-    Ok(std::time::Duration::from_millis(milliseconds))
+    if milliseconds == 0 {
+        return Err(anyhow!("The tick rate has to be positive."));
+    }
+    Ok(milliseconds)
 }
 
 pub fn parse_config_from_file_path(path: &str) -> Result<Config> {
     let config_file = std::fs::read_to_string(path)
-        .expect("Configuration file config.toml should be located in root directory.");
-    let config: Config = toml::from_str(&config_file)?;
+        .map_err(|e| anyhow!("Unable to read configuration file {path}: {e}"))?;
+    let mut config: Config = toml::from_str(&config_file)?;
 
     // Validate the config during `clap` parsing.
     config.validate()?;
+    config.load_profiles()?;
     Ok(config)
 }
@@ -1,58 +1,176 @@
-use std::io::BufReader;
+use std::{collections::BTreeMap, io::Cursor, time::Instant};
 
 use anyhow::Result;
+use log::{error, warn};
 use strum::Display;
 
-use crate::config::Config;
+use crate::{config::Config, constants::AUDIO_DEBOUNCE};
 
-#[derive(Display)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Audio {
+    CountdownGo,
+    CountdownTick,
+    PlayerJoined,
+    PlayerLeft,
     Reconnected,
+    Won,
 }
 
 impl Audio {
     /// # Get asset
     ///
-    /// Maps an `Audio` variant to bytes of an MP3 file. The file is embedded
+    /// Maps an `Audio` variant to bytes of an audio file. The file is embedded
     /// during compile time.
     pub fn get_asset(&self) -> Vec<u8> {
-        match self {
-            Audio::Reconnected => {
-                let file = include_bytes!(concat!(
-                    env!("CARGO_MANIFEST_DIR"),
-                    "/assets/reconnected.mp3"
-                ));
-                file.to_vec()
-            }
+        let file: &[u8] = match self {
+            Audio::CountdownGo => include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/countdown_go.wav"
+            )),
+            Audio::CountdownTick => include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/countdown_tick.wav"
+            )),
+            Audio::PlayerJoined => include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/player_joined.wav"
+            )),
+            Audio::PlayerLeft => include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/player_left.wav"
+            )),
+            Audio::Reconnected => include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/reconnected.mp3"
+            )),
+            Audio::Won => include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/won.wav")),
+        };
+        file.to_vec()
+    }
+}
+
+/// # Audio player
+///
+/// Plays audio without blocking the application. Every `Audio` variant is
+/// debounced, so a flood of events (e.g., many players joining at once) does
+/// not stack up dozens of sounds.
+#[derive(Default)]
+pub struct AudioPlayer {
+    played_at: BTreeMap<Audio, Instant>,
+}
+
+impl AudioPlayer {
+    pub fn play(&mut self, config: &Config, audio: Audio) {
+        let now = Instant::now();
+        if self
+            .played_at
+            .get(&audio)
+            .is_some_and(|played_at| now.duration_since(*played_at) < AUDIO_DEBOUNCE)
+        {
+            return;
         }
+        self.played_at.insert(audio, now);
+        play_audio(config, audio);
     }
 }
 
 /// # Play audio
 ///
-/// Plays an MP3 file until the sound ends. The file is defined by
-/// the `Audio` variant and the user provided config.
-pub fn play_audio(config: &Config, audio: Audio) -> Result<()> {
+/// Plays an audio file on a blocking task until the sound ends. The file is
+/// defined by the `Audio` variant and the user provided config. Returns early
+/// without touching the audio device if audio is disabled.
+///
+/// Audio is optional, therefore errors (e.g., a missing audio device) are
+/// logged and never interrupt the application.
+pub fn play_audio(config: &Config, audio: Audio) {
+    if !config.general.audio_enabled {
+        return;
+    }
+
     // Get the optional user configuration path for an audio file.
     let path = match audio {
-        Audio::Reconnected => &config.audio.reconnected,
+        Audio::CountdownGo => config.audio.countdown_go.clone(),
+        Audio::CountdownTick => config.audio.countdown_tick.clone(),
+        Audio::PlayerJoined => config.audio.player_joined.clone(),
+        Audio::PlayerLeft => config.audio.player_left.clone(),
+        Audio::Reconnected => config.audio.reconnected.clone(),
+        Audio::Won => config.audio.won.clone(),
     };
 
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = try_play_audio(path, audio) {
+            error!("Error playing audio {audio}: {e}");
+        }
+    });
+}
+
+fn try_play_audio(path: Option<String>, audio: Audio) -> Result<()> {
+    let source = decode_audio(path, audio)?;
+
     // Setup the audio sink.
     let (_stream, handle) = rodio::OutputStream::try_default()?;
     let sink = rodio::Sink::try_new(&handle)?;
-
-    // Play user specified file. If no file was specified, play the default.
-    if let Some(path) = path {
-        let file = std::fs::File::open(path)?;
-        sink.append(rodio::Decoder::new(BufReader::new(file))?);
-    } else {
-        let file = std::io::Cursor::new(audio.get_asset());
-        sink.append(rodio::Decoder::new(BufReader::new(file))?);
-    };
+    sink.append(source);
 
     // Wait for the audio to end.
     sink.sleep_until_end();
 
     Ok(())
 }
+
+/// # Decode audio
+///
+/// Decodes the user specified file. If no file was specified or the file can
+/// not be read or decoded, decodes the default asset of the `Audio` variant.
+fn decode_audio(path: Option<String>, audio: Audio) -> Result<rodio::Decoder<Cursor<Vec<u8>>>> {
+    if let Some(path) = path {
+        match std::fs::read(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| Ok(rodio::Decoder::new(Cursor::new(bytes))?))
+        {
+            Ok(decoder) => return Ok(decoder),
+            Err(e) => warn!("Falling back to default audio for {audio}: {e}"),
+        }
+    }
+    Ok(rodio::Decoder::new(Cursor::new(audio.get_asset()))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUDIOS: [Audio; 6] = [
+        Audio::CountdownGo,
+        Audio::CountdownTick,
+        Audio::PlayerJoined,
+        Audio::PlayerLeft,
+        Audio::Reconnected,
+        Audio::Won,
+    ];
+
+    #[test]
+    fn default_assets_are_decodable() {
+        for audio in AUDIOS {
+            assert!(decode_audio(None, audio).is_ok(), "{audio} should decode");
+        }
+    }
+
+    #[test]
+    fn missing_user_file_falls_back_to_default() {
+        let path = std::env::temp_dir().join("keyglide_missing_audio.wav");
+        let _ = std::fs::remove_file(&path);
+
+        let result = decode_audio(Some(path.to_string_lossy().into_owned()), Audio::Won);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn undecodable_user_file_falls_back_to_default() {
+        let path = std::env::temp_dir().join("keyglide_undecodable_audio.wav");
+        std::fs::write(&path, b"not an audio file").unwrap();
+
+        let result = decode_audio(Some(path.to_string_lossy().into_owned()), Audio::Won);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_ok());
+    }
+}
@@ -1,32 +1,242 @@
-use anyhow::Result;
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use general::General;
 use key_bindings::KeyBindings;
 use serde::Deserialize;
 
 #[cfg(feature = "audio")]
 use audio::Audio;
 
+use common::constants::{MAX_PLAYER_NAME_LENGTH, MAX_START_COUNTDOWN, MIN_START_COUNTDOWN};
+
+use crate::constants::{DEFAULT_KEY_BINDING_PROFILE, MAX_EDITOR_RATIO, MIN_EDITOR_RATIO};
+
 #[cfg(feature = "audio")]
 mod audio;
+mod general;
 mod key_bindings;
+mod service;
+mod theme;
+
+pub use key_bindings::KeyBinding;
+pub use service::Service;
+pub use theme::Theme;
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+#[serde(from = "ConfigFile")]
 pub struct Config {
     #[cfg(feature = "audio")]
     pub audio: Audio,
+    pub general: General,
+    /// The key bindings of the currently active profile.
     pub key_bindings: KeyBindings,
+    /// All available key binding profiles by name. The top-level key bindings
+    /// are registered as the default profile.
+    pub key_binding_profiles: BTreeMap<String, KeyBindings>,
+    /// The colors of the user interface.
+    pub theme: Theme,
+}
+
+/// # Configuration file
+///
+/// The layout of the configuration file. Named key binding profiles are
+/// nested inside the key bindings section.
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ConfigFile {
+    #[cfg(feature = "audio")]
+    audio: Audio,
+    general: General,
+    key_bindings: KeyBindingsSection,
+    #[serde(default)]
+    theme: Theme,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct KeyBindingsSection {
+    #[serde(flatten)]
+    key_bindings: KeyBindings,
+    #[serde(default)]
+    profiles: BTreeMap<String, KeyBindings>,
+}
+
+impl From<ConfigFile> for Config {
+    fn from(file: ConfigFile) -> Self {
+        Self {
+            #[cfg(feature = "audio")]
+            audio: file.audio,
+            general: file.general,
+            key_bindings: file.key_bindings.key_bindings,
+            key_binding_profiles: file.key_bindings.profiles,
+            theme: file.theme,
+        }
+    }
 }
 
 impl Config {
     /// # Validate configuration
     ///
-    /// Checks whether there are obvious duplicates in leaf categories.
+    /// Checks whether there are obvious duplicates in leaf categories of every
+    /// key binding profile and whether the selected profile exists.
     pub fn validate(&self) -> Result<()> {
         self.key_bindings.validate()?;
+        for (name, key_bindings) in self.key_binding_profiles.iter() {
+            if name.eq(DEFAULT_KEY_BINDING_PROFILE) {
+                return Err(anyhow!(
+                    "The key binding profile name {} is reserved.",
+                    DEFAULT_KEY_BINDING_PROFILE
+                ));
+            }
+            key_bindings
+                .validate()
+                .map_err(|e| anyhow!("Key binding profile {}: {e}", name))?;
+        }
+        if let Some(ref profile) = self.general.key_binding_profile {
+            if !profile.eq(DEFAULT_KEY_BINDING_PROFILE)
+                && !self.key_binding_profiles.contains_key(profile)
+            {
+                return Err(anyhow!("Key binding profile {} does not exist.", profile));
+            }
+        }
+        if self
+            .general
+            .player_name
+            .as_ref()
+            .is_some_and(|name| name.chars().count() > MAX_PLAYER_NAME_LENGTH)
+        {
+            return Err(anyhow!(
+                "The player name can not be longer than {} characters.",
+                MAX_PLAYER_NAME_LENGTH
+            ));
+        }
+        if self.general.animation_speed == 0 {
+            return Err(anyhow!(
+                "The animation has to reveal at least one character per tick."
+            ));
+        }
+        if self.general.scramble_symbols.is_empty() {
+            return Err(anyhow!("The scramble symbols can not be empty."));
+        }
+        if self.general.max_chat_messages == 0 {
+            return Err(anyhow!("The chat has to keep at least one message."));
+        }
+        if !(MIN_START_COUNTDOWN..=MAX_START_COUNTDOWN).contains(&self.general.start_countdown) {
+            return Err(anyhow!(
+                "The start countdown has to be within {}s and {}s.",
+                MIN_START_COUNTDOWN,
+                MAX_START_COUNTDOWN
+            ));
+        }
+
+        if !(MIN_EDITOR_RATIO..=MAX_EDITOR_RATIO).contains(&self.general.editor_ratio) {
+            return Err(anyhow!(
+                "The editor ratio has to be within {} and {}.",
+                MIN_EDITOR_RATIO,
+                MAX_EDITOR_RATIO
+            ));
+        }
+        if self.general.replay_speed <= 0.0 {
+            return Err(anyhow!("The replay speed has to be positive."));
+        }
+        if self.general.tick_rate == 0 {
+            return Err(anyhow!("The tick rate has to be positive."));
+        }
+        if self.general.reconnect_interval == 0 {
+            return Err(anyhow!("The reconnect interval has to be at least 1s."));
+        }
 
         #[cfg(feature = "audio")]
         self.audio.validate()?;
 
         Ok(())
     }
+
+    /// # Load key binding profiles
+    ///
+    /// Registers the top-level key bindings as default profile and activates
+    /// the profile selected in the general section.
+    pub fn load_profiles(&mut self) -> Result<()> {
+        self.key_binding_profiles.insert(
+            DEFAULT_KEY_BINDING_PROFILE.to_string(),
+            self.key_bindings.clone(),
+        );
+        let profile = self
+            .general
+            .key_binding_profile
+            .clone()
+            .unwrap_or(DEFAULT_KEY_BINDING_PROFILE.to_string());
+        self.set_profile(&profile)
+    }
+
+    /// # Set key binding profile
+    ///
+    /// Replaces the active key bindings with the ones of the given profile.
+    pub fn set_profile(&mut self, name: &str) -> Result<()> {
+        let Some(key_bindings) = self.key_binding_profiles.get(name) else {
+            return Err(anyhow!("Key binding profile {} does not exist.", name));
+        };
+        self.key_bindings = key_bindings.clone();
+        self.general.key_binding_profile = Some(name.to_string());
+        Ok(())
+    }
+
+    /// # Active profile
+    ///
+    /// Returns the name of the currently active key binding profile.
+    pub fn active_profile(&self) -> &str {
+        self.general
+            .key_binding_profile
+            .as_deref()
+            .unwrap_or(DEFAULT_KEY_BINDING_PROFILE)
+    }
+
+    /// # Cycle key binding profile
+    ///
+    /// Activates the next or previous profile in alphabetical order.
+    pub fn cycle_profile(&mut self, forward: bool) -> Result<()> {
+        let names = self.key_binding_profiles.keys().collect::<Vec<_>>();
+        let Some(index) = names
+            .iter()
+            .position(|name| name.as_str().eq(self.active_profile()))
+        else {
+            return Err(anyhow!("The active key binding profile was not found."));
+        };
+        let index = if forward {
+            (index + 1) % names.len()
+        } else {
+            (index + names.len() - 1) % names.len()
+        };
+        let name = names[index].clone();
+        self.set_profile(&name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyCode;
+
+    use super::*;
+
+    #[test]
+    fn profiles_are_nested_in_key_bindings() {
+        let file =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/config.toml")).unwrap();
+        // Copy the default key bindings into a profile with arrow keys.
+        let key_bindings = &file[file.find("[key-bindings.").unwrap()..];
+        let profile = key_bindings
+            .replace("[key-bindings.", "[key-bindings.profiles.arrows.")
+            .replacen(r#"left = { code = "h" }"#, r#"left = { code = "Left" }"#, 1);
+        let mut config: Config = toml::from_str(&format!("{file}\n{profile}")).unwrap();
+        config.validate().unwrap();
+        config.load_profiles().unwrap();
+
+        let names: Vec<&String> = config.key_binding_profiles.keys().collect();
+        assert_eq!(names, vec!["arrows", DEFAULT_KEY_BINDING_PROFILE]);
+        assert_eq!(config.key_bindings.movement.left.code, KeyCode::Char('h'));
+
+        config.set_profile("arrows").unwrap();
+        assert_eq!(config.key_bindings.movement.left.code, KeyCode::Left);
+    }
 }
@@ -3,26 +3,42 @@ use std::path::Path;
 use anyhow::{anyhow, Result};
 use serde::Deserialize;
 
+/// File extensions of audio files the client is able to decode.
+const SUPPORTED_EXTENSIONS: [&str; 2] = ["mp3", "wav"];
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Audio {
+    pub countdown_go: Option<String>,
+    pub countdown_tick: Option<String>,
+    pub player_joined: Option<String>,
+    pub player_left: Option<String>,
     pub reconnected: Option<String>,
+    pub won: Option<String>,
 }
 
 impl Audio {
     pub fn validate(&self) -> Result<()> {
-        if let Some(ref reconnected) = self.reconnected {
-            let path = Path::new(reconnected);
+        let paths = [
+            &self.countdown_go,
+            &self.countdown_tick,
+            &self.player_joined,
+            &self.player_left,
+            &self.reconnected,
+            &self.won,
+        ];
+        for file in paths.into_iter().flatten() {
+            let path = Path::new(file);
             if !path.exists() {
                 // TODO: Change this error when working on https://github.com/tomgroenwoldt/keyglide/issues/25.
-                return Err(anyhow!("File {} does not exist...", reconnected));
+                return Err(anyhow!("File {} does not exist...", file));
             }
-            let file_extension = path
+            let is_supported = path
                 .extension()
-                .expect("Path should have a file extension.");
-            if !file_extension.eq("mp3") {
+                .is_some_and(|extension| SUPPORTED_EXTENSIONS.iter().any(|e| extension.eq(*e)));
+            if !is_supported {
                 // TODO: Change this error when working on https://github.com/tomgroenwoldt/keyglide/issues/25.
-                return Err(anyhow!("File {} is not MP3...", reconnected));
+                return Err(anyhow!("File {} is not MP3 or WAV...", file));
             }
         }
         Ok(())
@@ -0,0 +1,154 @@
+use std::{path::PathBuf, time::Duration};
+
+use ratatui::layout::Direction;
+use serde::Deserialize;
+
+use super::Service;
+use crate::constants::SYMBOLS;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct General {
+    /// The name of the key binding profile that is active on startup. If
+    /// unset, the top-level key bindings are used.
+    pub key_binding_profile: Option<String>,
+    /// The name displayed to other players. A random name is assigned if
+    /// unset.
+    pub player_name: Option<String>,
+    /// The command used to open challenge files, optionally followed by
+    /// arguments, e.g., `nvim -u NONE`. The file path is appended last.
+    #[serde(default = "default_editor")]
+    pub editor: String,
+    /// Additional arguments passed to the editor in front of the file path.
+    #[serde(default)]
+    pub editor_args: Vec<String>,
+    /// Whether to edit challenges with the built-in editor instead of an
+    /// external one.
+    #[serde(default)]
+    pub use_builtin_editor: bool,
+    /// Whether quitting the editor disconnects from the lobby instead of
+    /// restarting the editor. Editor crashes still restart it.
+    #[serde(default)]
+    pub disconnect_on_editor_exit: bool,
+    /// The number of chat messages kept per lobby. Older messages are
+    /// dropped.
+    #[serde(default = "default_max_chat_messages")]
+    pub max_chat_messages: usize,
+    /// The seconds between a start request and the start of the game in
+    /// lobbies created by the user.
+    #[serde(default = "default_start_countdown")]
+    pub start_countdown: u64,
+    /// How editor and goal are arranged when joining a lobby.
+    #[serde(default)]
+    pub default_layout: TerminalLayout,
+    /// The share of the terminal area taken by the editor when joining a
+    /// lobby. The goal takes the rest.
+    #[serde(default = "default_editor_ratio")]
+    pub editor_ratio: f64,
+    /// How much faster than recorded games are replayed.
+    #[serde(default = "default_replay_speed")]
+    pub replay_speed: f64,
+    /// Where to reach the backend service.
+    #[serde(default)]
+    pub service: Service,
+    /// The application tick rate in milliseconds. Can be overridden via the
+    /// `--tick-rate` argument.
+    #[serde(default = "default_tick_rate")]
+    pub tick_rate: u64,
+    /// Path to a file the backend service and lobby of the last session are
+    /// stored in. The next launch offers to reconnect to them. Nothing is
+    /// stored if unset.
+    pub session_file: Option<PathBuf>,
+    /// Directory the results of every finished game are written to as JSON.
+    /// Nothing is written if unset.
+    pub results_directory: Option<PathBuf>,
+    /// The seconds between two attempts to reach the backend service while
+    /// offline.
+    #[serde(default = "default_reconnect_interval")]
+    pub reconnect_interval: u64,
+    /// The number of characters the encryption animation of lobby list and
+    /// player names reveals per tick.
+    #[serde(default = "default_animation_speed")]
+    pub animation_speed: usize,
+    /// The symbols the encryption animation scrambles unrevealed characters
+    /// with.
+    #[serde(default = "default_scramble_symbols")]
+    pub scramble_symbols: String,
+    /// Whether to play audio at all. Can be overridden via the `--no-audio`
+    /// argument.
+    #[cfg(feature = "audio")]
+    #[serde(default = "enabled")]
+    pub audio_enabled: bool,
+}
+
+/// # Terminal layout
+///
+/// Whether editor and goal are stacked on top of each other (vertical) or
+/// placed side by side (horizontal).
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TerminalLayout {
+    #[default]
+    Vertical,
+    Horizontal,
+}
+
+impl General {
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.tick_rate)
+    }
+
+    pub fn reconnect_interval(&self) -> Duration {
+        Duration::from_secs(self.reconnect_interval)
+    }
+}
+
+impl From<TerminalLayout> for Direction {
+    fn from(layout: TerminalLayout) -> Self {
+        match layout {
+            TerminalLayout::Vertical => Direction::Vertical,
+            TerminalLayout::Horizontal => Direction::Horizontal,
+        }
+    }
+}
+
+fn default_editor() -> String {
+    "helix".to_string()
+}
+
+fn default_max_chat_messages() -> usize {
+    500
+}
+
+fn default_start_countdown() -> u64 {
+    10
+}
+
+fn default_editor_ratio() -> f64 {
+    0.5
+}
+
+fn default_replay_speed() -> f64 {
+    2.0
+}
+
+fn default_tick_rate() -> u64 {
+    35
+}
+
+fn default_reconnect_interval() -> u64 {
+    5
+}
+
+fn default_animation_speed() -> usize {
+    1
+}
+
+fn default_scramble_symbols() -> String {
+    SYMBOLS.to_string()
+}
+
+#[cfg(feature = "audio")]
+fn enabled() -> bool {
+    true
+}
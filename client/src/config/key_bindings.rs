@@ -5,23 +5,25 @@ use crossterm::event::KeyEvent;
 use ratatui::crossterm::event::{KeyCode, KeyModifiers};
 use serde::Deserialize;
 
-use client_derive::{CheckChildrenDuplicates, CheckDuplicates};
+use client_derive::{
+    CheckChildrenDuplicates, CheckDuplicates, ListChildrenKeyBindings, ListKeyBindings,
+};
 
-#[derive(Clone, Debug, Deserialize, CheckChildrenDuplicates)]
+#[derive(Clone, Debug, Deserialize, CheckChildrenDuplicates, ListChildrenKeyBindings)]
 #[serde(rename_all = "kebab-case")]
 pub struct KeyBindings {
     pub movement: Movement,
     pub lobby: Lobby,
     pub join: Join,
+    pub logs: Logs,
     pub popup: Popup,
     pub miscellaneous: Miscellaneous,
 }
 
 impl KeyBindings {
     pub fn validate(&self) -> Result<()> {
-        if self.children_have_duplicates() {
-            // TODO: Change this error when working on https://github.com/tomgroenwoldt/keyglide/issues/25.
-            return Err(anyhow!("Duplicate key_bindings..."));
+        if let Some(error) = self.children_have_duplicates() {
+            return Err(anyhow!(error));
         }
 
         Ok(())
@@ -56,7 +58,7 @@ impl Display for KeyBinding {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, CheckDuplicates)]
+#[derive(Clone, Debug, Deserialize, CheckDuplicates, ListKeyBindings)]
 #[serde(rename_all = "kebab-case")]
 pub struct Movement {
     pub left: KeyBinding,
@@ -65,34 +67,64 @@ pub struct Movement {
     pub up: KeyBinding,
 }
 
-#[derive(Clone, Debug, Deserialize, CheckDuplicates)]
+#[derive(Clone, Debug, Deserialize, CheckDuplicates, ListKeyBindings)]
 #[serde(rename_all = "kebab-case")]
 pub struct Miscellaneous {
     pub unfocus: KeyBinding,
     pub toggle_full_screen: KeyBinding,
+    pub toggle_editor_full_screen: KeyBinding,
+    pub command_palette: KeyBinding,
+    pub help: KeyBinding,
 }
 
-#[derive(Clone, Debug, Deserialize, CheckDuplicates)]
+#[derive(Clone, Debug, Deserialize, CheckDuplicates, ListKeyBindings)]
 #[serde(rename_all = "kebab-case")]
 pub struct Lobby {
     pub disconnect: KeyBinding,
     pub focus_chat: KeyBinding,
     pub focus_editor: KeyBinding,
     pub focus_goal: KeyBinding,
+    pub focus_diff: KeyBinding,
+    pub focus_players: KeyBinding,
     pub toggle_terminal_layout: KeyBinding,
+    pub grow_editor: KeyBinding,
+    pub shrink_editor: KeyBinding,
+    pub reset_editor: KeyBinding,
+    pub scroll_chat_bottom: KeyBinding,
+    pub scroll_chat_page_up: KeyBinding,
+    pub scroll_chat_page_down: KeyBinding,
+    pub toggle_ready: KeyBinding,
+    pub replay: KeyBinding,
     pub start: KeyBinding,
+    pub force_start: KeyBinding,
+    pub cancel_start: KeyBinding,
+    pub toggle_pause: KeyBinding,
+    pub toggle_auto_start: KeyBinding,
+    pub kick_player: KeyBinding,
+    pub transfer_ownership: KeyBinding,
+    pub rename_lobby: KeyBinding,
 }
 
-#[derive(Clone, Debug, Deserialize, CheckDuplicates)]
+#[derive(Clone, Debug, Deserialize, CheckDuplicates, ListKeyBindings)]
 #[serde(rename_all = "kebab-case")]
 pub struct Join {
     pub focus_lobby_list: KeyBinding,
+    pub search: KeyBinding,
     pub join_selected: KeyBinding,
+    pub spectate_selected: KeyBinding,
     pub quickplay: KeyBinding,
     pub create: KeyBinding,
+    pub cycle_sort_order: KeyBinding,
+    pub practice: KeyBinding,
 }
 
-#[derive(Clone, Debug, Deserialize, CheckDuplicates)]
+#[derive(Clone, Debug, Deserialize, CheckDuplicates, ListKeyBindings)]
+#[serde(rename_all = "kebab-case")]
+pub struct Logs {
+    pub focus_logs: KeyBinding,
+}
+
+#[derive(Clone, Debug, Deserialize, CheckDuplicates, ListKeyBindings)]
 #[serde(rename_all = "kebab-case")]
 pub struct Popup {
     pub confirm: KeyBinding,
@@ -163,6 +195,75 @@ impl PartialEq<KeyBinding> for KeyEvent {
         if let Some(modifiers) = other.modifiers {
             return self.modifiers == modifiers && self.code == other.code;
         }
-        self.code == other.code
+        // Key bindings without modifiers only match if no modifier is held,
+        // e.g., `q` does not match `Ctrl+q`. SHIFT is part of upper case
+        // characters and therefore ignored.
+        self.modifiers.difference(KeyModifiers::SHIFT).is_empty() && self.code == other.code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(code: KeyCode, modifiers: Option<KeyModifiers>) -> KeyBinding {
+        KeyBinding { code, modifiers }
+    }
+
+    #[test]
+    fn plain_binding_ignores_held_modifiers() {
+        let quit = binding(KeyCode::Char('q'), None);
+        assert_eq!(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE), quit);
+        assert_ne!(
+            KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL),
+            quit
+        );
+        assert_ne!(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::ALT), quit);
+        assert_ne!(
+            KeyEvent::new(
+                KeyCode::Char('q'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ),
+            quit
+        );
+    }
+
+    #[test]
+    fn plain_binding_matches_upper_case_with_shift() {
+        for character in ['R', 'X'] {
+            let upper_case = binding(KeyCode::Char(character), None);
+            assert_eq!(
+                KeyEvent::new(KeyCode::Char(character), KeyModifiers::SHIFT),
+                upper_case
+            );
+            assert_eq!(
+                KeyEvent::new(KeyCode::Char(character), KeyModifiers::NONE),
+                upper_case
+            );
+            assert_ne!(
+                KeyEvent::new(
+                    KeyCode::Char(character.to_ascii_lowercase()),
+                    KeyModifiers::SHIFT
+                ),
+                upper_case
+            );
+        }
+    }
+
+    #[test]
+    fn modifier_binding_requires_exact_modifiers() {
+        let quit = binding(KeyCode::Char('q'), Some(KeyModifiers::CONTROL));
+        assert_eq!(
+            KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL),
+            quit
+        );
+        assert_ne!(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE), quit);
+        assert_ne!(
+            KeyEvent::new(
+                KeyCode::Char('q'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ),
+            quit
+        );
     }
 }
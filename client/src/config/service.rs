@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Service {
+    /// Host and port of the backend service.
+    #[serde(default = "default_address")]
+    pub address: String,
+    /// Whether to connect via TLS, i.e., `https://` and `wss://`.
+    #[serde(default)]
+    pub secure: bool,
+}
+
+impl Default for Service {
+    fn default() -> Self {
+        Self {
+            address: default_address(),
+            secure: false,
+        }
+    }
+}
+
+impl Service {
+    /// # HTTP URL
+    ///
+    /// Builds the URL of an HTTP route of the backend service.
+    pub fn http_url(&self, path: &str) -> String {
+        let scheme = if self.secure { "https" } else { "http" };
+        format!("{scheme}://{}/{path}", self.address)
+    }
+
+    /// # WebSocket URL
+    ///
+    /// Builds the URL of a WebSocket route of the backend service.
+    pub fn ws_url(&self, path: &str) -> String {
+        let scheme = if self.secure { "wss" } else { "ws" };
+        format!("{scheme}://{}/{path}", self.address)
+    }
+}
+
+fn default_address() -> String {
+    "127.0.0.1:3030".to_string()
+}
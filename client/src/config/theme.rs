@@ -0,0 +1,63 @@
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::constants::{PROGRESS_HIGH_THRESHOLD, PROGRESS_MEDIUM_THRESHOLD};
+
+/// # Theme
+///
+/// Maps semantic roles of the user interface to colors. Colors are given by
+/// name (e.g., `light-yellow`), as indexed color (e.g., `208`) or as hex
+/// string (e.g., `#ffaf00`).
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct Theme {
+    /// The border of the currently focused component.
+    pub focus_border: Color,
+    /// The border of popups, e.g., the start timer.
+    pub popup_border: Color,
+    /// Highlighted elements, e.g., the selected tab.
+    pub highlight: Color,
+    /// Error messages.
+    pub error: Color,
+    /// Progress gauges below a third of the goal.
+    pub progress_low: Color,
+    /// Progress gauges below two thirds of the goal.
+    pub progress_medium: Color,
+    /// Progress gauges from two thirds of the goal on.
+    pub progress_high: Color,
+    /// Progress gauges of players who reached the goal.
+    pub progress_complete: Color,
+}
+
+impl Theme {
+    /// # Progress color
+    ///
+    /// Returns the color of a progress gauge for the given progress between
+    /// `0.0` and `1.0`.
+    pub fn progress_color(&self, progress: f64) -> Color {
+        if progress >= 1.0 {
+            self.progress_complete
+        } else if progress >= PROGRESS_HIGH_THRESHOLD {
+            self.progress_high
+        } else if progress >= PROGRESS_MEDIUM_THRESHOLD {
+            self.progress_medium
+        } else {
+            self.progress_low
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            focus_border: Color::Green,
+            popup_border: Color::LightYellow,
+            highlight: Color::Yellow,
+            error: Color::LightRed,
+            progress_low: Color::Red,
+            progress_medium: Color::Yellow,
+            progress_high: Color::Green,
+            progress_complete: Color::LightMagenta,
+        }
+    }
+}
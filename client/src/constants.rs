@@ -1,17 +1,83 @@
 use std::time::Duration;
 
 pub static APP_TITLE: &str = "KEYGLIDE";
-/// The height of the editor instance displaying the actual editor (the user is
-/// editing in) in percent of the whole application size.
-pub static EDITOR_HEIGHT: f64 = 0.5;
-/// The height of the editor instance displaying the goal in percent of the
-/// whole application size.
-pub static GOAL_HEIGHT: f64 = 0.5;
+/// The minimum time between two playbacks of the same sound.
+#[cfg(feature = "audio")]
+pub static AUDIO_DEBOUNCE: Duration = Duration::from_millis(500);
+/// The height of the challenge description in the lobby sidebar, including
+/// borders.
+pub static CHALLENGE_HEIGHT: u16 = 5;
+/// The height of the challenge instructions below the challenge description,
+/// including borders.
+pub static INSTRUCTIONS_HEIGHT: u16 = 6;
+/// The width and height of the command palette, excluding borders.
+pub static COMMAND_PALETTE_WIDTH: u16 = 40;
+pub static COMMAND_PALETTE_HEIGHT: u16 = 14;
+/// The name of the key binding profile holding the top-level key bindings.
+pub static DEFAULT_KEY_BINDING_PROFILE: &str = "default";
+/// Program names of helix, which differs from other editors in how it takes
+/// the initial cursor position.
+pub static HELIX_PROGRAMS: [&str; 2] = ["helix", "hx"];
+/// The width of the key binding overlay, excluding borders.
+pub static HELP_WIDTH: u16 = 42;
+/// The maximum time between two clicks on the same lobby to join it.
+pub static DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+/// The delay before restarting a crashed editor. Doubles with every
+/// consecutive crash.
+pub static EDITOR_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+/// The terminal type announced to editors, so they render colors inside the
+/// embedded terminal.
+pub static EDITOR_TERM: &str = "xterm-256color";
+/// The gauge of a player is highlighted for 1.5 seconds after he edited his
+/// file. Slightly longer than the backend announces activities, so the
+/// highlight stays while the player keeps typing.
+pub static PLAYER_ACTIVE_HIGHLIGHT: Duration = Duration::from_millis(1500);
+/// The share of the terminal area the editor grows or shrinks by per key
+/// press.
+pub static EDITOR_RATIO_STEP: f64 = 0.05;
+/// The backend is pinged every two seconds to measure the latency.
+pub static LATENCY_PING_INTERVAL: Duration = Duration::from_secs(2);
+/// Latencies up to 100ms are considered good and up to 250ms acceptable.
+pub static LATENCY_GOOD: Duration = Duration::from_millis(100);
+pub static LATENCY_ACCEPTABLE: Duration = Duration::from_millis(250);
+/// Without a pong for six seconds, the connection is considered to be
+/// dropping.
+pub static LATENCY_TIMEOUT: Duration = Duration::from_secs(6);
+/// The number of columns the logs scroll horizontally per key press.
+pub static LOGS_SCROLL_STEP: u16 = 8;
+/// The maximum number of columns the logs can be scrolled horizontally.
+pub static MAX_LOGS_OFFSET: u16 = 1000;
+/// The number of players shown on the leaderboard of the home page.
+pub static LEADERBOARD_SIZE: usize = 10;
+/// The number of rows above the first lobby of the lobby table, i.e., the
+/// top border, the header and its bottom margin.
+pub static LOBBY_TABLE_HEADER_HEIGHT: u16 = 3;
+/// The delay between attempts to reconnect to a lobby after the connection
+/// dropped.
+pub static LOBBY_RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+/// The editor takes at least 20% and at most 80% of the terminal area, so
+/// the goal stays visible.
+pub static MIN_EDITOR_RATIO: f64 = 0.2;
+pub static MAX_EDITOR_RATIO: f64 = 0.8;
+/// Below this size the application shows a hint instead of its layout.
+pub static MIN_APP_WIDTH: u16 = 60;
+pub static MIN_APP_HEIGHT: u16 = 16;
+/// The number of consecutive crashes after which an editor is not restarted
+/// anymore.
+pub static MAX_EDITOR_RESTARTS: u32 = 3;
+/// The number of attempts to reconnect to a lobby before giving up. All
+/// attempts happen within the reconnect grace period of the backend.
+pub static MAX_LOBBY_RECONNECT_ATTEMPTS: u32 = 5;
 /// Width of the sidebar in the play tab in percent of the whole application
 /// size.
 pub static PLAY_SIDE_WIDTH: f64 = 0.2;
+/// Progress gauges change their color at a third and two thirds of the goal.
+pub static PROGRESS_MEDIUM_THRESHOLD: f64 = 0.33;
+pub static PROGRESS_HIGH_THRESHOLD: f64 = 0.66;
+/// Replays keep showing the final player file for two seconds.
+pub static REPLAY_HOLD: Duration = Duration::from_secs(2);
 
-pub static RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+/// The default symbols scrambled text is drawn from.
 pub static SYMBOLS: &str = "!@#$%^&*()_+-=[]{}|;:,.<>?";
 /// Width of the terminals in percent of the whole application size.
 pub static TERMINAL_WIDTH: f64 = 0.8;
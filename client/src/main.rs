@@ -35,7 +35,22 @@ async fn main() -> Result<()> {
     init_panic_hook();
 
     // Parse arguments and configuration file.
-    let args = Args::parse();
+    let mut args = Args::parse();
+    if let Some(ref profile) = args.profile {
+        args.config.set_profile(profile)?;
+    }
+    if let Some(tick_rate) = args.tick_rate {
+        args.config.general.tick_rate = tick_rate;
+    }
+    #[cfg(feature = "audio")]
+    if args.no_audio {
+        args.config.general.audio_enabled = false;
+    }
+    // Invalid configurations never make it past the argument parsing.
+    if args.check_config {
+        println!("The configuration is valid.");
+        return Ok(());
+    }
 
     // Initialize the logger.
     set_log_file(&args.log)?;
@@ -55,8 +70,9 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create the app and run it.
+    let tick_rate = args.config.general.tick_rate();
     let mut app = App::new(args.config, terminal.size()?).await?;
-    let res = app.run(&mut terminal, args.tick_rate).await;
+    let res = app.run(&mut terminal, tick_rate).await;
 
     // Restore the terminal after app termination.
     disable_raw_mode()?;
@@ -1,5 +1,8 @@
+pub(crate) mod builtin_editor;
 pub(crate) mod chat;
+pub(crate) mod command_palette;
 pub(crate) mod connection;
+pub(crate) mod diff;
 pub(crate) mod editor;
 pub(crate) mod encryption;
 pub(crate) mod focused_component;
@@ -7,5 +10,9 @@ pub(crate) mod goal;
 pub(crate) mod join;
 pub(crate) mod lobby;
 pub(crate) mod offline;
+pub(crate) mod practice;
+pub(crate) mod replay;
+pub(crate) mod results;
+pub(crate) mod session;
 pub(crate) mod tab;
 pub(crate) mod terminal;
@@ -0,0 +1,202 @@
+use anyhow::Result;
+use common::CursorPosition;
+use log::debug;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::lobby::LobbyMessage;
+
+/// The number of spaces inserted when pressing tab.
+const TAB_WIDTH: usize = 4;
+
+/// # Built-in editor
+///
+/// A minimal text buffer used as fallback whenever no external editor is
+/// available. Every modification is directly sent as progress via the lobby
+/// channel.
+pub struct BuiltinEditor {
+    pub lines: Vec<String>,
+    /// The cursor position as row and column. The column counts characters,
+    /// not bytes.
+    pub cursor: (usize, usize),
+    pub lobby_tx: UnboundedSender<LobbyMessage>,
+}
+
+impl BuiltinEditor {
+    pub fn new(start_file: &[u8], lobby_tx: UnboundedSender<LobbyMessage>) -> Self {
+        // Splitting at newlines keeps a trailing newline as empty last line, so
+        // joining the lines again restores the exact file content.
+        let lines = String::from_utf8_lossy(start_file)
+            .split('\n')
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect();
+        Self {
+            lines,
+            cursor: (0, 0),
+            lobby_tx,
+        }
+    }
+
+    /// # Set cursor
+    ///
+    /// Moves the cursor to the given position, clamped to the buffer.
+    pub fn set_cursor(&mut self, position: CursorPosition) {
+        let row = position.line.saturating_sub(1).min(self.lines.len() - 1);
+        let column = position.column.saturating_sub(1).min(self.line_length(row));
+        self.cursor = (row, column);
+    }
+
+    /// # Contents
+    ///
+    /// Returns the current buffer as bytes.
+    pub fn contents(&self) -> Vec<u8> {
+        self.lines.join("\n").into_bytes()
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
+        debug!("Handle key event {:?}.", key);
+
+        let modified = match key.code {
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.insert(&c.to_string());
+                true
+            }
+            KeyCode::Tab => {
+                self.insert(&" ".repeat(TAB_WIDTH));
+                true
+            }
+            KeyCode::Enter => {
+                self.insert_newline();
+                true
+            }
+            KeyCode::Backspace => self.delete_backward(),
+            KeyCode::Delete => self.delete_forward(),
+            KeyCode::Left => {
+                self.move_left();
+                false
+            }
+            KeyCode::Right => {
+                self.move_right();
+                false
+            }
+            KeyCode::Up => {
+                self.move_vertically(-1);
+                false
+            }
+            KeyCode::Down => {
+                self.move_vertically(1);
+                false
+            }
+            KeyCode::Home => {
+                self.cursor.1 = 0;
+                false
+            }
+            KeyCode::End => {
+                self.cursor.1 = self.line_length(self.cursor.0);
+                false
+            }
+            _ => false,
+        };
+
+        if modified {
+            self.lobby_tx.send(LobbyMessage::SendProgress {
+                progress: self.contents(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn line_length(&self, row: usize) -> usize {
+        self.lines.get(row).map_or(0, |line| line.chars().count())
+    }
+
+    /// Converts the cursor column of the current line into a byte index.
+    fn byte_index(&self) -> usize {
+        let (row, col) = self.cursor;
+        self.lines[row]
+            .char_indices()
+            .nth(col)
+            .map_or(self.lines[row].len(), |(i, _)| i)
+    }
+
+    fn insert(&mut self, text: &str) {
+        let index = self.byte_index();
+        self.lines[self.cursor.0].insert_str(index, text);
+        self.cursor.1 += text.chars().count();
+    }
+
+    fn insert_newline(&mut self) {
+        let index = self.byte_index();
+        let rest = self.lines[self.cursor.0].split_off(index);
+        self.lines.insert(self.cursor.0 + 1, rest);
+        self.cursor = (self.cursor.0 + 1, 0);
+    }
+
+    /// Deletes the character before the cursor or joins the current line with
+    /// the previous one. Returns whether the buffer changed.
+    fn delete_backward(&mut self) -> bool {
+        let (row, col) = self.cursor;
+        if col > 0 {
+            self.cursor.1 -= 1;
+            let index = self.byte_index();
+            self.lines[row].remove(index);
+            true
+        } else if row > 0 {
+            let line = self.lines.remove(row);
+            let previous_length = self.line_length(row - 1);
+            self.lines[row - 1].push_str(&line);
+            self.cursor = (row - 1, previous_length);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Deletes the character at the cursor or joins the next line with the
+    /// current one. Returns whether the buffer changed.
+    fn delete_forward(&mut self) -> bool {
+        let (row, col) = self.cursor;
+        if col < self.line_length(row) {
+            let index = self.byte_index();
+            self.lines[row].remove(index);
+            true
+        } else if row + 1 < self.lines.len() {
+            let line = self.lines.remove(row + 1);
+            self.lines[row].push_str(&line);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn move_left(&mut self) {
+        let (row, col) = self.cursor;
+        if col > 0 {
+            self.cursor.1 -= 1;
+        } else if row > 0 {
+            self.cursor = (row - 1, self.line_length(row - 1));
+        }
+    }
+
+    fn move_right(&mut self) {
+        let (row, col) = self.cursor;
+        if col < self.line_length(row) {
+            self.cursor.1 += 1;
+        } else if row + 1 < self.lines.len() {
+            self.cursor = (row + 1, 0);
+        }
+    }
+
+    fn move_vertically(&mut self, offset: isize) {
+        let row = self
+            .cursor
+            .0
+            .saturating_add_signed(offset)
+            .min(self.lines.len() - 1);
+        self.cursor = (row, self.cursor.1.min(self.line_length(row)));
+    }
+}
@@ -9,33 +9,123 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use super::lobby::LobbyMessage;
 
+/// Usage of all chat commands, printed by `/help`.
+const CHAT_COMMANDS_HELP: [&str; 4] = [
+    "Available commands:",
+    "  /help           Show this help",
+    "  /me <action>    Describe an action",
+    "  /clear          Clear the chat",
+];
+
+/// # Chat command
+///
+/// The parsed chat input. Input starting with `/` is a command that is handled
+/// locally, everything else is sent to the lobby as is.
+#[derive(Debug, PartialEq)]
+pub enum ChatCommand {
+    /// A plain message sent to the lobby.
+    Message(String),
+    /// Describes an action of the player, e.g., `/me waves`.
+    Emote(String),
+    /// Prints the available commands into the chat.
+    Help,
+    /// Empties the local chat.
+    Clear,
+    /// An unknown or malformed command. Holds the error shown in the chat.
+    Invalid(String),
+}
+
+/// # Parse chat command
+///
+/// Parses the chat input into a `ChatCommand`.
+pub fn parse_chat_command(input: &str) -> ChatCommand {
+    let Some(command) = input.strip_prefix('/') else {
+        return ChatCommand::Message(input.to_string());
+    };
+    let (name, argument) = command
+        .split_once(char::is_whitespace)
+        .map_or((command, ""), |(name, argument)| (name, argument.trim()));
+
+    match name {
+        "help" => ChatCommand::Help,
+        "clear" => ChatCommand::Clear,
+        "me" if argument.is_empty() => ChatCommand::Invalid("Usage: /me <action>".to_string()),
+        "me" => ChatCommand::Emote(argument.to_string()),
+        _ => ChatCommand::Invalid(format!(
+            "Unknown command /{name}. Type /help for a list of commands."
+        )),
+    }
+}
+
 pub struct Chat {
     pub messages: Vec<String>,
+    /// The number of messages kept in the chat. Older messages are dropped.
+    pub max_messages: usize,
     pub input: String,
     pub message_tx: UnboundedSender<LobbyMessage>,
     pub state: TableState,
+    /// The number of rows the chat showed when it was last drawn. Paging
+    /// moves the selection by this amount.
+    pub page_height: usize,
 }
 
 impl Chat {
-    pub fn new(message_tx: UnboundedSender<LobbyMessage>) -> Self {
+    pub fn new(message_tx: UnboundedSender<LobbyMessage>, max_messages: usize) -> Self {
         Self {
             messages: Vec::new(),
+            max_messages,
             input: String::new(),
             message_tx,
             state: TableState::default(),
+            page_height: 1,
         }
     }
 
     pub fn add_message(&mut self, message: String) {
         debug!("Add message '{message}' to chat.");
+        // Only follow new messages if the user is not reading older ones.
+        let at_bottom = self
+            .state
+            .selected()
+            .is_none_or(|i| i + 1 >= self.messages.len());
         self.messages.push(message);
-        self.state.scroll_down_by(1);
+        if at_bottom {
+            self.scroll_to_bottom();
+        }
+
+        // Drop the oldest messages and shift the selection accordingly, so the
+        // viewed messages stay in place.
+        let overflow = self.messages.len().saturating_sub(self.max_messages);
+        if overflow > 0 {
+            self.messages.drain(..overflow);
+            let selected = self.state.selected().map(|i| {
+                i.saturating_sub(overflow)
+                    .min(self.messages.len().saturating_sub(1))
+            });
+            self.state.select(selected);
+            *self.state.offset_mut() = self.state.offset().saturating_sub(overflow);
+        }
+    }
+
+    /// # Scroll to bottom
+    ///
+    /// Selects the newest message.
+    pub fn scroll_to_bottom(&mut self) {
+        self.state.select(self.messages.len().checked_sub(1));
+    }
+
+    /// # Clear
+    ///
+    /// Removes all messages from the local chat.
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.state = TableState::default();
     }
 
     pub fn next(&mut self) {
         let i = match self.state.selected() {
             Some(i) => {
-                if i >= self.messages.len() - 1 {
+                if i >= self.messages.len().saturating_sub(1) {
                     return;
                 } else {
                     i + 1
@@ -60,26 +150,163 @@ impl Chat {
         self.state.select(Some(i));
     }
 
+    /// # Next page
+    ///
+    /// Moves the selection one page towards the newest message.
+    pub fn next_page(&mut self) {
+        let last = self.messages.len().saturating_sub(1);
+        let i = self
+            .state
+            .selected()
+            .map_or(0, |i| (i + self.page_height).min(last));
+        self.state.select(Some(i));
+    }
+
+    /// # Previous page
+    ///
+    /// Moves the selection one page towards the oldest message.
+    pub fn previous_page(&mut self) {
+        let i = self
+            .state
+            .selected()
+            .map_or(0, |i| i.saturating_sub(self.page_height));
+        self.state.select(Some(i));
+    }
+
     pub fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
         debug!("Handle key event {:?}.", key);
 
         match key.code {
-            KeyCode::Char(input) => {
-                if self.input.len() < MAX_CHAT_MESSAGE_LENGTH {
-                    self.input.push(input);
-                }
+            KeyCode::Char(input) if self.input.len() < MAX_CHAT_MESSAGE_LENGTH => {
+                self.input.push(input);
             }
             KeyCode::Backspace => {
                 self.input.pop();
             }
             KeyCode::Enter => {
-                self.message_tx.send(LobbyMessage::SendMessage {
-                    message: self.input.clone(),
-                })?;
-                self.input = String::new();
+                let input = std::mem::take(&mut self.input);
+                match parse_chat_command(&input) {
+                    ChatCommand::Message(message) => {
+                        self.message_tx
+                            .send(LobbyMessage::SendMessage { message })?;
+                    }
+                    ChatCommand::Emote(action) => {
+                        self.message_tx.send(LobbyMessage::SendMessage {
+                            message: format!("*{action}*"),
+                        })?;
+                    }
+                    ChatCommand::Help => {
+                        for line in CHAT_COMMANDS_HELP {
+                            self.add_message(line.to_string());
+                        }
+                    }
+                    ChatCommand::Clear => self.clear(),
+                    ChatCommand::Invalid(error) => self.add_message(error),
+                }
             }
             _ => {}
         };
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+
+    fn new_chat(max_messages: usize) -> Chat {
+        let (message_tx, _message_rx) = unbounded_channel();
+        Chat::new(message_tx, max_messages)
+    }
+
+    #[test]
+    fn plain_input_is_a_message() {
+        assert_eq!(
+            parse_chat_command("hello /me"),
+            ChatCommand::Message("hello /me".to_string())
+        );
+        assert_eq!(parse_chat_command(""), ChatCommand::Message(String::new()));
+    }
+
+    #[test]
+    fn known_commands_are_parsed() {
+        assert_eq!(parse_chat_command("/help"), ChatCommand::Help);
+        assert_eq!(parse_chat_command("/clear"), ChatCommand::Clear);
+        assert_eq!(
+            parse_chat_command("/me waves"),
+            ChatCommand::Emote("waves".to_string())
+        );
+    }
+
+    #[test]
+    fn arguments_are_trimmed() {
+        assert_eq!(
+            parse_chat_command("/me   waves at everyone  "),
+            ChatCommand::Emote("waves at everyone".to_string())
+        );
+        assert_eq!(
+            parse_chat_command("/me   "),
+            ChatCommand::Invalid("Usage: /me <action>".to_string())
+        );
+        assert_eq!(
+            parse_chat_command("/me"),
+            ChatCommand::Invalid("Usage: /me <action>".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_commands_are_invalid() {
+        assert_eq!(
+            parse_chat_command("/dance now"),
+            ChatCommand::Invalid(
+                "Unknown command /dance. Type /help for a list of commands.".to_string()
+            )
+        );
+        assert!(matches!(parse_chat_command("/"), ChatCommand::Invalid(_)));
+    }
+
+    #[test]
+    fn chat_keeps_newest_messages() {
+        let mut chat = new_chat(500);
+        for i in 0..1000 {
+            chat.add_message(format!("Message {i}"));
+        }
+
+        assert_eq!(chat.messages.len(), 500);
+        assert_eq!(chat.messages.first().unwrap(), "Message 500");
+        assert_eq!(chat.messages.last().unwrap(), "Message 999");
+        // The selection follows the newest message.
+        assert_eq!(chat.state.selected(), Some(499));
+        chat.next();
+        assert_eq!(chat.state.selected(), Some(499));
+        chat.next_page();
+        assert_eq!(chat.state.selected(), Some(499));
+    }
+
+    #[test]
+    fn selection_stays_on_viewed_message_while_trimming() {
+        let mut chat = new_chat(500);
+        for i in 0..500 {
+            chat.add_message(format!("Message {i}"));
+        }
+        chat.state.select(Some(300));
+
+        for i in 500..600 {
+            chat.add_message(format!("Message {i}"));
+        }
+
+        assert_eq!(chat.messages.len(), 500);
+        assert_eq!(chat.state.selected(), Some(200));
+        assert_eq!(chat.messages[200], "Message 300");
+
+        // Trimming beyond the viewed message keeps the selection in bounds.
+        for i in 600..1000 {
+            chat.add_message(format!("Message {i}"));
+        }
+        assert_eq!(chat.state.selected(), Some(0));
+        chat.previous();
+        assert_eq!(chat.state.selected(), Some(0));
+    }
+}
@@ -0,0 +1,267 @@
+use common::LobbyStatus;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::{connection::Connection, focused_component::ComponentKind};
+use crate::config::{Config, KeyBinding};
+
+/// # Command
+///
+/// An action listed in the command palette. Running it replays its key
+/// binding, so it behaves exactly like pressing the key.
+#[derive(Clone, Debug)]
+pub struct Command {
+    pub name: &'static str,
+    pub key_binding: KeyBinding,
+    /// The component that handles the key binding. Without one, the key
+    /// binding is handled by the current tab.
+    pub focus: Option<ComponentKind>,
+}
+
+impl Command {
+    fn new(name: &'static str, key_binding: &KeyBinding, focus: Option<ComponentKind>) -> Self {
+        Self {
+            name,
+            key_binding: key_binding.clone(),
+            focus,
+        }
+    }
+
+    /// # Key event
+    ///
+    /// Returns the key event triggering this command.
+    pub fn key_event(&self) -> KeyEvent {
+        KeyEvent::new(
+            self.key_binding.code,
+            self.key_binding.modifiers.unwrap_or(KeyModifiers::NONE),
+        )
+    }
+}
+
+/// # Command palette
+///
+/// Lists all commands available in the current context and filters them by
+/// the query the user types.
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    /// The index of the selected command among the matching commands.
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    /// # Handle key event
+    ///
+    /// Edits the query and moves the selection. Returns the selected command
+    /// once the user confirms it.
+    pub fn handle_key_event(&mut self, key: KeyEvent, commands: Vec<Command>) -> Option<Command> {
+        let mut matches = self.matching_commands(commands);
+        match key.code {
+            KeyCode::Enter if self.selected < matches.len() => {
+                return Some(matches.swap_remove(self.selected));
+            }
+            KeyCode::Down if self.selected + 1 < matches.len() => self.selected += 1,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Char(input) => {
+                self.query.push(input);
+                self.selected = 0;
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// # Matching commands
+    ///
+    /// Returns the commands whose name contains the characters of the query
+    /// in the same order, ignoring case.
+    pub fn matching_commands(&self, commands: Vec<Command>) -> Vec<Command> {
+        let query = self.query.to_lowercase();
+        commands
+            .into_iter()
+            .filter(|command| {
+                let mut name = command.name.chars().flat_map(char::to_lowercase);
+                query.chars().all(|c| name.any(|n| n == c))
+            })
+            .collect()
+    }
+}
+
+/// # Commands
+///
+/// Returns the commands available for the given connection. Mirrors the
+/// conditions of the key binding handlers, e.g., owner commands are only
+/// listed for the lobby owner.
+pub fn commands(connection: &Connection, config: &Config) -> Vec<Command> {
+    let join_keys = &config.key_bindings.join;
+    let lobby_keys = &config.key_bindings.lobby;
+    let toggle_editor_full_screen = &config.key_bindings.miscellaneous.toggle_editor_full_screen;
+    let mut commands = vec![];
+
+    match connection {
+        Connection::Join(join) => {
+            commands.push(Command::new(
+                "Browse lobbies",
+                &join_keys.focus_lobby_list,
+                None,
+            ));
+            commands.push(Command::new("Search lobbies", &join_keys.search, None));
+            if join.selected_lobby.is_some() {
+                commands.push(Command::new(
+                    "Join selected lobby",
+                    &join_keys.join_selected,
+                    Some(ComponentKind::Lobbies),
+                ));
+                commands.push(Command::new(
+                    "Spectate selected lobby",
+                    &join_keys.spectate_selected,
+                    Some(ComponentKind::Lobbies),
+                ));
+            }
+            commands.push(Command::new(
+                "Quickplay",
+                &join_keys.quickplay,
+                Some(ComponentKind::Lobbies),
+            ));
+            commands.push(Command::new(
+                "Create lobby",
+                &join_keys.create,
+                Some(ComponentKind::Lobbies),
+            ));
+            commands.push(Command::new(
+                "Cycle sort order",
+                &join_keys.cycle_sort_order,
+                Some(ComponentKind::Lobbies),
+            ));
+            commands.push(Command::new("Practice", &join_keys.practice, None));
+        }
+        Connection::Lobby(lobby) => {
+            let is_owner = lobby.owner == lobby.local_player && lobby.local_player.is_some();
+            if let LobbyStatus::AboutToStart(_) = lobby.status {
+                if is_owner {
+                    commands.push(Command::new("Cancel start", &lobby_keys.cancel_start, None));
+                    commands.push(Command::new(
+                        "Toggle automatic start",
+                        &lobby_keys.toggle_auto_start,
+                        None,
+                    ));
+                }
+                if lobby.auto_start && !lobby.spectating {
+                    commands.push(Command::new("Toggle ready", &lobby_keys.toggle_ready, None));
+                }
+                commands.push(Command::new("Disconnect", &lobby_keys.disconnect, None));
+                return commands;
+            }
+            if !lobby.spectating {
+                commands.push(Command::new("Focus editor", &lobby_keys.focus_editor, None));
+                commands.push(Command::new(
+                    "Toggle full screen editor",
+                    toggle_editor_full_screen,
+                    None,
+                ));
+            }
+            commands.push(Command::new("Focus goal", &lobby_keys.focus_goal, None));
+            if !lobby.spectating {
+                commands.push(Command::new("Focus diff", &lobby_keys.focus_diff, None));
+            }
+            commands.push(Command::new(
+                "Focus players",
+                &lobby_keys.focus_players,
+                None,
+            ));
+            commands.push(Command::new("Focus chat", &lobby_keys.focus_chat, None));
+            commands.push(Command::new(
+                "Jump to newest message",
+                &lobby_keys.scroll_chat_bottom,
+                None,
+            ));
+            commands.push(Command::new(
+                "Page chat up",
+                &lobby_keys.scroll_chat_page_up,
+                None,
+            ));
+            commands.push(Command::new(
+                "Page chat down",
+                &lobby_keys.scroll_chat_page_down,
+                None,
+            ));
+            commands.push(Command::new(
+                "Toggle layout",
+                &lobby_keys.toggle_terminal_layout,
+                None,
+            ));
+            commands.push(Command::new("Grow editor", &lobby_keys.grow_editor, None));
+            commands.push(Command::new(
+                "Shrink editor",
+                &lobby_keys.shrink_editor,
+                None,
+            ));
+            if lobby.can_reset_editor() {
+                commands.push(Command::new("Reset editor", &lobby_keys.reset_editor, None));
+            }
+            if lobby.recording.is_some()
+                && !matches!(
+                    lobby.status,
+                    LobbyStatus::InProgress(_) | LobbyStatus::Paused { .. }
+                )
+            {
+                commands.push(Command::new("Replay last game", &lobby_keys.replay, None));
+            }
+            if lobby.status == LobbyStatus::WaitingForPlayers {
+                if !lobby.spectating {
+                    commands.push(Command::new("Toggle ready", &lobby_keys.toggle_ready, None));
+                }
+                if is_owner {
+                    commands.push(Command::new("Start", &lobby_keys.start, None));
+                    commands.push(Command::new("Force start", &lobby_keys.force_start, None));
+                    commands.push(Command::new(
+                        "Toggle automatic start",
+                        &lobby_keys.toggle_auto_start,
+                        None,
+                    ));
+                }
+            }
+            if is_owner
+                && matches!(
+                    lobby.status,
+                    LobbyStatus::InProgress(_) | LobbyStatus::Paused { .. }
+                )
+            {
+                commands.push(Command::new("Toggle pause", &lobby_keys.toggle_pause, None));
+            }
+            if is_owner {
+                commands.push(Command::new("Rename lobby", &lobby_keys.rename_lobby, None));
+            }
+            commands.push(Command::new("Disconnect", &lobby_keys.disconnect, None));
+        }
+        Connection::Practice(_) => {
+            commands.push(Command::new("Focus editor", &lobby_keys.focus_editor, None));
+            commands.push(Command::new(
+                "Toggle full screen editor",
+                toggle_editor_full_screen,
+                None,
+            ));
+            commands.push(Command::new("Focus goal", &lobby_keys.focus_goal, None));
+            commands.push(Command::new(
+                "Toggle layout",
+                &lobby_keys.toggle_terminal_layout,
+                None,
+            ));
+            commands.push(Command::new("Grow editor", &lobby_keys.grow_editor, None));
+            commands.push(Command::new(
+                "Shrink editor",
+                &lobby_keys.shrink_editor,
+                None,
+            ));
+            commands.push(Command::new("Start over", &lobby_keys.reset_editor, None));
+            commands.push(Command::new("End practice", &lobby_keys.disconnect, None));
+        }
+        Connection::Offline(_) => {
+            commands.push(Command::new("Practice", &join_keys.practice, None));
+        }
+    }
+    commands
+}
@@ -1,14 +1,29 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use anyhow::Result;
-use log::error;
-use tokio::sync::mpsc::UnboundedSender;
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use log::{error, warn};
+use tokio::{net::TcpStream, sync::mpsc::UnboundedSender, time::timeout};
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use common::constants::{HEARTBEAT_INTERVAL, HEARTBEAT_TIMEOUT};
+
+use ratatui::layout::Direction;
 
-use super::{join::Join, lobby::Lobby, offline::Offline};
-use crate::app::AppMessage;
+use super::{
+    editor::Editor, goal::Goal, join::Join, lobby::Lobby, offline::Offline, practice::Practice,
+};
+use crate::{app::AppMessage, config::Config};
 
 pub enum Connection {
-    Join(Join),
-    Lobby(Lobby),
+    Join(Box<Join>),
+    Lobby(Box<Lobby>),
     Offline(Offline),
+    /// A local practice round that needs no backend.
+    Practice(Box<Practice>),
 }
 
 impl Connection {
@@ -18,16 +33,116 @@ impl Connection {
     /// `Connection::Offline` variant and spawns a task that tries to reconnect
     /// continously.
     /// Notifies the application on a successful reconnect.
-    pub async fn new(app_tx: UnboundedSender<AppMessage>) -> Result<Self> {
-        let connection = match Join::new(app_tx.clone()).await {
-            Ok(join) => Connection::Join(join),
+    pub async fn new(app_tx: UnboundedSender<AppMessage>, config: &Config) -> Result<Self> {
+        let service = config.general.service.clone();
+        let connection = match Join::new(app_tx.clone(), &service, config.general.animation_speed)
+            .await
+        {
+            Ok(join) => Connection::Join(Box::new(join)),
             Err(e) => {
                 error!("Error connecting to backend service: {e}.");
 
-                let offline = Offline::new(app_tx);
+                let offline = Offline::new(app_tx, service, config.general.reconnect_interval());
                 Connection::Offline(offline)
             }
         };
         Ok(connection)
     }
+
+    /// # Editor
+    ///
+    /// Returns the editor of the current lobby or practice round together
+    /// with the layout direction and the editor ratio of the terminals.
+    pub fn editor_mut(&mut self) -> Option<(&mut Editor, Direction, f64)> {
+        match self {
+            Connection::Lobby(lobby) => {
+                let direction = lobby.terminal_layout_direction;
+                let editor_ratio = lobby.editor_ratio;
+                lobby
+                    .editor
+                    .as_mut()
+                    .map(|editor| (editor, direction, editor_ratio))
+            }
+            Connection::Practice(practice) => Some((
+                &mut practice.editor,
+                practice.terminal_layout_direction,
+                practice.editor_ratio,
+            )),
+            Connection::Join(_) | Connection::Offline(_) => None,
+        }
+    }
+
+    /// # Goal
+    ///
+    /// Returns the goal of the current lobby or practice round together with
+    /// the layout direction and the editor ratio of the terminals.
+    pub fn goal_mut(&mut self) -> Option<(&mut Goal, Direction, f64)> {
+        match self {
+            Connection::Lobby(lobby) => Some((
+                &mut lobby.goal,
+                lobby.terminal_layout_direction,
+                lobby.editor_ratio,
+            )),
+            Connection::Practice(practice) => Some((
+                &mut practice.goal,
+                practice.terminal_layout_direction,
+                practice.editor_ratio,
+            )),
+            Connection::Join(_) | Connection::Offline(_) => None,
+        }
+    }
+}
+
+/// # Next backend message
+///
+/// Waits for the next message of the backend, skipping heartbeat frames.
+/// Pings are answered automatically, pongs report the latency to the app.
+/// Returns `None` once the connection closed or the backend stayed silent for
+/// longer than the heartbeat allows.
+pub async fn next_backend_message(
+    ws_rx: &mut SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    app_tx: &UnboundedSender<AppMessage>,
+) -> Option<Message> {
+    loop {
+        let msg = match timeout(HEARTBEAT_INTERVAL + HEARTBEAT_TIMEOUT, ws_rx.next()).await {
+            Ok(Some(Ok(msg))) => msg,
+            Ok(_) => return None,
+            Err(_) => {
+                warn!("Backend service missed its heartbeat.");
+                return None;
+            }
+        };
+        if let Message::Pong(ref payload) = msg {
+            if let Some(latency) = round_trip_time(payload) {
+                let _ = app_tx.send(AppMessage::Latency(latency));
+            }
+            continue;
+        }
+        if msg.is_ping() {
+            continue;
+        }
+        return Some(msg);
+    }
+}
+
+/// # Send ping
+///
+/// Pings the backend with the current time as payload. The backend echoes the
+/// payload in its pong, which allows to measure the round trip time.
+pub async fn send_ping(ws_tx: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>) {
+    let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+        return;
+    };
+    let payload = (now.as_millis() as u64).to_be_bytes().to_vec();
+    if let Err(e) = ws_tx.send(Message::Ping(payload)).await {
+        warn!("Unable to ping the backend service: {e}");
+    }
+}
+
+/// Returns the time passed since the ping carrying the given payload was
+/// sent.
+fn round_trip_time(payload: &[u8]) -> Option<Duration> {
+    let sent_at = u64::from_be_bytes(payload.try_into().ok()?);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+    Some(now.saturating_sub(Duration::from_millis(sent_at)))
 }
@@ -0,0 +1,103 @@
+/// # Diff line
+///
+/// A single line of the unified diff between the player file and the goal.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiffLine {
+    /// The line is already part of both files.
+    Equal(String),
+    /// The line is missing from the player file.
+    Added(String),
+    /// The line has to be removed from the player file.
+    Removed(String),
+}
+
+/// # Diff
+///
+/// The differences between the player file and the goal file. Is recomputed
+/// whenever the player file changes.
+#[derive(Debug, Default)]
+pub struct Diff {
+    pub lines: Vec<DiffLine>,
+    /// The number of lines scrolled down from the top.
+    pub offset: usize,
+}
+
+impl Diff {
+    /// # Update
+    ///
+    /// Recomputes the diff of the current player file against the goal file.
+    pub fn update(&mut self, current: &[u8], goal: &[u8]) {
+        self.lines = diff_lines(current, goal);
+        self.offset = self.offset.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// # Has changes
+    ///
+    /// Returns whether the player file differs from the goal file.
+    pub fn has_changes(&self) -> bool {
+        self.lines
+            .iter()
+            .any(|line| !matches!(line, DiffLine::Equal(_)))
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.offset = self.offset.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.offset + 1 < self.lines.len() {
+            self.offset += 1;
+        }
+    }
+}
+
+/// # Diff lines
+///
+/// Computes a line based diff that turns the current file into the goal file
+/// via the longest common subsequence of both.
+fn diff_lines(current: &[u8], goal: &[u8]) -> Vec<DiffLine> {
+    let current = String::from_utf8_lossy(current);
+    let goal = String::from_utf8_lossy(goal);
+    let current: Vec<&str> = current.lines().collect();
+    let goal: Vec<&str> = goal.lines().collect();
+
+    // lengths[i][j] holds the length of the longest common subsequence of
+    // current[i..] and goal[j..].
+    let mut lengths = vec![vec![0usize; goal.len() + 1]; current.len() + 1];
+    for i in (0..current.len()).rev() {
+        for j in (0..goal.len()).rev() {
+            lengths[i][j] = if current[i] == goal[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(current.len().max(goal.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < current.len() && j < goal.len() {
+        if current[i] == goal[j] {
+            lines.push(DiffLine::Equal(current[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            lines.push(DiffLine::Removed(current[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(goal[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(
+        current[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    lines.extend(
+        goal[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+    lines
+}
@@ -1,61 +1,140 @@
 use std::{
-    env,
-    fs::{self, File},
-    io::Write,
+    fs,
     path::{Path, PathBuf},
 };
 
-use anyhow::{anyhow, Result};
-use log::{error, warn};
+use anyhow::Result;
+use common::{ContentType, CursorPosition};
+use log::{error, info, warn};
 use notify::{
     event::ModifyKind, Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
 };
-use portable_pty::{Child, CommandBuilder};
-use ratatui::layout::{Direction, Size};
+use portable_pty::Child;
+use ratatui::{
+    crossterm::event::KeyEvent,
+    layout::{Direction, Size},
+};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use uuid::Uuid;
 
-use super::terminal::Terminal;
-use crate::{
-    constants::{EDITOR_HEIGHT, TERMINAL_WIDTH},
-    schema::lobby::LobbyMessage,
+use super::{
+    builtin_editor::BuiltinEditor,
+    terminal::{self, pane_size, remove_challenge_file, ChallengeFile, Terminal},
 };
+use crate::schema::lobby::LobbyMessage;
+
+pub enum EditorKind {
+    /// The users editor running inside a PTY instance.
+    Terminal(Terminal),
+    /// The built-in text buffer.
+    Builtin(BuiltinEditor),
+    /// Shown in place of an editor for challenge files that are no text.
+    Unsupported,
+}
 
 pub struct Editor {
-    pub terminal: Terminal,
+    pub kind: EditorKind,
     pub is_full_screen: bool,
+    /// Why the external editor could not be started, if the built-in editor
+    /// is used as a fallback.
+    pub fallback_reason: Option<String>,
+    /// The file opened by the external editor. Is removed on drop.
+    pub file_path: Option<PathBuf>,
 }
 
 impl Editor {
     /// # Create a new editor
     ///
     /// Starts a new editor inside a PTY instance that opens up the start file
-    /// of the current lobby.
+    /// of the current lobby at the given cursor position. Falls back to the
+    /// built-in editor if requested or if the external editor is not
+    /// available.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         app_size: Size,
         lobby_tx: UnboundedSender<LobbyMessage>,
-        start_file: Vec<u8>,
+        start_file: ChallengeFile,
+        cursor: Option<CursorPosition>,
         is_full_screen: bool,
+        use_builtin_editor: bool,
+        editor_command: &str,
+        editor_args: &[String],
     ) -> Result<Self> {
-        // Get the temporary directory.
-        let mut temp_dir = env::temp_dir();
-        temp_dir.push("keyglide_challenge");
+        // Send the untouched start file directly, as it may already partially
+        // match the goal file.
+        lobby_tx.send(LobbyMessage::SendProgress {
+            progress: start_file.content.clone(),
+        })?;
 
-        // Create the directory.
-        if let Err(e) = fs::create_dir_all(&temp_dir) {
-            return Err(anyhow!("Failed to create folder: {e}"));
+        let mut fallback_reason = None;
+        let mut file_path = None;
+        let mut kind = if start_file.content_type == ContentType::Binary {
+            warn!("Not opening the binary start file in an editor.");
+            EditorKind::Unsupported
+        } else if use_builtin_editor {
+            EditorKind::Builtin(BuiltinEditor::new(&start_file.content, lobby_tx))
+        } else {
+            match Editor::spawn_terminal(
+                app_size,
+                lobby_tx.clone(),
+                &start_file,
+                cursor,
+                editor_command,
+                editor_args,
+            ) {
+                Ok((terminal, path)) => {
+                    file_path = Some(path);
+                    EditorKind::Terminal(terminal)
+                }
+                Err(e) => {
+                    warn!("Unable to start external editor, using built-in editor: {e}");
+                    fallback_reason = Some(e.to_string());
+                    EditorKind::Builtin(BuiltinEditor::new(&start_file.content, lobby_tx))
+                }
+            }
+        };
+        if let (EditorKind::Builtin(ref mut editor), Some(cursor)) = (&mut kind, cursor) {
+            editor.set_cursor(cursor);
         }
+
+        Ok(Self {
+            kind,
+            is_full_screen,
+            fallback_reason,
+            file_path,
+        })
+    }
+
+    /// # Spawn terminal
+    ///
+    /// Writes the start file to a temporary location, watches it for progress
+    /// and opens it inside a PTY instance.
+    fn spawn_terminal(
+        app_size: Size,
+        lobby_tx: UnboundedSender<LobbyMessage>,
+        start_file: &ChallengeFile,
+        cursor: Option<CursorPosition>,
+        editor_command: &str,
+        editor_args: &[String],
+    ) -> Result<(Terminal, PathBuf)> {
+        // Use a unique file name inside the directory of the lobby.
+        let temp_dir = start_file.directory.clone();
+        let file_path = start_file.path(&Uuid::new_v4().to_string());
+
+        // Build the command that opens the new start file.
+        let cmd = terminal::editor_command(editor_command, editor_args, &file_path, cursor)?;
+
         // Write the start file bytes to file.
-        let mut file_path = temp_dir.clone();
-        file_path.push(Uuid::new_v4().to_string());
+        start_file.write(&file_path)?;
 
-        let mut file = match File::create(&file_path) {
-            Ok(file) => file,
-            Err(e) => return Err(anyhow!("Error creating file: {e}")),
+        // Build the terminal and resize it directly.
+        let (terminal, child) = match Terminal::new(app_size, cmd) {
+            Ok(terminal) => terminal,
+            Err(e) => {
+                remove_challenge_file(&file_path);
+                return Err(e);
+            }
         };
-        if let Err(e) = file.write_all(&start_file) {
-            return Err(anyhow!("Error writing to file: {e}"));
-        }
 
         tokio::spawn(watch_progress(
             temp_dir,
@@ -63,21 +142,11 @@ impl Editor {
             lobby_tx.clone(),
         ));
 
-        // Build the command that opens the new start file.
-        let mut cmd = CommandBuilder::new("helix");
-        cmd.arg(&file_path);
-
-        // Build the terminal and resize it directly.
-        let (terminal, child) = Terminal::new(app_size, cmd)?;
-
         // Spawn a task that messages the application after our editor instance
         // terminates and kills the terminal process on app close.
         tokio::spawn(Editor::handle_termination(child, lobby_tx));
 
-        Ok(Self {
-            terminal,
-            is_full_screen,
-        })
+        Ok((terminal, file_path))
     }
 
     /// # Handle termination
@@ -88,33 +157,63 @@ impl Editor {
         mut child: Box<dyn Child + Send + Sync>,
         lobby_tx: UnboundedSender<LobbyMessage>,
     ) -> Result<()> {
-        child.wait()?;
-        warn!("The editor process terminated.");
-        lobby_tx.send(LobbyMessage::EditorTerminated)?;
+        let status = child.wait()?;
+        // Clean exits are regular quits, everything else is a crash.
+        if status.success() {
+            info!("The editor process exited with status: {status}");
+        } else {
+            warn!("The editor process terminated with status: {status}");
+        }
+        lobby_tx.send(LobbyMessage::EditorTerminated { status })?;
         Ok(())
     }
 
-    pub fn resize(&mut self, rows: u16, cols: u16, direction: Direction) -> Result<()> {
-        if self.is_full_screen {
-            self.terminal.resize(rows - 2, cols - 2)?;
-            return Ok(());
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
+        match self.kind {
+            EditorKind::Terminal(ref mut terminal) => terminal.handle_key_event(key),
+            EditorKind::Builtin(ref mut builtin) => builtin.handle_key_event(key),
+            EditorKind::Unsupported => Ok(()),
+        }
+    }
+
+    /// # Kill
+    ///
+    /// Kills the PTY process of the external editor. The built-in editor has
+    /// no process attached.
+    pub fn kill(&mut self) -> Result<()> {
+        if let EditorKind::Terminal(ref mut terminal) = self.kind {
+            terminal.child_killer.kill()?;
         }
-        let (rows, cols) = match direction {
-            Direction::Horizontal => (
-                // The full application height - header and borders.
-                ((rows - 5) as f64) as u16,
-                ((cols - 2) as f64 * TERMINAL_WIDTH * 0.5) as u16 - 1,
-            ),
-            Direction::Vertical => (
-                ((rows - 5) as f64 * EDITOR_HEIGHT) as u16 - 1,
-                ((cols - 2) as f64 * TERMINAL_WIDTH) as u16,
-            ),
+        Ok(())
+    }
+
+    pub fn resize(
+        &mut self,
+        rows: u16,
+        cols: u16,
+        direction: Direction,
+        editor_ratio: f64,
+    ) -> Result<()> {
+        // Only the PTY needs to know about its size.
+        let EditorKind::Terminal(ref mut terminal) = self.kind else {
+            return Ok(());
         };
-        self.terminal.resize(rows, cols)?;
+        let (rows, cols) = pane_size(rows, cols, direction, editor_ratio, self.is_full_screen);
+        terminal.resize(rows, cols)?;
         Ok(())
     }
 }
 
+// Remove the temporary start file once the editor is gone, e.g., after
+// leaving the lobby or restarting the editor.
+impl Drop for Editor {
+    fn drop(&mut self) {
+        if let Some(ref file_path) = self.file_path {
+            remove_challenge_file(file_path);
+        }
+    }
+}
+
 fn async_watcher() -> notify::Result<(RecommendedWatcher, UnboundedReceiver<notify::Result<Event>>)>
 {
     let (tx, rx) = unbounded_channel();
@@ -19,4 +19,25 @@ impl Encryption {
             value,
         }
     }
+
+    /// # Reveal
+    ///
+    /// Reveals up to `speed` further characters of a joined value.
+    pub fn reveal(&mut self, speed: usize) {
+        self.index = (self.index + speed).min(self.value.len());
+    }
+
+    /// # Hide
+    ///
+    /// Removes up to `speed` characters of a leaving value. Returns whether
+    /// the value was already gone.
+    pub fn hide(&mut self, speed: usize) -> bool {
+        if self.value.is_empty() {
+            return true;
+        }
+        for _ in 0..speed {
+            self.value.pop();
+        }
+        false
+    }
 }
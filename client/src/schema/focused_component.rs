@@ -1,10 +1,13 @@
 use anyhow::{anyhow, Result};
-use common::LobbyStatus;
+use common::{constants::MAX_LOBBY_NAME_LENGTH, LobbyStatus};
 use log::debug;
-use ratatui::crossterm::event::KeyEvent;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
 
-use super::connection::Connection;
-use crate::app::App;
+use super::{command_palette::commands, connection::Connection, lobby::LobbyMessage};
+use crate::{
+    app::{App, AppMessage},
+    constants::{LOGS_SCROLL_STEP, MAX_LOGS_OFFSET},
+};
 
 #[derive(Debug)]
 pub struct FocusedComponent {
@@ -12,13 +15,22 @@ pub struct FocusedComponent {
     pub is_full_screen: bool,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ComponentKind {
     Chat,
+    CommandPalette,
+    Diff,
     Editor,
     ExitPopup,
     Goal,
+    Help,
     Lobbies,
+    LobbySearch,
+    Logs,
+    Players,
+    ReconnectPopup,
+    RenameLobbyPopup,
+    ResetEditorPopup,
 }
 
 impl FocusedComponent {
@@ -44,32 +56,65 @@ impl FocusedComponent {
 
         match focused_component.kind {
             ComponentKind::Chat => {}
+            ComponentKind::CommandPalette => {}
+            ComponentKind::Diff => {}
             ComponentKind::Editor => {
-                if let Connection::Lobby(ref mut lobby) = app.connection {
-                    lobby.editor.is_full_screen = focused_component.is_full_screen;
-                    lobby.editor.resize(
-                        app.size.height,
-                        app.size.width,
-                        lobby.terminal_layout_direction,
-                    )?;
+                if let Some((editor, direction, editor_ratio)) = app.connection.editor_mut() {
+                    editor.is_full_screen = focused_component.is_full_screen;
+                    editor.resize(app.size.height, app.size.width, direction, editor_ratio)?;
                 }
             }
             ComponentKind::Goal => {
-                if let Connection::Lobby(ref mut lobby) = app.connection {
-                    lobby.goal.is_full_screen = focused_component.is_full_screen;
-                    lobby.goal.resize(
-                        app.size.height,
-                        app.size.width,
-                        lobby.terminal_layout_direction,
-                    )?;
+                if let Some((goal, direction, editor_ratio)) = app.connection.goal_mut() {
+                    goal.is_full_screen = focused_component.is_full_screen;
+                    goal.resize(app.size.height, app.size.width, direction, editor_ratio)?;
                 }
             }
+            ComponentKind::Help => {}
             ComponentKind::Lobbies => {}
+            ComponentKind::LobbySearch => {}
+            ComponentKind::Logs => {}
+            ComponentKind::Players => {}
             ComponentKind::ExitPopup => {}
+            ComponentKind::ReconnectPopup => {}
+            ComponentKind::RenameLobbyPopup => {}
+            ComponentKind::ResetEditorPopup => {}
         };
         Ok(())
     }
 
+    /// # Toggle editor full screen
+    ///
+    /// Focuses the editor in full screen. If the editor already is in full
+    /// screen, returns to the split view instead.
+    pub fn toggle_editor_full_screen(app: &mut App) -> Result<()> {
+        if app.focused_component.as_ref().is_some_and(|component| {
+            component.kind.eq(&ComponentKind::Editor) && component.is_full_screen
+        }) {
+            FocusedComponent::clean_up(app)?;
+            app.focused_component = None;
+            return Ok(());
+        }
+
+        // Only focus an existing editor outside of the start countdown.
+        let editor_available = match app.connection {
+            Connection::Lobby(ref lobby) => {
+                lobby.editor.is_some() && !matches!(lobby.status, LobbyStatus::AboutToStart(_))
+            }
+            Connection::Practice(_) => true,
+            Connection::Join(_) | Connection::Offline(_) => false,
+        };
+        if !editor_available {
+            return Ok(());
+        }
+
+        if app.focused_component.is_some() {
+            FocusedComponent::clean_up(app)?;
+        }
+        app.focused_component = Some(FocusedComponent::new(ComponentKind::Editor));
+        FocusedComponent::toggle_full_screen(app)
+    }
+
     pub async fn handle_key_event(app: &mut App, key: KeyEvent) -> Result<()> {
         debug!("Handle key event {:?}.", key);
         let Some(ref mut focused_component) = app.focused_component else {
@@ -91,20 +136,116 @@ impl FocusedComponent {
                     lobby.chat.handle_key_event(key)?;
                 }
             }
-            ComponentKind::Editor => {
+            ComponentKind::CommandPalette => {
+                let commands = commands(&app.connection, &app.config);
+                if let Some(command) = app.command_palette.handle_key_event(key, commands) {
+                    app.focused_component = None;
+                    app.tx.send(AppMessage::RunCommand(command))?;
+                }
+            }
+            ComponentKind::Editor => match app.connection {
+                Connection::Lobby(ref mut lobby) => {
+                    // Only allow to edit the file if the lobby is in progress
+                    // and the editor did not crash.
+                    if let (LobbyStatus::InProgress(_), None, Some(editor)) = (
+                        &lobby.status,
+                        &lobby.editor_restarts.error,
+                        &mut lobby.editor,
+                    ) {
+                        editor.handle_key_event(key)?;
+                    }
+                }
+                // Practice rounds never wait for anyone.
+                Connection::Practice(ref mut practice) => {
+                    if practice.editor_restarts.error.is_none() {
+                        practice.editor.handle_key_event(key)?;
+                    }
+                }
+                Connection::Join(_) | Connection::Offline(_) => {}
+            },
+            ComponentKind::Diff => {
                 if let Connection::Lobby(ref mut lobby) = app.connection {
-                    // Only allow to edit the file if the lobby is in progress.
-                    if let LobbyStatus::InProgress(_) = lobby.status {
-                        lobby.editor.terminal.handle_key_event(key)?;
+                    if key.eq(&app.config.key_bindings.movement.up) {
+                        lobby.diff.scroll_up();
+                    } else if key.eq(&app.config.key_bindings.movement.down) {
+                        lobby.diff.scroll_down();
                     }
                 }
             }
             ComponentKind::Goal => {}
+            ComponentKind::Help => {
+                // The help key closes the overlay just like the unfocus key.
+                if key.eq(&app.config.key_bindings.miscellaneous.help) {
+                    app.focused_component = None;
+                } else if key.eq(&app.config.key_bindings.movement.up) {
+                    app.help_offset = app.help_offset.saturating_sub(1);
+                } else if key.eq(&app.config.key_bindings.movement.down) {
+                    app.help_offset = app.help_offset.saturating_add(1);
+                }
+            }
+            ComponentKind::Players => {
+                if let Connection::Lobby(ref mut lobby) = app.connection {
+                    if key.eq(&app.config.key_bindings.movement.down) {
+                        lobby.select_next_player();
+                    } else if key.eq(&app.config.key_bindings.movement.up) {
+                        lobby.select_previous_player();
+                    }
+                    // Kick the selected player as lobby owner.
+                    else if key.eq(&app.config.key_bindings.lobby.kick_player)
+                        && lobby.owner == lobby.local_player
+                        && lobby.local_player.is_some()
+                    {
+                        if let Some(player_id) = lobby.selected_player {
+                            if lobby.local_player.ne(&Some(player_id)) {
+                                lobby.tx.send(LobbyMessage::KickPlayer { player_id })?;
+                            }
+                        }
+                    }
+                    // Hand the owner role to the selected player. Waiting
+                    // players do not take part in the game yet.
+                    else if key.eq(&app.config.key_bindings.lobby.transfer_ownership)
+                        && lobby.owner == lobby.local_player
+                        && lobby.local_player.is_some()
+                    {
+                        if let Some(player) = lobby
+                            .selected_player
+                            .and_then(|player_id| lobby.players.get(&player_id))
+                        {
+                            if lobby.local_player.ne(&Some(player.id)) && !player.waiting {
+                                lobby.tx.send(LobbyMessage::TransferOwnership {
+                                    player_id: player.id,
+                                })?;
+                            }
+                        }
+                    }
+                }
+            }
             ComponentKind::Lobbies => {
                 if let Connection::Join(ref mut join) = app.connection {
                     join.handle_key_event(&app.config, key).await?;
                 }
             }
+            ComponentKind::LobbySearch => {
+                if let Connection::Join(ref mut join) = app.connection {
+                    // Browse the filtered lobbies once the query is entered.
+                    if key.code == KeyCode::Enter {
+                        app.focused_component = Some(FocusedComponent::new(ComponentKind::Lobbies));
+                    } else {
+                        join.handle_search_key_event(key);
+                    }
+                }
+            }
+            ComponentKind::Logs => {
+                // Scroll long log lines horizontally.
+                if key.eq(&app.config.key_bindings.movement.left) {
+                    app.logs_offset = app.logs_offset.saturating_sub(LOGS_SCROLL_STEP);
+                } else if key.eq(&app.config.key_bindings.movement.right) {
+                    app.logs_offset = app
+                        .logs_offset
+                        .saturating_add(LOGS_SCROLL_STEP)
+                        .min(MAX_LOGS_OFFSET);
+                }
+            }
             ComponentKind::ExitPopup => {
                 if key.eq(&app.config.key_bindings.popup.confirm) {
                     app.exit = true;
@@ -112,6 +253,52 @@ impl FocusedComponent {
                     app.focused_component = None;
                 }
             }
+            ComponentKind::ReconnectPopup => {
+                if key.eq(&app.config.key_bindings.popup.confirm) {
+                    app.focused_component = None;
+                    if let Some(session) = app.last_session.take() {
+                        app.tx.send(AppMessage::RestoreSession(session))?;
+                    }
+                } else if key.eq(&app.config.key_bindings.popup.abort) {
+                    app.last_session = None;
+                    app.focused_component = None;
+                }
+            }
+            ComponentKind::RenameLobbyPopup => {
+                if let Connection::Lobby(ref mut lobby) = app.connection {
+                    // Typed characters belong to the name, so the popup keys
+                    // do not apply here.
+                    match key.code {
+                        KeyCode::Enter => {
+                            let name = std::mem::take(&mut lobby.name_input);
+                            lobby.tx.send(LobbyMessage::RenameLobby { name })?;
+                            app.focused_component = None;
+                        }
+                        KeyCode::Esc => app.focused_component = None,
+                        KeyCode::Char(input)
+                            if lobby.name_input.chars().count() < MAX_LOBBY_NAME_LENGTH =>
+                        {
+                            lobby.name_input.push(input);
+                        }
+                        KeyCode::Backspace => {
+                            lobby.name_input.pop();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            ComponentKind::ResetEditorPopup => {
+                if key.eq(&app.config.key_bindings.popup.confirm) {
+                    match app.connection {
+                        Connection::Lobby(ref mut lobby) => lobby.reset_editor()?,
+                        Connection::Practice(ref mut practice) => practice.reset()?,
+                        Connection::Join(_) | Connection::Offline(_) => {}
+                    }
+                    app.focused_component = None;
+                } else if key.eq(&app.config.key_bindings.popup.abort) {
+                    app.focused_component = None;
+                }
+            }
         };
         Ok(())
     }
@@ -128,32 +315,33 @@ impl FocusedComponent {
         };
         match focused_component.kind {
             ComponentKind::Chat => {}
+            ComponentKind::CommandPalette => {}
+            ComponentKind::Diff => {}
             // In case of a focused editor, tell the actual editor instance it's
             // not full screen anymore and resize it.
             ComponentKind::Editor => {
-                if let Connection::Lobby(ref mut lobby) = app.connection {
-                    lobby.editor.is_full_screen = false;
-                    lobby.editor.resize(
-                        app.size.height,
-                        app.size.width,
-                        lobby.terminal_layout_direction,
-                    )?;
+                if let Some((editor, direction, editor_ratio)) = app.connection.editor_mut() {
+                    editor.is_full_screen = false;
+                    editor.resize(app.size.height, app.size.width, direction, editor_ratio)?;
                 }
             }
             // In case of a focused editor, tell the actual editor instance it's
             // not full screen anymore and resize it.
             ComponentKind::Goal => {
-                if let Connection::Lobby(ref mut lobby) = app.connection {
-                    lobby.goal.is_full_screen = false;
-                    lobby.goal.resize(
-                        app.size.height,
-                        app.size.width,
-                        lobby.terminal_layout_direction,
-                    )?;
+                if let Some((goal, direction, editor_ratio)) = app.connection.goal_mut() {
+                    goal.is_full_screen = false;
+                    goal.resize(app.size.height, app.size.width, direction, editor_ratio)?;
                 }
             }
+            ComponentKind::Help => {}
             ComponentKind::Lobbies => {}
+            ComponentKind::LobbySearch => {}
+            ComponentKind::Logs => {}
+            ComponentKind::Players => {}
             ComponentKind::ExitPopup => {}
+            ComponentKind::ReconnectPopup => {}
+            ComponentKind::RenameLobbyPopup => {}
+            ComponentKind::ResetEditorPopup => {}
         };
         Ok(())
     }
@@ -1,57 +1,115 @@
-use std::{env::temp_dir, fs::File, io::Write};
+use std::path::PathBuf;
 
-use anyhow::{anyhow, Result};
-use log::warn;
-use portable_pty::{Child, CommandBuilder};
+use anyhow::Result;
+use common::ContentType;
+use log::{info, warn};
+use portable_pty::Child;
 use ratatui::layout::{Direction, Size};
 use tokio::sync::mpsc::UnboundedSender;
+use uuid::Uuid;
 
-use crate::constants::{GOAL_HEIGHT, TERMINAL_WIDTH};
-
-use super::{lobby::LobbyMessage, terminal::Terminal};
+use super::{
+    builtin_editor::BuiltinEditor,
+    editor::EditorKind,
+    lobby::LobbyMessage,
+    terminal::{self, pane_size, remove_challenge_file, ChallengeFile, Terminal},
+};
 
 pub struct Goal {
-    pub terminal: Terminal,
+    pub kind: EditorKind,
     pub is_full_screen: bool,
+    /// Why the external editor could not be started, if the built-in editor
+    /// is used as a fallback.
+    pub fallback_reason: Option<String>,
+    /// The file opened by the external editor. Is removed on drop.
+    pub file_path: Option<PathBuf>,
 }
 
 impl Goal {
     /// # Create a new goal editor
     ///
     /// Starts a new editor inside a PTY instance that opens up the goal file of
-    /// the current lobby.
+    /// the current lobby. Falls back to displaying the goal file via the
+    /// built-in editor if requested or if the external editor is not
+    /// available.
     pub fn new(
         app_size: Size,
         lobby_tx: UnboundedSender<LobbyMessage>,
-        goal_file: Vec<u8>,
+        goal_file: ChallengeFile,
         is_full_screen: bool,
+        use_builtin_editor: bool,
+        editor_command: &str,
+        editor_args: &[String],
     ) -> Result<Self> {
-        // Write the start file bytes to a temporary file.
-        let mut path = temp_dir();
-        path.push("goal.txt");
-
-        let mut file = match File::create(&path) {
-            Ok(file) => file,
-            Err(e) => return Err(anyhow!("Error creating file: {e}")),
+        let mut fallback_reason = None;
+        let mut file_path = None;
+        let kind = if goal_file.content_type == ContentType::Binary {
+            warn!("Not opening the binary goal file in an editor.");
+            EditorKind::Unsupported
+        } else if use_builtin_editor {
+            EditorKind::Builtin(BuiltinEditor::new(&goal_file.content, lobby_tx))
+        } else {
+            match Goal::spawn_terminal(
+                app_size,
+                lobby_tx.clone(),
+                &goal_file,
+                editor_command,
+                editor_args,
+            ) {
+                Ok((terminal, path)) => {
+                    file_path = Some(path);
+                    EditorKind::Terminal(terminal)
+                }
+                Err(e) => {
+                    warn!("Unable to start external goal editor, using built-in editor: {e}");
+                    fallback_reason = Some(e.to_string());
+                    EditorKind::Builtin(BuiltinEditor::new(&goal_file.content, lobby_tx))
+                }
+            }
         };
-        if let Err(e) = file.write_all(&goal_file) {
-            return Err(anyhow!("Error writing to file: {e}"));
-        }
+
+        Ok(Self {
+            kind,
+            is_full_screen,
+            fallback_reason,
+            file_path,
+        })
+    }
+
+    /// # Spawn terminal
+    ///
+    /// Writes the goal file to a temporary location and opens it inside a PTY
+    /// instance.
+    fn spawn_terminal(
+        app_size: Size,
+        lobby_tx: UnboundedSender<LobbyMessage>,
+        goal_file: &ChallengeFile,
+        editor_command: &str,
+        editor_args: &[String],
+    ) -> Result<(Terminal, PathBuf)> {
+        // Use a unique file name inside the directory of the lobby, so
+        // concurrent goals never collide.
+        let file_path = goal_file.path(&format!("goal-{}", Uuid::new_v4()));
 
         // Build the command that opens the goal file fetched from the backend
         // service.
-        let mut cmd = CommandBuilder::new("helix");
-        cmd.arg(&path);
+        let cmd = terminal::editor_command(editor_command, editor_args, &file_path, None)?;
+
+        // Write the goal file bytes to file.
+        goal_file.write(&file_path)?;
 
         // Build the terminal and resize it directly.
-        let (terminal, child) = Terminal::new(app_size, cmd)?;
+        let (terminal, child) = match Terminal::new(app_size, cmd) {
+            Ok(terminal) => terminal,
+            Err(e) => {
+                remove_challenge_file(&file_path);
+                return Err(e);
+            }
+        };
 
         tokio::spawn(Goal::handle_termination(child, lobby_tx));
 
-        Ok(Self {
-            terminal,
-            is_full_screen,
-        })
+        Ok((terminal, file_path))
     }
 
     /// # Handle termination
@@ -62,29 +120,120 @@ impl Goal {
         mut child: Box<dyn Child + Send + Sync>,
         lobby_tx: UnboundedSender<LobbyMessage>,
     ) -> Result<()> {
-        child.wait()?;
-        warn!("The goal process has completed.");
-        lobby_tx.send(LobbyMessage::GoalTerminated)?;
+        let status = child.wait()?;
+        // Clean exits are regular quits, everything else is a crash.
+        if status.success() {
+            info!("The goal process exited with status: {status}");
+        } else {
+            warn!("The goal process terminated with status: {status}");
+        }
+        lobby_tx.send(LobbyMessage::GoalTerminated { status })?;
         Ok(())
     }
 
-    pub fn resize(&mut self, rows: u16, cols: u16, direction: Direction) -> Result<()> {
-        if self.is_full_screen {
-            self.terminal.resize(rows - 2, cols - 2)?;
-            return Ok(());
+    /// # Kill
+    ///
+    /// Kills the PTY process of the external goal editor.
+    pub fn kill(&mut self) -> Result<()> {
+        if let EditorKind::Terminal(ref mut terminal) = self.kind {
+            terminal.child_killer.kill()?;
         }
-        let (rows, cols) = match direction {
-            Direction::Horizontal => (
-                // The full application height - header and borders.
-                ((rows - 5) as f64) as u16,
-                ((cols - 2) as f64 * TERMINAL_WIDTH * 0.5) as u16 - 1,
-            ),
-            Direction::Vertical => (
-                ((rows - 5) as f64 * GOAL_HEIGHT) as u16 - 1,
-                ((cols - 2) as f64 * TERMINAL_WIDTH) as u16,
-            ),
+        Ok(())
+    }
+
+    pub fn resize(
+        &mut self,
+        rows: u16,
+        cols: u16,
+        direction: Direction,
+        editor_ratio: f64,
+    ) -> Result<()> {
+        // Only the PTY needs to know about its size.
+        let EditorKind::Terminal(ref mut terminal) = self.kind else {
+            return Ok(());
         };
-        self.terminal.resize(rows, cols)?;
+        // The goal takes the space the editor leaves.
+        let (rows, cols) = pane_size(
+            rows,
+            cols,
+            direction,
+            1.0 - editor_ratio,
+            self.is_full_screen,
+        );
+        terminal.resize(rows, cols)?;
         Ok(())
     }
 }
+
+// Remove the temporary goal file once the editor is gone, e.g., after
+// leaving the lobby or restarting the editor.
+impl Drop for Goal {
+    fn drop(&mut self) {
+        if let Some(ref file_path) = self.file_path {
+            remove_challenge_file(file_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+    use crate::schema::terminal::challenge_directory;
+
+    fn new_goal(directory: PathBuf) -> Goal {
+        let (lobby_tx, _lobby_rx) = unbounded_channel();
+        let goal_file = ChallengeFile {
+            content: b"fn main() {}\n".to_vec(),
+            directory,
+            extension: "rs".to_string(),
+            content_type: ContentType::Text,
+        };
+        Goal::new(
+            Size::new(80, 24),
+            lobby_tx,
+            goal_file,
+            false,
+            false,
+            "cat",
+            &[],
+        )
+        .expect("goal should be created")
+    }
+
+    #[tokio::test]
+    async fn goals_get_distinct_paths_and_are_removed() {
+        let directory = challenge_directory(Uuid::new_v4());
+        let first_goal = new_goal(directory.clone());
+        let second_goal = new_goal(directory.clone());
+        let other_lobby_goal = new_goal(challenge_directory(Uuid::new_v4()));
+
+        let first_path = first_goal
+            .file_path
+            .clone()
+            .expect("goal should be written");
+        let second_path = second_goal
+            .file_path
+            .clone()
+            .expect("goal should be written");
+        let other_lobby_path = other_lobby_goal
+            .file_path
+            .clone()
+            .expect("goal should be written");
+        assert_ne!(first_path, second_path);
+        assert_ne!(first_path.parent(), other_lobby_path.parent());
+        assert!(first_path.is_file() && second_path.is_file());
+        assert_eq!(first_path.extension().unwrap(), "rs");
+
+        drop(first_goal);
+        assert!(!first_path.exists());
+        assert!(second_path.is_file());
+        drop(second_goal);
+        assert!(!second_path.exists());
+        // The directory of the lobby is gone with its last file.
+        assert!(!directory.exists());
+        drop(other_lobby_goal);
+        assert!(!other_lobby_path.exists());
+    }
+}
@@ -1,15 +1,18 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, ops::Range, time::Instant};
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use futures_util::{
     stream::{SplitSink, SplitStream},
     SinkExt, StreamExt,
 };
 use log::{debug, error, info};
 use ratatui::{
-    crossterm::event::KeyEvent,
+    crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
+    layout::{Position, Rect},
     widgets::{ScrollbarState, TableState},
 };
+use strum::Display;
 use tokio::{
     net::TcpStream,
     sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
@@ -21,10 +24,21 @@ use tokio_tungstenite::{
 };
 use uuid::Uuid;
 
-use common::{constants::MAX_LOBBY_SIZE, BackendMessage, JoinMode, LobbyListItem, LobbyStatus};
+use common::{
+    constants::{MAX_LOBBY_CAPACITY, MAX_LOBBY_SIZE, MIN_LOBBY_CAPACITY},
+    BackendMessage, CreateLobbyOptions, JoinMode, LobbyListItem, LobbyStatus,
+};
 
-use super::encryption::{Encryption, EncryptionAction};
-use crate::{app::AppMessage, config::Config};
+use super::{
+    connection::next_backend_message,
+    encryption::{Encryption, EncryptionAction},
+    focused_component::{ComponentKind, FocusedComponent},
+};
+use crate::{
+    app::AppMessage,
+    config::{Config, Service},
+    constants::{DOUBLE_CLICK_TIME, LOBBY_TABLE_HEADER_HEIGHT},
+};
 
 pub struct Join {
     pub lobby_list: BTreeMap<Uuid, LobbyListItem>,
@@ -34,12 +48,57 @@ pub struct Join {
     pub app_tx: UnboundedSender<AppMessage>,
     pub state: TableState,
     pub scroll_state: ScrollbarState,
+    /// The number of players lobbies created by the user hold.
+    pub max_players: usize,
+    /// Only lobbies whose name contains this query are listed. An empty
+    /// query lists all lobbies.
+    pub search: String,
+    /// The order lobbies are listed in.
+    pub sort_order: SortOrder,
+    /// The IDs of all listed lobbies, including the ones that are being
+    /// removed, sorted by the current sort order.
+    pub order: Vec<Uuid>,
+    /// The area the lobby table was rendered in. Used to map mouse clicks to
+    /// table rows.
+    pub table_area: Rect,
+    /// The number of lobby rows fitting into the table. Only these rows are
+    /// rendered and animated.
+    pub page_height: usize,
+    /// The lobby clicked last and when. A second click on the same lobby
+    /// within the double click time joins it.
+    pub last_click: Option<(Uuid, Instant)>,
+    /// Why the last attempt to connect to a lobby failed, e.g., because the
+    /// server is full. Shown below the lobby list.
+    pub notice: Option<String>,
 
+    /// The number of characters the encryptions reveal per tick.
+    pub animation_speed: usize,
     pub encrypted_names: BTreeMap<Uuid, Encryption>,
     pub encrypted_player_counts: BTreeMap<Uuid, Encryption>,
+    pub encrypted_challenges: BTreeMap<Uuid, Encryption>,
     pub encrypted_status: BTreeMap<Uuid, Encryption>,
 }
 
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum SortOrder {
+    #[strum(to_string = "name")]
+    Name,
+    #[strum(to_string = "player count")]
+    PlayerCount,
+    #[strum(to_string = "status")]
+    Status,
+}
+
+impl SortOrder {
+    pub fn next(&self) -> Self {
+        match self {
+            SortOrder::Name => SortOrder::PlayerCount,
+            SortOrder::PlayerCount => SortOrder::Status,
+            SortOrder::Status => SortOrder::Name,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum JoinMessage {
     /// Updates the table showing current lobbies.
@@ -50,15 +109,26 @@ pub enum JoinMessage {
     AddLobby(Uuid, LobbyListItem),
     /// Updates the player count for a lobby in the lobby list table.
     UpdateLobbyPlayerCount { id: Uuid, player_count: usize },
-    /// Updates the status for a lobby in the lobby list table.
-    UpdateLobbyStatus { id: Uuid, status: LobbyStatus },
+    /// Updates the status and the optional removal time for a lobby in the
+    /// lobby list table.
+    UpdateLobbyStatus {
+        id: Uuid,
+        status: LobbyStatus,
+        removal_time: Option<DateTime<Utc>>,
+    },
+    /// Updates the name of a lobby in the lobby list table.
+    UpdateLobbyName { id: Uuid, name: String },
     /// Removes a lobby from the lobby list table.
     RemoveLobby(Uuid),
 }
 
 impl Join {
-    pub async fn new(app_tx: UnboundedSender<AppMessage>) -> Result<Self, Error> {
-        let (ws_stream, _) = connect_async("ws://127.0.0.1:3030/clients").await?;
+    pub async fn new(
+        app_tx: UnboundedSender<AppMessage>,
+        service: &Service,
+        animation_speed: usize,
+    ) -> Result<Self, Error> {
+        let (ws_stream, _) = connect_async(service.ws_url("clients")).await?;
         let (ws_tx, ws_rx) = ws_stream.split();
 
         let (tx, rx) = unbounded_channel();
@@ -77,9 +147,19 @@ impl Join {
             app_tx,
             state: TableState::default(),
             scroll_state: ScrollbarState::default(),
+            max_players: MAX_LOBBY_SIZE,
+            search: String::new(),
+            sort_order: SortOrder::Name,
+            order: vec![],
+            table_area: Rect::default(),
+            page_height: 0,
+            last_click: None,
+            notice: None,
 
+            animation_speed,
             encrypted_names: BTreeMap::new(),
             encrypted_player_counts: BTreeMap::new(),
+            encrypted_challenges: BTreeMap::new(),
             encrypted_status: BTreeMap::new(),
         })
     }
@@ -89,11 +169,26 @@ impl Join {
 
         // Join a selected lobby.
         if key.eq(&config.key_bindings.join.join_selected) {
+            self.join_selected().await?;
+        }
+        // Watch a selected lobby without playing.
+        else if key.eq(&config.key_bindings.join.spectate_selected) {
             if let Some(lobby_id) = self.selected_lobby {
                 self.ws_tx.close().await?;
-                let join_mode = JoinMode::Join { lobby_id };
-                self.app_tx.send(AppMessage::ConnectToLobby { join_mode })?;
+                let join_mode = JoinMode::Spectate { lobby_id };
+                self.app_tx.send(AppMessage::ConnectToLobby {
+                    join_mode,
+                    options: CreateLobbyOptions::default(),
+                })?;
             }
+        } else if key.eq(&config.key_bindings.join.search) {
+            self.app_tx
+                .send(AppMessage::FocusComponent(Some(FocusedComponent::new(
+                    ComponentKind::LobbySearch,
+                ))))?;
+        } else if key.eq(&config.key_bindings.join.cycle_sort_order) {
+            self.sort_order = self.sort_order.next();
+            self.sort();
         } else if key.eq(&config.key_bindings.movement.down) {
             self.next_lobby_entry();
         } else if key.eq(&config.key_bindings.movement.up) {
@@ -101,17 +196,207 @@ impl Join {
         } else if key.eq(&config.key_bindings.join.quickplay) {
             self.ws_tx.close().await?;
             let join_mode = JoinMode::Quickplay;
-            self.app_tx.send(AppMessage::ConnectToLobby { join_mode })?;
+            self.app_tx.send(AppMessage::ConnectToLobby {
+                join_mode,
+                options: CreateLobbyOptions::default(),
+            })?;
         } else if key.eq(&config.key_bindings.join.create) {
             debug!("Close client connection.");
 
             self.ws_tx.close().await?;
             let join_mode = JoinMode::Create;
-            self.app_tx.send(AppMessage::ConnectToLobby { join_mode })?;
+            let options = CreateLobbyOptions {
+                max_players: Some(self.max_players),
+                start_countdown: Some(config.general.start_countdown),
+                ..Default::default()
+            };
+            self.app_tx
+                .send(AppMessage::ConnectToLobby { join_mode, options })?;
+        }
+        // Adjust the capacity of lobbies created by the user.
+        else if key.eq(&config.key_bindings.movement.left) {
+            self.max_players = self.max_players.saturating_sub(1).max(MIN_LOBBY_CAPACITY);
+        } else if key.eq(&config.key_bindings.movement.right) {
+            self.max_players = (self.max_players + 1).min(MAX_LOBBY_CAPACITY);
         }
         Ok(())
     }
 
+    /// # Handle mouse event
+    ///
+    /// Selects the clicked lobby and joins it on a double click. Scrolling
+    /// moves the selection.
+    pub async fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<()> {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = Position::new(mouse.column, mouse.row);
+                if !self.table_area.contains(position) {
+                    return Ok(());
+                }
+                let Some(row) = mouse
+                    .row
+                    .checked_sub(self.table_area.y + LOBBY_TABLE_HEADER_HEIGHT)
+                else {
+                    return Ok(());
+                };
+                let i = self.state.offset() + row as usize;
+                let Some(lobby_id) = self.visible_lobbies().get(i).cloned() else {
+                    return Ok(());
+                };
+
+                let is_double_click = self.last_click.is_some_and(|(id, clicked_at)| {
+                    id.eq(&lobby_id) && clicked_at.elapsed() < DOUBLE_CLICK_TIME
+                });
+                self.select_lobby_entry(i);
+                if is_double_click {
+                    self.last_click = None;
+                    self.join_selected().await?;
+                } else {
+                    self.last_click = Some((lobby_id, Instant::now()));
+                }
+            }
+            MouseEventKind::ScrollDown => self.next_lobby_entry(),
+            MouseEventKind::ScrollUp => self.previous_lobby_entry(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// # Join selected
+    ///
+    /// Joins the selected lobby, if any.
+    async fn join_selected(&mut self) -> Result<()> {
+        if let Some(lobby_id) = self.selected_lobby {
+            self.ws_tx.close().await?;
+            let join_mode = JoinMode::Join { lobby_id };
+            self.app_tx.send(AppMessage::ConnectToLobby {
+                join_mode,
+                options: CreateLobbyOptions::default(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// # Handle search key event
+    ///
+    /// Edits the search query. The selection is reset whenever the query
+    /// changes, because the selected lobby might not be listed anymore.
+    pub fn handle_search_key_event(&mut self, key: KeyEvent) {
+        debug!("Handle search key event {:?}.", key);
+
+        match key.code {
+            KeyCode::Char(input) => self.search.push(input),
+            KeyCode::Backspace => {
+                if self.search.pop().is_none() {
+                    return;
+                }
+            }
+            _ => return,
+        }
+        self.state.select(None);
+        self.selected_lobby = None;
+        self.scroll_state = self
+            .scroll_state
+            .content_length(self.visible_lobbies().len())
+            .position(0);
+    }
+
+    /// # Is visible
+    ///
+    /// Whether the lobby with the given ID matches the search query. Lobbies
+    /// that are being removed are only visible without a query.
+    pub fn is_visible(&self, lobby_id: &Uuid) -> bool {
+        if self.search.is_empty() {
+            return true;
+        }
+        let query = self.search.to_lowercase();
+        self.lobby_list
+            .get(lobby_id)
+            .is_some_and(|lobby| lobby.name.to_lowercase().contains(&query))
+    }
+
+    /// # Listed lobbies
+    ///
+    /// Returns the IDs of all rows of the lobby table, including the lobbies
+    /// that are being removed.
+    pub fn listed_lobbies(&self) -> impl Iterator<Item = &Uuid> {
+        self.order.iter().filter(|id| self.is_visible(id))
+    }
+
+    /// # Visible window
+    ///
+    /// Returns the range of table rows that fit into the table at the current
+    /// scroll offset.
+    pub fn visible_window(&self) -> Range<usize> {
+        let offset = self.state.offset();
+        offset..offset + self.page_height
+    }
+
+    /// # Scroll to selection
+    ///
+    /// Moves the scroll offset so the selected row is part of the visible
+    /// window and no space is wasted below the last of `row_count` rows.
+    pub fn scroll_to_selection(&mut self, row_count: usize) {
+        let mut offset = self
+            .state
+            .offset()
+            .min(row_count.saturating_sub(self.page_height));
+        if let Some(selected) = self.state.selected() {
+            if selected < offset {
+                offset = selected;
+            } else if selected >= offset + self.page_height {
+                offset = (selected + 1).saturating_sub(self.page_height);
+            }
+        }
+        *self.state.offset_mut() = offset;
+    }
+
+    /// # Visible lobbies
+    ///
+    /// Returns the IDs of all lobbies matching the search query in the order
+    /// they are listed.
+    pub fn visible_lobbies(&self) -> Vec<Uuid> {
+        self.order
+            .iter()
+            .filter(|id| self.lobby_list.contains_key(id) && self.is_visible(id))
+            .cloned()
+            .collect()
+    }
+
+    /// # Sort
+    ///
+    /// Sorts the listed lobbies by the current sort order. Lobbies that are
+    /// being removed are listed last. The selection follows the selected
+    /// lobby to its new position.
+    pub fn sort(&mut self) {
+        let mut order = self.encrypted_names.keys().cloned().collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            let (Some(a), Some(b)) = (self.lobby_list.get(a), self.lobby_list.get(b)) else {
+                return self
+                    .lobby_list
+                    .contains_key(b)
+                    .cmp(&self.lobby_list.contains_key(a));
+            };
+            match self.sort_order {
+                SortOrder::Name => a.name.cmp(&b.name),
+                SortOrder::PlayerCount => b.player_count.cmp(&a.player_count),
+                SortOrder::Status => status_rank(&a.status).cmp(&status_rank(&b.status)),
+            }
+        });
+        self.order = order;
+
+        if let Some(lobby_id) = self.selected_lobby {
+            if let Some(i) = self
+                .visible_lobbies()
+                .iter()
+                .position(|id| id.eq(&lobby_id))
+            {
+                self.state.select(Some(i));
+                self.scroll_state = self.scroll_state.position(i);
+            }
+        }
+    }
+
     pub async fn handle_message(&mut self, msg: JoinMessage) -> Result<()> {
         debug!("Handle message {:?}.", msg);
 
@@ -122,13 +407,18 @@ impl Join {
                         .insert(*id, Encryption::new(lobby.name.clone()));
                     self.encrypted_player_counts.insert(
                         *id,
-                        Encryption::new(format!("{} / {}", lobby.player_count, MAX_LOBBY_SIZE)),
+                        Encryption::new(format!("{} / {}", lobby.player_count, lobby.max_players)),
                     );
+                    self.encrypted_challenges
+                        .insert(*id, Encryption::new(challenge_label(lobby)));
                     self.encrypted_status
                         .insert(*id, Encryption::new(lobby.status.to_string()));
                 }
                 self.lobby_list = lobby_list;
-                self.scroll_state = self.scroll_state.content_length(self.lobby_list.len());
+                self.sort();
+                self.scroll_state = self
+                    .scroll_state
+                    .content_length(self.visible_lobbies().len());
             }
             JoinMessage::CloseConnection => {
                 info!("Close non-player connection.");
@@ -143,12 +433,17 @@ impl Join {
                     .insert(lobby_id, Encryption::new(lobby.name.clone()));
                 self.encrypted_player_counts.insert(
                     lobby_id,
-                    Encryption::new(format!("{} / {}", lobby.player_count, MAX_LOBBY_SIZE)),
+                    Encryption::new(format!("{} / {}", lobby.player_count, lobby.max_players)),
                 );
+                self.encrypted_challenges
+                    .insert(lobby_id, Encryption::new(challenge_label(&lobby)));
                 self.encrypted_status
                     .insert(lobby_id, Encryption::new(lobby.status.to_string()));
                 self.lobby_list.insert(lobby_id, lobby);
-                self.scroll_state = self.scroll_state.content_length(self.lobby_list.len());
+                self.sort();
+                self.scroll_state = self
+                    .scroll_state
+                    .content_length(self.visible_lobbies().len());
             }
             JoinMessage::RemoveLobby(lobby_id) => {
                 // If the currently selected lobby was removed, unselect it.
@@ -157,8 +452,19 @@ impl Join {
                         self.selected_lobby = None;
                     }
                 }
+                // Rows outside of the visible window vanish without
+                // animation.
+                let is_in_window = self
+                    .listed_lobbies()
+                    .position(|id| id.eq(&lobby_id))
+                    .is_some_and(|i| self.visible_window().contains(&i));
                 if let Some(lobby) = self.lobby_list.remove(&lobby_id) {
-                    self.scroll_state = self.scroll_state.content_length(self.lobby_list.len());
+                    self.scroll_state = self
+                        .scroll_state
+                        .content_length(self.visible_lobbies().len());
+                    if !is_in_window {
+                        self.remove_encryptions(&[lobby_id]);
+                    }
                     if let Some(encryption) = self.encrypted_names.get_mut(&lobby_id) {
                         encryption.action = EncryptionAction::Left;
                         encryption.index = encryption.value.len() - 1;
@@ -167,10 +473,15 @@ impl Join {
                         encryption.action = EncryptionAction::Left;
                         encryption.index = encryption.value.len() - 1;
                     }
+                    if let Some(encryption) = self.encrypted_challenges.get_mut(&lobby_id) {
+                        encryption.action = EncryptionAction::Left;
+                        encryption.index = encryption.value.len() - 1;
+                    }
                     if let Some(encryption) = self.encrypted_status.get_mut(&lobby_id) {
                         encryption.action = EncryptionAction::Left;
                         encryption.index = encryption.value.len() - 1;
                     }
+                    self.sort();
                     info!("Remove lobby {} from lobby list.", lobby.name);
                 } else {
                     error!("Tried to remove a non-existent lobby with ID {}.", lobby_id);
@@ -180,12 +491,17 @@ impl Join {
                 if let Some(lobby) = self.lobby_list.get_mut(&id) {
                     self.encrypted_player_counts.insert(
                         id,
-                        Encryption::new(format!("{} / {}", player_count, MAX_LOBBY_SIZE)),
+                        Encryption::new(format!("{} / {}", player_count, lobby.max_players)),
                     );
                     lobby.player_count = player_count;
+                    self.sort();
                 }
             }
-            JoinMessage::UpdateLobbyStatus { id, status } => {
+            JoinMessage::UpdateLobbyStatus {
+                id,
+                status,
+                removal_time,
+            } => {
                 if let Some(lobby) = self.lobby_list.get_mut(&id) {
                     info!(
                         "received lobby status update: {}, status: {:?}",
@@ -194,6 +510,17 @@ impl Join {
                     self.encrypted_status
                         .insert(id, Encryption::new(status.to_string()));
                     lobby.status = status;
+                    lobby.removal_time = removal_time;
+                    self.sort();
+                }
+            }
+            JoinMessage::UpdateLobbyName { id, name } => {
+                if let Some(lobby) = self.lobby_list.get_mut(&id) {
+                    info!("Lobby {} was renamed to {}.", lobby.name, name);
+                    self.encrypted_names
+                        .insert(id, Encryption::new(name.clone()));
+                    lobby.name = name;
+                    self.sort();
                 }
             }
         }
@@ -205,17 +532,16 @@ impl Join {
         message_tx: UnboundedSender<JoinMessage>,
         app_tx: UnboundedSender<AppMessage>,
     ) -> Result<()> {
-        while let Some(Ok(msg)) = ws_rx.next().await {
+        while let Some(msg) = next_backend_message(&mut ws_rx, &app_tx).await {
             debug!("Handle backend message {:?}.", msg);
 
-            if msg.is_close() {
-                return Ok(());
-            }
             let backend_message: BackendMessage = msg.into();
             match backend_message {
+                // The backend closes the connection on shutdown, so handle it
+                // like a lost connection.
                 BackendMessage::CloseConnection => {
                     message_tx.send(JoinMessage::CloseConnection)?;
-                    return Ok(());
+                    break;
                 }
                 BackendMessage::CurrentLobbies(lobbies) => {
                     message_tx.send(JoinMessage::CurrentLobbies(lobbies))?;
@@ -226,14 +552,38 @@ impl Join {
                 BackendMessage::RemoveLobby(lobby_id) => {
                     message_tx.send(JoinMessage::RemoveLobby(lobby_id))?;
                 }
-                BackendMessage::ConnectionCounts { clients, players } => {
-                    app_tx.send(AppMessage::ConnectionCounts { clients, players })?;
+                BackendMessage::ConnectionCounts {
+                    clients,
+                    players,
+                    spectators,
+                    lobbies,
+                } => {
+                    app_tx.send(AppMessage::ConnectionCounts {
+                        clients,
+                        players,
+                        spectators,
+                        lobbies,
+                    })?;
+                }
+                BackendMessage::Leaderboard(standings) => {
+                    app_tx.send(AppMessage::UpdateLeaderboard(standings))?;
                 }
                 BackendMessage::UpdateLobbyPlayerCount { id, player_count } => {
                     message_tx.send(JoinMessage::UpdateLobbyPlayerCount { id, player_count })?;
                 }
-                BackendMessage::UpdateLobbyStatus { id, status } => {
-                    message_tx.send(JoinMessage::UpdateLobbyStatus { id, status })?;
+                BackendMessage::UpdateLobbyStatus {
+                    id,
+                    status,
+                    removal_time,
+                } => {
+                    message_tx.send(JoinMessage::UpdateLobbyStatus {
+                        id,
+                        status,
+                        removal_time,
+                    })?;
+                }
+                BackendMessage::UpdateLobbyName { id, name } => {
+                    message_tx.send(JoinMessage::UpdateLobbyName { id, name })?;
                 }
                 _ => {}
             }
@@ -251,9 +601,10 @@ impl Join {
     /// Selects the next lobby entry given an already selected lobby. Otherwise
     /// select the first entry.
     pub fn next_lobby_entry(&mut self) {
+        let lobby_count = self.visible_lobbies().len();
         let i = match self.state.selected() {
             Some(i) => {
-                let length = self.lobby_list.len().checked_sub(1).unwrap_or_default();
+                let length = lobby_count.saturating_sub(1);
                 if i >= length {
                     0
                 } else {
@@ -262,8 +613,15 @@ impl Join {
             }
             None => 0,
         };
+        self.select_lobby_entry(i);
+    }
+
+    /// # Select lobby entry
+    ///
+    /// Selects the lobby entry at the given index of the visible lobbies.
+    pub fn select_lobby_entry(&mut self, i: usize) {
         self.state.select(Some(i));
-        self.selected_lobby = self.lobby_list.keys().cloned().nth(i);
+        self.selected_lobby = self.visible_lobbies().get(i).cloned();
         self.scroll_state = self.scroll_state.position(i);
     }
 
@@ -272,68 +630,131 @@ impl Join {
     /// Selects the previous lobby entry given an already selected lobby. Otherwise
     /// select the last entry.
     pub fn previous_lobby_entry(&mut self) {
+        let lobby_count = self.visible_lobbies().len();
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.lobby_list.len().checked_sub(1).unwrap_or_default()
+                    lobby_count.saturating_sub(1)
                 } else {
                     i - 1
                 }
             }
             None => 0,
         };
-        self.state.select(Some(i));
-        self.selected_lobby = self.lobby_list.keys().cloned().nth(i);
-        self.scroll_state = self.scroll_state.position(i);
+        self.select_lobby_entry(i);
     }
 
+    /// # Restore selection
+    ///
+    /// Selects the given lobby again, e.g., after a reconnect to the backend
+    /// service. If the lobby does not exist anymore, select the entry at the
+    /// previous index instead.
+    pub fn restore_selection(&mut self, lobby_id: Uuid, index: usize) {
+        let lobbies = self.visible_lobbies();
+        if lobbies.is_empty() {
+            return;
+        }
+        let i = lobbies
+            .iter()
+            .position(|id| id.eq(&lobby_id))
+            .unwrap_or(index.min(lobbies.len() - 1));
+        self.select_lobby_entry(i);
+    }
+
+    /// # On tick
+    ///
+    /// Advances the encryption animation of all rows in the visible window.
+    /// Rows outside of it continue animating once they are scrolled into
+    /// view.
     pub fn on_tick(&mut self) {
+        let window = self.visible_window();
+        let visible_rows = self
+            .listed_lobbies()
+            .skip(window.start)
+            .take(window.len())
+            .cloned()
+            .collect::<Vec<_>>();
         let mut encryptions_to_delete = vec![];
 
-        // Zip the three encryption vectors to iterate over triplets.
-        for (((id, name), player_count), status) in self
-            .encrypted_names
-            .iter_mut()
-            .zip(self.encrypted_player_counts.values_mut())
-            .zip(self.encrypted_status.values_mut())
-        {
+        for id in visible_rows {
+            let (Some(name), Some(player_count), Some(challenge), Some(status)) = (
+                self.encrypted_names.get_mut(&id),
+                self.encrypted_player_counts.get_mut(&id),
+                self.encrypted_challenges.get_mut(&id),
+                self.encrypted_status.get_mut(&id),
+            ) else {
+                continue;
+            };
             let name_finished = match name.action {
                 EncryptionAction::Joined => {
-                    if name.index < name.value.len() {
-                        name.index += 1;
-                    }
+                    name.reveal(self.animation_speed);
                     false
                 }
-                EncryptionAction::Left => name.value.pop().is_none(),
+                EncryptionAction::Left => name.hide(self.animation_speed),
             };
             let player_count_finished = match player_count.action {
                 EncryptionAction::Joined => {
-                    if player_count.index < player_count.value.len() {
-                        player_count.index += 1;
-                    }
+                    player_count.reveal(self.animation_speed);
+                    false
+                }
+                EncryptionAction::Left => player_count.hide(self.animation_speed),
+            };
+            let challenge_finished = match challenge.action {
+                EncryptionAction::Joined => {
+                    challenge.reveal(self.animation_speed);
                     false
                 }
-                EncryptionAction::Left => player_count.value.pop().is_none(),
+                EncryptionAction::Left => challenge.hide(self.animation_speed),
             };
             let status_finished = match status.action {
                 EncryptionAction::Joined => {
-                    if status.index < status.value.len() {
-                        status.index += 1;
-                    }
+                    status.reveal(self.animation_speed);
                     false
                 }
-                EncryptionAction::Left => status.value.pop().is_none(),
+                EncryptionAction::Left => status.hide(self.animation_speed),
             };
-            // Only delete encryptions if the encryptions for all three fields
+            // Only delete encryptions if the encryptions for all four fields
             // are finished animating.
-            if name_finished && player_count_finished && status_finished {
-                encryptions_to_delete.push(*id);
+            if name_finished && player_count_finished && challenge_finished && status_finished {
+                encryptions_to_delete.push(id);
             }
         }
-        for id in encryptions_to_delete {
-            self.encrypted_names.remove(&id);
-            self.encrypted_player_counts.remove(&id);
-            self.encrypted_status.remove(&id);
+        self.remove_encryptions(&encryptions_to_delete);
+    }
+
+    /// # Remove encryptions
+    ///
+    /// Removes the rows of the given lobbies from the lobby table.
+    fn remove_encryptions(&mut self, lobby_ids: &[Uuid]) {
+        if lobby_ids.is_empty() {
+            return;
         }
+        self.order.retain(|id| !lobby_ids.contains(id));
+        for id in lobby_ids {
+            self.encrypted_names.remove(id);
+            self.encrypted_player_counts.remove(id);
+            self.encrypted_challenges.remove(id);
+            self.encrypted_status.remove(id);
+        }
+    }
+}
+
+/// # Challenge label
+///
+/// Names the challenge of a lobby along with its language.
+fn challenge_label(lobby: &LobbyListItem) -> String {
+    format!("{} ({})", lobby.challenge, lobby.language)
+}
+
+/// # Status rank
+///
+/// Orders lobbies by how soon a user is able to play in them.
+fn status_rank(status: &LobbyStatus) -> u8 {
+    match status {
+        LobbyStatus::WaitingForPlayers => 0,
+        LobbyStatus::AboutToStart(_) => 1,
+        LobbyStatus::InProgress(_) => 2,
+        LobbyStatus::Paused { .. } => 3,
+        LobbyStatus::Finish(_) => 4,
     }
 }
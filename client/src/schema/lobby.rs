@@ -1,15 +1,22 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use common::{
-    BackendMessage, ChallengeFiles, ClientMessage, JoinMode, LobbyInformation, LobbyStatus, Player,
+    BackendMessage, ChallengeFiles, ClientMessage, CreateLobbyOptions, FinalStanding, JoinMode,
+    LobbyInformation, LobbyStatus, Player,
 };
 use futures_util::{
     stream::{SplitSink, SplitStream},
     SinkExt, StreamExt,
 };
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use portable_pty::ExitStatus;
 use ratatui::layout::{Direction, Size};
+use reqwest::{StatusCode, Url};
 use tokio::{
     net::TcpStream,
     sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
@@ -19,11 +26,23 @@ use uuid::Uuid;
 
 use super::{
     chat::Chat,
-    editor::Editor,
+    connection::next_backend_message,
+    diff::Diff,
+    editor::{Editor, EditorKind},
     encryption::{Encryption, EncryptionAction},
+    replay::{Recording, Replay},
+    results::GameResults,
+    terminal::{challenge_directory, ChallengeFile},
 };
+#[cfg(feature = "audio")]
+use crate::audio::Audio;
 use crate::{
     app::AppMessage,
+    config::{Config, Service},
+    constants::{
+        EDITOR_RESTART_BACKOFF, LOBBY_RECONNECT_INTERVAL, MAX_EDITOR_RATIO, MAX_EDITOR_RESTARTS,
+        MAX_LOBBY_RECONNECT_ATTEMPTS, MIN_EDITOR_RATIO, PLAYER_ACTIVE_HIGHLIGHT,
+    },
     schema::{
         focused_component::{ComponentKind, FocusedComponent},
         goal::Goal,
@@ -33,59 +52,273 @@ use crate::{
 #[derive(Debug)]
 pub enum LobbyMessage {
     CloseConnection,
-    EditorTerminated,
-    GoalTerminated,
-    AssignOwner { id: Uuid },
+    EditorTerminated {
+        status: ExitStatus,
+    },
+    GoalTerminated {
+        status: ExitStatus,
+    },
+    /// Restarts a crashed editor after its restart delay.
+    RestartEditor,
+    /// Restarts a crashed goal editor after its restart delay.
+    RestartGoal,
+    /// Tries to reconnect to the lobby after the connection dropped.
+    Reconnect {
+        attempt: u32,
+    },
+    /// Replaces the lobby state with the one sent after a reconnect.
+    UpdateLobbyInformation(Box<LobbyInformation>),
+    AssignOwner {
+        id: Uuid,
+    },
     PlayerJoined(Player),
     PlayerLeft(Uuid),
     ReceiveMessage(String),
+    /// The chat messages sent before joining the lobby.
+    ReceiveChatHistory(Vec<String>),
     RequestStart,
-    StatusUpdate { status: LobbyStatus },
-    SendMessage { message: String },
-    SendProgress { progress: Vec<u8> },
-    SetLocalPlayerId { id: Uuid },
-    UpdatePlayerProgress { player_id: Uuid, progress: f64 },
+    ForceStart,
+    CancelStart,
+    ToggleReady,
+    TogglePause,
+    ToggleAutoStart,
+    StatusUpdate {
+        status: LobbyStatus,
+    },
+    SendMessage {
+        message: String,
+    },
+    SendProgress {
+        progress: Vec<u8>,
+    },
+    SetLocalPlayerId {
+        id: Uuid,
+        token: Uuid,
+    },
+    UpdatePlayerProgress {
+        player_id: Uuid,
+        progress: f64,
+        edits_per_minute: f64,
+    },
+    UpdatePlayerReady {
+        player_id: Uuid,
+        ready: bool,
+    },
+    /// A waiting player takes part in the games from now on.
+    PromotePlayer {
+        player_id: Uuid,
+    },
+    /// The owner turned the automatic start on or off.
+    UpdateAutoStart {
+        auto_start: bool,
+    },
+    /// A player edited his file.
+    PlayerActive {
+        player_id: Uuid,
+    },
+    /// A player reached the goal file.
+    PlayerFinished {
+        player_id: Uuid,
+        position: usize,
+        duration: Duration,
+    },
+    /// Shows the results of the finished game.
+    FinalStandings(Vec<FinalStanding>),
+    KickPlayer {
+        player_id: Uuid,
+    },
+    TransferOwnership {
+        player_id: Uuid,
+    },
+    /// Asks the backend to rename the lobby.
+    RenameLobby {
+        name: String,
+    },
+    /// The owner renamed the lobby.
+    UpdateLobbyName(String),
 }
 
 pub struct Lobby {
+    pub id: Uuid,
     pub name: String,
+    /// The name typed into the rename popup.
+    pub name_input: String,
     pub owner: Option<Uuid>,
     pub players: BTreeMap<Uuid, Player>,
+    /// The player selected in the focused player list.
+    pub selected_player: Option<Uuid>,
+    /// The snapshots of the player file taken during the latest game.
+    pub recording: Option<Recording>,
+    /// Plays the recording back in place of the editor.
+    pub replay: Option<Replay>,
+    /// The players who recently edited their file and when their latest
+    /// activity arrived. Their gauges are highlighted for a short time.
+    pub active_players: BTreeMap<Uuid, Instant>,
     pub local_player: Option<Uuid>,
+    /// Allows to reconnect to the lobby after an accidental disconnect.
+    pub token: Option<Uuid>,
     pub encryptions: BTreeMap<Uuid, Encryption>,
     pub waiting_encryptions: BTreeMap<Uuid, Encryption>,
+    /// The number of characters the encryptions reveal per tick.
+    pub animation_speed: usize,
     pub chat: Chat,
     pub ws_tx: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
     pub tx: UnboundedSender<LobbyMessage>,
     pub rx: UnboundedReceiver<LobbyMessage>,
-    /// An instance of the users default editor with full interactivity.
-    pub editor: Editor,
+    pub app_tx: UnboundedSender<AppMessage>,
+    /// The latest progress sent to the backend. Is sent again after a
+    /// reconnect, as it might have been lost.
+    pub last_progress: Option<Vec<u8>>,
+    /// The differences between the latest progress and the goal file.
+    pub diff: Diff,
+    /// An instance of the users default editor with full interactivity. Is
+    /// `None` while spectating.
+    pub editor: Option<Editor>,
     /// An instance of the users default editor only capable of resizing.
     pub goal: Goal,
     pub app_size: Size,
     pub challenge_files: ChallengeFiles,
     pub status: LobbyStatus,
+    /// Whether the game starts on its own once all players are ready.
+    pub auto_start: bool,
+    /// The results of the finished game. Shown until the lobby waits for
+    /// players again.
+    pub final_standings: Option<Vec<FinalStanding>>,
+    /// The position and time of every player who reached the goal file in
+    /// the current game.
+    pub finished_players: BTreeMap<Uuid, (usize, Duration)>,
+    /// The whole seconds left of the start countdown that were announced
+    /// last. Makes sure every second ticks only once.
+    #[cfg(feature = "audio")]
+    pub countdown_second: Option<i64>,
     /// Whether to display the two editors horizontally or vertically next to
     /// each other.
     pub terminal_layout_direction: Direction,
+    /// The share of the terminal area taken by the editor. The goal takes
+    /// the rest.
+    pub editor_ratio: f64,
+    /// Whether to use the built-in editor instead of an external one.
+    pub use_builtin_editor: bool,
+    /// Whether the user only watches the lobby without playing.
+    pub spectating: bool,
+    /// The command used to start the external editor.
+    pub editor_command: String,
+    /// Additional arguments passed to the external editor.
+    pub editor_args: Vec<String>,
+    /// Where to reach the backend service.
+    pub service: Service,
+    /// Set while the user resets the editor, so the resulting termination is
+    /// not mistaken for a crash.
+    pub editor_reset_requested: bool,
+    /// Whether quitting the editor disconnects from the lobby instead of
+    /// restarting the editor.
+    pub disconnect_on_editor_exit: bool,
+    /// Where to export the results of finished games. Nothing is exported if
+    /// unset.
+    pub results_directory: Option<PathBuf>,
+    /// Restart state of the editor after crashes.
+    pub editor_restarts: Restarts,
+    /// Restart state of the goal editor after crashes.
+    pub goal_restarts: Restarts,
+}
+
+/// # Restarts
+///
+/// Tracks consecutive crashes of an editor process to back off its restarts.
+#[derive(Debug, Default)]
+pub struct Restarts {
+    pub count: u32,
+    /// Describes the last crash. Is `None` while the editor is running
+    /// normally.
+    pub error: Option<String>,
+}
+
+impl Restarts {
+    /// # Handle crash
+    ///
+    /// Records a crash and returns the delay after which the editor should be
+    /// restarted. Returns `None` if the editor crashed too often.
+    pub fn handle_crash(&mut self, status: &ExitStatus) -> Option<Duration> {
+        self.count += 1;
+        if self.count > MAX_EDITOR_RESTARTS {
+            self.error = Some(format!(
+                "Exited with status: {status}. Gave up restarting after {} attempts.",
+                MAX_EDITOR_RESTARTS
+            ));
+            return None;
+        }
+        let delay = EDITOR_RESTART_BACKOFF * 2u32.pow(self.count - 1);
+        self.error = Some(format!(
+            "Exited with status: {status}. Restarting in {}s.",
+            delay.as_secs()
+        ));
+        Some(delay)
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.error = None;
+    }
 }
 
 impl Lobby {
     /// # Create new lobby connection
     ///
     /// Connects the player to the backend. Depending on `join_mode` creates or joins a lobby.
+    /// Editor and goal are arranged by the given layout direction and split
+    /// by the given editor ratio.
     pub async fn new(
         app_tx: UnboundedSender<AppMessage>,
         join_mode: JoinMode,
+        options: CreateLobbyOptions,
         app_size: Size,
+        terminal_layout_direction: Direction,
+        editor_ratio: f64,
+        config: &Config,
     ) -> Result<Self> {
+        let service = config.general.service.clone();
+        let use_builtin_editor = config.general.use_builtin_editor;
+        let editor_command = config.general.editor.clone();
+        let editor_args = config.general.editor_args.clone();
+
         // First, fetch lobby information of the lobby we want to join.
-        let url = format!("http://127.0.0.1:3030/lobbies/{}", join_mode);
-        let lobby_information = reqwest::get(url).await?.json::<LobbyInformation>().await?;
+        let url = service.http_url(&format!("lobbies/{}", join_mode));
+        let response = reqwest::Client::new()
+            .get(url)
+            .query(&options)
+            .send()
+            .await?;
+        // The backend explains why there is no lobby, e.g., because the lobby
+        // was removed after it was selected.
+        let status = response.status();
+        if status != StatusCode::OK {
+            let reason = match response.json::<BackendMessage>().await {
+                Ok(BackendMessage::ServerFull) => {
+                    "The server is full. Try again later.".to_string()
+                }
+                Ok(BackendMessage::LobbyNotFound) => "Lobby no longer exists.".to_string(),
+                Ok(BackendMessage::InvalidLobbyOptions(reason)) => reason,
+                _ => format!("The backend service is unavailable ({status})."),
+            };
+            return Err(anyhow!(reason));
+        }
+        let lobby_information = response
+            .json::<LobbyInformation>()
+            .await
+            .map_err(|e| anyhow!("Unable to read the lobby information: {e}"))?;
 
-        // Connect to lobby with given join mode.
-        let url = format!("ws://127.0.0.1:3030/players/{}", lobby_information.id);
-        let (ws_stream, _) = connect_async(url).await?;
+        // Connect to lobby with given join mode. Spectators use their own
+        // route, as they never take a seat in the lobby.
+        let spectating = matches!(join_mode, JoinMode::Spectate { .. });
+        let route = if spectating { "spectators" } else { "players" };
+        let mut url = Url::parse(&service.ws_url(&format!("{}/{}", route, lobby_information.id)))?;
+        // Spectators never take a seat, so they go without a name.
+        if !spectating {
+            if let Some(ref name) = config.general.player_name {
+                url.query_pairs_mut().append_pair("name", name);
+            }
+        }
+        let (ws_stream, _) = connect_async(url.as_str()).await?;
 
         // Setup messaging channels.
         let (ws_tx, ws_rx) = ws_stream.split();
@@ -97,67 +330,201 @@ impl Lobby {
             ws_rx,
             message_tx,
             app_tx.clone(),
+            spectating,
         ));
 
         debug!("{:?}", lobby_information);
 
-        let mut encryptions = BTreeMap::new();
-        let mut waiting_encryptions = BTreeMap::new();
-        for (id, player) in lobby_information.players.iter() {
-            let mut encryption = Encryption {
-                action: EncryptionAction::Joined,
-                index: 0,
-                value: player.name.clone(),
-            };
-            if lobby_information
-                .owner
-                .is_some_and(|owner_id| owner_id.eq(id))
-            {
-                encryption.value.push_str(" (owner)");
-            }
-            if player.waiting {
-                waiting_encryptions.insert(*id, encryption);
-            } else {
-                encryptions.insert(*id, encryption);
-            }
-        }
+        let (encryptions, waiting_encryptions) = Lobby::build_encryptions(&lobby_information);
 
-        let mut editor = Editor::new(
-            app_size,
-            tx.clone(),
-            lobby_information.challenge_files.start_file.clone(),
-            false,
-        )?;
-        let terminal_layout_direction = Direction::Vertical;
-        editor.resize(app_size.height, app_size.width, terminal_layout_direction)?;
+        // Spectators can not edit, so neither spawn an editor nor watch its
+        // progress.
+        let challenge_files = &lobby_information.challenge_files;
+        let editor = if spectating {
+            None
+        } else {
+            let start_file = ChallengeFile {
+                content: challenge_files.start_file.clone(),
+                directory: challenge_directory(lobby_information.id),
+                extension: challenge_files.extension.clone(),
+                content_type: challenge_files.content_type,
+            };
+            let mut editor = Editor::new(
+                app_size,
+                tx.clone(),
+                start_file,
+                challenge_files.cursor,
+                false,
+                use_builtin_editor,
+                &editor_command,
+                &editor_args,
+            )?;
+            editor.resize(
+                app_size.height,
+                app_size.width,
+                terminal_layout_direction,
+                editor_ratio,
+            )?;
+            Some(editor)
+        };
+        let goal_file = ChallengeFile {
+            content: challenge_files.goal_file.clone(),
+            directory: challenge_directory(lobby_information.id),
+            extension: challenge_files.extension.clone(),
+            content_type: challenge_files.content_type,
+        };
         let mut goal = Goal::new(
             app_size,
             tx.clone(),
-            lobby_information.challenge_files.goal_file.clone(),
+            goal_file,
             false,
+            use_builtin_editor,
+            &editor_command,
+            &editor_args,
+        )?;
+        goal.resize(
+            app_size.height,
+            app_size.width,
+            terminal_layout_direction,
+            editor_ratio,
         )?;
-        goal.resize(app_size.height, app_size.width, terminal_layout_direction)?;
 
         Ok(Self {
+            id: lobby_information.id,
             name: lobby_information.name,
+            name_input: String::new(),
             owner: lobby_information.owner,
             players: lobby_information.players,
+            selected_player: None,
+            recording: None,
+            replay: None,
+            active_players: BTreeMap::new(),
+            finished_players: BTreeMap::new(),
+            #[cfg(feature = "audio")]
+            countdown_second: None,
             local_player: None,
+            token: None,
             encryptions,
             waiting_encryptions,
-            chat: Chat::new(tx.clone()),
+            animation_speed: config.general.animation_speed,
+            chat: Chat::new(tx.clone(), config.general.max_chat_messages),
             ws_tx,
             tx,
             rx,
+            app_tx,
+            last_progress: None,
+            diff: Diff::default(),
             editor,
             goal,
             app_size,
             challenge_files: lobby_information.challenge_files,
             status: lobby_information.status,
+            auto_start: lobby_information.auto_start,
+            final_standings: None,
             terminal_layout_direction,
+            editor_ratio,
+            use_builtin_editor,
+            spectating,
+            editor_command,
+            editor_args,
+            service,
+            editor_reset_requested: false,
+            disconnect_on_editor_exit: config.general.disconnect_on_editor_exit,
+            results_directory: config.general.results_directory.clone(),
+            editor_restarts: Restarts::default(),
+            goal_restarts: Restarts::default(),
         })
     }
 
+    /// # Build encryptions
+    ///
+    /// Creates the name animations of all active and waiting players.
+    fn build_encryptions(
+        lobby_information: &LobbyInformation,
+    ) -> (BTreeMap<Uuid, Encryption>, BTreeMap<Uuid, Encryption>) {
+        let mut encryptions = BTreeMap::new();
+        let mut waiting_encryptions = BTreeMap::new();
+        for (id, player) in lobby_information.players.iter() {
+            let encryption = Encryption {
+                action: EncryptionAction::Joined,
+                index: 0,
+                value: player.name.clone(),
+            };
+            if player.waiting {
+                waiting_encryptions.insert(*id, encryption);
+            } else {
+                encryptions.insert(*id, encryption);
+            }
+        }
+        (encryptions, waiting_encryptions)
+    }
+
+    /// # Reconnect
+    ///
+    /// Opens a new connection to the lobby with the token of the dropped one,
+    /// which restores the seat of the player. Retries a few times before
+    /// giving up and leaving the lobby.
+    pub async fn reconnect(&mut self, attempt: u32) -> Result<()> {
+        let Some(token) = self.token else {
+            self.app_tx
+                .send(AppMessage::DisconnectLobby { notice: None })?;
+            return Ok(());
+        };
+        let url = self
+            .service
+            .ws_url(&format!("players/{}?token={}", self.id, token));
+        match connect_async(url).await {
+            Ok((ws_stream, _)) => {
+                let (ws_tx, ws_rx) = ws_stream.split();
+                self.ws_tx = ws_tx;
+                tokio::spawn(Lobby::handle_backend_message(
+                    ws_rx,
+                    self.tx.clone(),
+                    self.app_tx.clone(),
+                    self.spectating,
+                ));
+                info!("Reconnected to lobby {}.", self.name);
+                self.chat
+                    .add_message("Reconnected to the lobby.".to_string());
+
+                // Progress sent while being disconnected is lost.
+                if let Some(progress) = self.last_progress.clone() {
+                    self.tx.send(LobbyMessage::SendProgress { progress })?;
+                }
+            }
+            Err(e) if attempt < MAX_LOBBY_RECONNECT_ATTEMPTS => {
+                warn!(
+                    "Unable to reconnect to lobby {} (attempt {}): {e}",
+                    self.name,
+                    attempt + 1
+                );
+                let tx = self.tx.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(LOBBY_RECONNECT_INTERVAL).await;
+                    let _ = tx.send(LobbyMessage::Reconnect {
+                        attempt: attempt + 1,
+                    });
+                });
+            }
+            Err(e) => {
+                error!("Giving up reconnecting to lobby {}: {e}", self.name);
+                self.app_tx
+                    .send(AppMessage::DisconnectLobby { notice: None })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// # Send
+    ///
+    /// Sends a message to the backend. Failures are only logged, as the
+    /// connection might currently be reconnecting.
+    async fn send(&mut self, message: ClientMessage) {
+        if let Err(e) = self.ws_tx.send(message.into()).await {
+            warn!("Unable to send message to lobby {}: {e}", self.name);
+        }
+    }
+
     pub async fn handle_message(&mut self, msg: LobbyMessage) -> Result<()> {
         debug!("Handle message {:?}.", msg);
 
@@ -169,9 +536,6 @@ impl Lobby {
                         "Assigned player {} with ID {} lobby owner.",
                         player.name, player.id
                     );
-                    if let Some(owner_encryption) = self.encryptions.get_mut(&id) {
-                        owner_encryption.value.push_str(" (owner)");
-                    }
                 } else {
                     error!("New lobby owner with ID {} was not found!", id);
                 }
@@ -195,11 +559,18 @@ impl Lobby {
                     self.encryptions.insert(player.id, encryption);
                 }
                 self.players.insert(player.id, player);
+
+                #[cfg(feature = "audio")]
+                self.app_tx
+                    .send(AppMessage::PlayAudio(Audio::PlayerJoined))?;
             }
             LobbyMessage::PlayerLeft(id) => {
                 if let Some(player) = self.players.remove(&id) {
                     info!("Player {} left the lobby.", player.name);
                     self.chat.add_message(format!("{} left!", player.name));
+
+                    #[cfg(feature = "audio")]
+                    self.app_tx.send(AppMessage::PlayAudio(Audio::PlayerLeft))?;
                 } else {
                     error!("Tried to remove a non-existent player with ID {}.", id);
                 }
@@ -216,68 +587,178 @@ impl Lobby {
             LobbyMessage::ReceiveMessage(msg) => {
                 self.chat.add_message(msg);
             }
+            LobbyMessage::ReceiveChatHistory(messages) => {
+                for msg in messages {
+                    self.chat.add_message(msg);
+                }
+            }
             LobbyMessage::SendMessage { message } => {
-                self.ws_tx
-                    .send(ClientMessage::SendMessage { message }.into())
-                    .await?;
+                self.send(ClientMessage::SendMessage { message }).await;
             }
-            LobbyMessage::SetLocalPlayerId { id } => {
+            LobbyMessage::SetLocalPlayerId { id, token } => {
                 info!("Received local player ID {} from the backend.", id);
                 self.local_player = Some(id);
-
-                if let Some(local_player) = self
-                    .encryptions
-                    .get_mut(&id)
-                    .or(self.waiting_encryptions.get_mut(&id))
+                self.token = Some(token);
+            }
+            LobbyMessage::EditorTerminated { status } => {
+                // Leave the lobby if the user quit the editor on purpose.
+                // Crashes still restart the editor.
+                if status.success()
+                    && !self.editor_reset_requested
+                    && self.disconnect_on_editor_exit
                 {
-                    local_player.value.push_str(" (you)");
+                    info!("The editor was quit, disconnecting from the lobby.");
+                    self.app_tx
+                        .send(AppMessage::DisconnectLobby { notice: None })?;
+                    return Ok(());
+                }
+                // Restart the editor directly if the user quit it or reset it.
+                if status.success() || self.editor_reset_requested {
+                    self.editor_reset_requested = false;
+                    self.editor_restarts.reset();
+                    self.restart_editor()?;
+                    return Ok(());
+                }
+                error!("The editor crashed with status: {status}");
+                // Otherwise, back off the restart.
+                if let Some(delay) = self.editor_restarts.handle_crash(&status) {
+                    let tx = self.tx.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        let _ = tx.send(LobbyMessage::RestartEditor);
+                    });
                 }
             }
-            LobbyMessage::EditorTerminated => {
-                // Restart the editor if it terminates.
-                self.editor = Editor::new(
-                    self.app_size,
-                    self.tx.clone(),
-                    self.challenge_files.start_file.clone(),
-                    self.editor.is_full_screen,
-                )?;
-                self.editor.resize(
-                    self.app_size.height,
-                    self.app_size.width,
-                    self.terminal_layout_direction,
-                )?;
-            }
-            LobbyMessage::GoalTerminated => {
-                // Restart the goal editor if it terminates.
-                self.goal = Goal::new(
-                    self.app_size,
-                    self.tx.clone(),
-                    self.challenge_files.goal_file.clone(),
-                    self.goal.is_full_screen,
-                )?;
-                self.goal.resize(
-                    self.app_size.height,
-                    self.app_size.width,
-                    self.terminal_layout_direction,
-                )?;
+            LobbyMessage::GoalTerminated { status } => {
+                if status.success() {
+                    self.goal_restarts.reset();
+                    self.restart_goal()?;
+                    return Ok(());
+                }
+                error!("The goal editor crashed with status: {status}");
+                if let Some(delay) = self.goal_restarts.handle_crash(&status) {
+                    let tx = self.tx.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        let _ = tx.send(LobbyMessage::RestartGoal);
+                    });
+                }
+            }
+            LobbyMessage::RestartEditor => {
+                // The user might have reset the editor in the meantime.
+                if self.editor_restarts.error.take().is_some() {
+                    self.restart_editor()?;
+                }
+            }
+            LobbyMessage::RestartGoal => {
+                if self.goal_restarts.error.take().is_some() {
+                    self.restart_goal()?;
+                }
             }
             LobbyMessage::RequestStart => {
-                self.ws_tx.send(ClientMessage::RequestStart.into()).await?;
+                self.send(ClientMessage::RequestStart).await;
+            }
+            LobbyMessage::ForceStart => {
+                self.send(ClientMessage::ForceStart).await;
+            }
+            LobbyMessage::CancelStart => {
+                self.send(ClientMessage::CancelStart).await;
+            }
+            LobbyMessage::ToggleReady => {
+                self.send(ClientMessage::ToggleReady).await;
+            }
+            LobbyMessage::TogglePause => {
+                self.send(ClientMessage::TogglePause).await;
+            }
+            LobbyMessage::ToggleAutoStart => {
+                self.send(ClientMessage::ToggleAutoStart).await;
+            }
+            LobbyMessage::KickPlayer { player_id } => {
+                self.send(ClientMessage::KickPlayer { player_id }).await;
+            }
+            LobbyMessage::TransferOwnership { player_id } => {
+                self.send(ClientMessage::TransferOwnership { player_id })
+                    .await;
+            }
+            LobbyMessage::RenameLobby { name } => {
+                self.send(ClientMessage::RenameLobby { name }).await;
+            }
+            LobbyMessage::UpdateLobbyName(name) => {
+                info!("Lobby {} was renamed to {}.", self.name, name);
+                self.name = name;
+            }
+            LobbyMessage::Reconnect { attempt } => {
+                self.reconnect(attempt).await?;
+            }
+            LobbyMessage::UpdateLobbyInformation(lobby_information) => {
+                let (encryptions, waiting_encryptions) =
+                    Lobby::build_encryptions(&lobby_information);
+                self.encryptions = encryptions;
+                self.waiting_encryptions = waiting_encryptions;
+                self.owner = lobby_information.owner;
+                self.players = lobby_information.players;
+                self.status = lobby_information.status;
             }
             LobbyMessage::StatusUpdate { status } => {
+                if !matches!(status, LobbyStatus::Finish(_)) {
+                    self.final_standings = None;
+                }
+                // The lobby was reset after the game.
+                if status == LobbyStatus::WaitingForPlayers {
+                    self.clear_finished_players();
+                }
+                // Record the player file from the start of each game on.
+                if matches!(self.status, LobbyStatus::AboutToStart(_))
+                    && matches!(status, LobbyStatus::InProgress(_))
+                {
+                    self.replay = None;
+                    self.clear_finished_players();
+                    #[cfg(feature = "audio")]
+                    self.app_tx
+                        .send(AppMessage::PlayAudio(Audio::CountdownGo))?;
+                    if !self.spectating && !self.is_waiting() {
+                        self.recording =
+                            Some(Recording::new(self.challenge_files.start_file.clone()));
+                    }
+                }
                 self.status = status;
             }
+            LobbyMessage::FinalStandings(standings) => {
+                if let Some(ref directory) = self.results_directory {
+                    GameResults {
+                        lobby_name: &self.name,
+                        challenge: &self.challenge_files.name,
+                        finished_at: chrono::Utc::now(),
+                        standings: &standings,
+                    }
+                    .export(directory);
+                }
+                self.final_standings = Some(standings);
+            }
             LobbyMessage::SendProgress { progress } => {
-                self.ws_tx
-                    .send(ClientMessage::Progress { progress }.into())
-                    .await?;
+                self.diff.update(&progress, &self.challenge_files.goal_file);
+                // The backend only accepts progress of running lobbies and
+                // players taking part in the game.
+                let LobbyStatus::InProgress(_) = self.status else {
+                    return Ok(());
+                };
+                if self.is_waiting() {
+                    return Ok(());
+                }
+                self.last_progress = Some(progress.clone());
+                if let Some(ref mut recording) = self.recording {
+                    recording.record(progress.clone());
+                }
+                self.send(ClientMessage::Progress { progress }).await;
             }
             LobbyMessage::UpdatePlayerProgress {
                 player_id,
                 progress,
+                edits_per_minute,
             } => {
                 if let Some(player) = self.players.get_mut(&player_id) {
                     player.progress = progress;
+                    player.edits_per_minute = edits_per_minute;
                 } else {
                     error!(
                         "Tried to update progress of non-existent player with ID {}.",
@@ -285,6 +766,53 @@ impl Lobby {
                     );
                 }
             }
+            LobbyMessage::UpdatePlayerReady { player_id, ready } => {
+                if let Some(player) = self.players.get_mut(&player_id) {
+                    player.ready = ready;
+                } else {
+                    error!(
+                        "Tried to update readiness of non-existent player with ID {}.",
+                        player_id
+                    );
+                }
+            }
+            LobbyMessage::PromotePlayer { player_id } => {
+                let Some(player) = self.players.get_mut(&player_id) else {
+                    error!(
+                        "Tried to promote non-existent player with ID {}.",
+                        player_id
+                    );
+                    return Ok(());
+                };
+                player.waiting = false;
+                info!("Promoted waiting player {}.", player.name);
+                // Move the player from the waiting room to the gauges.
+                if let Some(encryption) = self.waiting_encryptions.remove(&player_id) {
+                    self.encryptions.insert(player_id, encryption);
+                }
+            }
+            LobbyMessage::UpdateAutoStart { auto_start } => {
+                self.auto_start = auto_start;
+            }
+            LobbyMessage::PlayerActive { player_id } => {
+                self.active_players.insert(player_id, Instant::now());
+            }
+            LobbyMessage::PlayerFinished {
+                player_id,
+                position,
+                duration,
+            } => {
+                // Celebrate once the local player reaches the goal.
+                #[cfg(feature = "audio")]
+                if self.local_player.eq(&Some(player_id)) {
+                    self.app_tx.send(AppMessage::PlayAudio(Audio::Won))?;
+                }
+                if let Some(player) = self.players.get_mut(&player_id) {
+                    player.position = Some(position);
+                }
+                self.finished_players
+                    .insert(player_id, (position, duration));
+            }
         }
         Ok(())
     }
@@ -293,47 +821,83 @@ impl Lobby {
         mut ws_rx: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
         message_tx: UnboundedSender<LobbyMessage>,
         app_tx: UnboundedSender<AppMessage>,
+        spectating: bool,
     ) -> Result<()> {
-        while let Some(Ok(msg)) = ws_rx.next().await {
+        while let Some(msg) = next_backend_message(&mut ws_rx, &app_tx).await {
             debug!("Handle backend message {:?}.", msg);
 
-            if msg.is_close() {
-                return Ok(());
-            }
             let backend_message: BackendMessage = msg.into();
             match backend_message {
-                BackendMessage::ProvidePlayerId { id } => {
-                    message_tx.send(LobbyMessage::SetLocalPlayerId { id })?;
+                BackendMessage::ProvidePlayerId { id, token } => {
+                    message_tx.send(LobbyMessage::SetLocalPlayerId { id, token })?;
+                }
+                BackendMessage::SendLobbyInformation(lobby_information) => {
+                    message_tx.send(LobbyMessage::UpdateLobbyInformation(Box::new(
+                        lobby_information,
+                    )))?;
+                }
+                BackendMessage::ReconnectFailed => {
+                    app_tx.send(AppMessage::DisconnectLobby { notice: None })?;
+                    return Ok(());
                 }
                 BackendMessage::AssignOwner { id } => {
                     message_tx.send(LobbyMessage::AssignOwner { id })?;
                 }
+                BackendMessage::UpdateLobbyName { name, .. } => {
+                    message_tx.send(LobbyMessage::UpdateLobbyName(name))?;
+                }
+                // The backend closes the connection on shutdown and when the
+                // player was kicked. Either way, leave the lobby instead of
+                // trying to get the seat back.
                 BackendMessage::CloseConnection => {
                     message_tx.send(LobbyMessage::CloseConnection)?;
+                    app_tx.send(AppMessage::DisconnectLobby {
+                        notice: Some("The backend closed the lobby connection.".to_string()),
+                    })?;
+                    return Ok(());
                 }
                 BackendMessage::SendMessage(msg) => {
                     message_tx.send(LobbyMessage::ReceiveMessage(msg))?;
                 }
+                BackendMessage::ChatHistory(messages) => {
+                    message_tx.send(LobbyMessage::ReceiveChatHistory(messages))?;
+                }
                 BackendMessage::AddPlayer(player) => {
                     message_tx.send(LobbyMessage::PlayerJoined(player))?;
                 }
                 BackendMessage::RemovePlayer(player_id) => {
                     message_tx.send(LobbyMessage::PlayerLeft(player_id))?;
                 }
-                BackendMessage::LobbyFull => {
-                    app_tx.send(AppMessage::DisconnectLobby)?;
+                BackendMessage::LobbyFull | BackendMessage::ServerFull => {
+                    app_tx.send(AppMessage::DisconnectLobby { notice: None })?;
                 }
                 BackendMessage::LobbyNotWaitingForPlayers => {
-                    app_tx.send(AppMessage::DisconnectLobby)?;
+                    app_tx.send(AppMessage::DisconnectLobby { notice: None })?;
+                }
+                BackendMessage::LobbyNotFound => {
+                    app_tx.send(AppMessage::LobbyNotFound)?;
                 }
-                BackendMessage::ConnectionCounts { clients, players } => {
-                    app_tx.send(AppMessage::ConnectionCounts { clients, players })?;
+                BackendMessage::ConnectionCounts {
+                    clients,
+                    players,
+                    spectators,
+                    lobbies,
+                } => {
+                    app_tx.send(AppMessage::ConnectionCounts {
+                        clients,
+                        players,
+                        spectators,
+                        lobbies,
+                    })?;
                 }
                 BackendMessage::StatusUpdate { status } => {
                     let component_to_focus = match status {
                         LobbyStatus::WaitingForPlayers
                         | LobbyStatus::AboutToStart(_)
-                        | LobbyStatus::Finish(_) => None,
+                        | LobbyStatus::Finish(_)
+                        | LobbyStatus::Paused { .. } => None,
+                        // Spectators have no editor to focus.
+                        LobbyStatus::InProgress(_) if spectating => None,
                         LobbyStatus::InProgress(_) => {
                             Some(FocusedComponent::new(ComponentKind::Editor))
                         }
@@ -344,28 +908,71 @@ impl Lobby {
                 BackendMessage::UpdatePlayerProgress {
                     player_id,
                     progress,
+                    edits_per_minute,
                 } => {
                     message_tx.send(LobbyMessage::UpdatePlayerProgress {
                         player_id,
                         progress,
+                        edits_per_minute,
                     })?;
                 }
+                BackendMessage::UpdatePlayerReady { player_id, ready } => {
+                    message_tx.send(LobbyMessage::UpdatePlayerReady { player_id, ready })?;
+                }
+                BackendMessage::PromotePlayer { player_id } => {
+                    message_tx.send(LobbyMessage::PromotePlayer { player_id })?;
+                }
+                BackendMessage::UpdateAutoStart { auto_start } => {
+                    message_tx.send(LobbyMessage::UpdateAutoStart { auto_start })?;
+                }
+                BackendMessage::PlayerActive { player_id } => {
+                    message_tx.send(LobbyMessage::PlayerActive { player_id })?;
+                }
+                BackendMessage::PlayerFinished {
+                    player_id,
+                    position,
+                    duration,
+                } => {
+                    message_tx.send(LobbyMessage::PlayerFinished {
+                        player_id,
+                        position,
+                        duration,
+                    })?;
+                }
+                BackendMessage::FinalStandings(standings) => {
+                    message_tx.send(LobbyMessage::FinalStandings(standings))?;
+                }
                 _ => {}
             }
         }
 
         // We should only arrive here whenever the WS connection is abruptly
-        // closed. Therefore remove the current lobby here.
-        app_tx.send(AppMessage::DisconnectLobby)?;
+        // closed. Players try to get their seat back, spectators simply
+        // leave the lobby.
+        if spectating {
+            app_tx.send(AppMessage::DisconnectLobby { notice: None })?;
+        } else {
+            message_tx.send(LobbyMessage::Reconnect { attempt: 0 })?;
+        }
         Ok(())
     }
 
     pub fn resize(&mut self, rows: u16, cols: u16) -> Result<()> {
         self.app_size = Size::new(cols, rows);
-        self.goal
-            .resize(rows, cols, self.terminal_layout_direction)?;
-        self.editor
-            .resize(rows, cols, self.terminal_layout_direction)?;
+        self.goal.resize(
+            rows,
+            cols,
+            self.terminal_layout_direction,
+            self.editor_ratio,
+        )?;
+        if let Some(ref mut editor) = self.editor {
+            editor.resize(
+                rows,
+                cols,
+                self.terminal_layout_direction,
+                self.editor_ratio,
+            )?;
+        }
         Ok(())
     }
 
@@ -377,13 +984,9 @@ impl Lobby {
             .chain(self.waiting_encryptions.iter_mut())
         {
             match encryption.action {
-                EncryptionAction::Joined => {
-                    if encryption.index < encryption.value.len() {
-                        encryption.index += 1;
-                    }
-                }
+                EncryptionAction::Joined => encryption.reveal(self.animation_speed),
                 EncryptionAction::Left => {
-                    if encryption.value.pop().is_none() {
+                    if encryption.hide(self.animation_speed) {
                         encryptions_to_delete.push(*id);
                     }
                 }
@@ -394,6 +997,103 @@ impl Lobby {
                 .remove(&id)
                 .or(self.waiting_encryptions.remove(&id));
         }
+
+        if self.replay.as_ref().is_some_and(Replay::is_finished) {
+            self.replay = None;
+        }
+
+        // Fade the highlight of players who stopped editing.
+        self.active_players
+            .retain(|_, active_at| active_at.elapsed() < PLAYER_ACTIVE_HIGHLIGHT);
+
+        // Tick on every whole second of the start countdown.
+        #[cfg(feature = "audio")]
+        if let LobbyStatus::AboutToStart(start_date) = self.status {
+            let remaining_millis = start_date
+                .signed_duration_since(chrono::Utc::now())
+                .num_milliseconds();
+            // Round up, so the tick matches the second shown by the timer.
+            let second = (remaining_millis + 999).div_euclid(1000);
+            if second > 0 && self.countdown_second != Some(second) {
+                self.countdown_second = Some(second);
+                let _ = self
+                    .app_tx
+                    .send(AppMessage::PlayAudio(Audio::CountdownTick));
+            }
+        } else {
+            self.countdown_second = None;
+        }
+    }
+
+    /// # Clear finished players
+    ///
+    /// Forgets the positions and times of the players who finished the
+    /// previous game.
+    fn clear_finished_players(&mut self) {
+        self.finished_players.clear();
+        for player in self.players.values_mut() {
+            player.position = None;
+        }
+    }
+
+    /// # Is waiting
+    ///
+    /// Whether the local player joined after the game was started and waits
+    /// for the next one.
+    pub fn is_waiting(&self) -> bool {
+        self.local_player
+            .and_then(|id| self.players.get(&id))
+            .is_some_and(|player| player.waiting)
+    }
+
+    /// # Name tags
+    ///
+    /// Marks the local player and the lobby owner, e.g., " (you) (owner)".
+    /// The tags are added when rendering, so they always follow the current
+    /// owner.
+    pub fn name_tags(&self, id: &Uuid) -> String {
+        let mut tags = String::new();
+        if self.local_player.as_ref() == Some(id) {
+            tags.push_str(" (you)");
+        }
+        if self.owner.as_ref() == Some(id) {
+            tags.push_str(" (owner)");
+        }
+        tags
+    }
+
+    /// # Select next player
+    ///
+    /// Selects the player after the currently selected one, wrapping around
+    /// at the end of the list.
+    pub fn select_next_player(&mut self) {
+        let next = self
+            .selected_player
+            .and_then(|selected| {
+                self.players
+                    .range(selected..)
+                    .map(|(id, _)| *id)
+                    .find(|id| id.ne(&selected))
+            })
+            .or_else(|| self.players.keys().next().copied());
+        self.selected_player = next;
+    }
+
+    /// # Select previous player
+    ///
+    /// Selects the player before the currently selected one, wrapping around
+    /// at the start of the list.
+    pub fn select_previous_player(&mut self) {
+        let previous = self
+            .selected_player
+            .and_then(|selected| {
+                self.players
+                    .range(..selected)
+                    .next_back()
+                    .map(|(id, _)| *id)
+            })
+            .or_else(|| self.players.keys().next_back().copied());
+        self.selected_player = previous;
     }
 
     pub fn toggle_terminal_layout(&mut self) {
@@ -404,9 +1104,135 @@ impl Lobby {
         }
     }
 
+    /// # Toggle replay
+    ///
+    /// Replays the latest game at the given speed or stops a running replay.
+    pub fn toggle_replay(&mut self, speed: f64) {
+        if self.replay.take().is_some() {
+            return;
+        }
+        self.replay = self
+            .recording
+            .as_ref()
+            .map(|recording| recording.replay(speed));
+    }
+
+    /// # Adjust editor ratio
+    ///
+    /// Grows the editor by the given share of the terminal area, or shrinks
+    /// it for a negative share. The goal takes the rest.
+    pub fn adjust_editor_ratio(&mut self, share: f64) {
+        self.editor_ratio = (self.editor_ratio + share).clamp(MIN_EDITOR_RATIO, MAX_EDITOR_RATIO);
+    }
+
+    /// # Challenge file
+    ///
+    /// Places the given content of a challenge file inside the temporary
+    /// directory of the lobby.
+    fn challenge_file(&self, content: Vec<u8>) -> ChallengeFile {
+        ChallengeFile {
+            content,
+            directory: challenge_directory(self.id),
+            extension: self.challenge_files.extension.clone(),
+            content_type: self.challenge_files.content_type,
+        }
+    }
+
+    /// # Restart editor
+    ///
+    /// Replaces the editor with a new instance opening the untouched start
+    /// file.
+    pub fn restart_editor(&mut self) -> Result<()> {
+        let start_file = self.challenge_file(self.challenge_files.start_file.clone());
+        let Some(ref mut editor) = self.editor else {
+            return Ok(());
+        };
+        *editor = Editor::new(
+            self.app_size,
+            self.tx.clone(),
+            start_file,
+            self.challenge_files.cursor,
+            editor.is_full_screen,
+            self.use_builtin_editor,
+            &self.editor_command,
+            &self.editor_args,
+        )?;
+        editor.resize(
+            self.app_size.height,
+            self.app_size.width,
+            self.terminal_layout_direction,
+            self.editor_ratio,
+        )?;
+        Ok(())
+    }
+
+    /// # Restart goal
+    ///
+    /// Replaces the goal editor with a new instance opening the goal file.
+    pub fn restart_goal(&mut self) -> Result<()> {
+        self.goal = Goal::new(
+            self.app_size,
+            self.tx.clone(),
+            self.challenge_file(self.challenge_files.goal_file.clone()),
+            self.goal.is_full_screen,
+            self.use_builtin_editor,
+            &self.editor_command,
+            &self.editor_args,
+        )?;
+        self.goal.resize(
+            self.app_size.height,
+            self.app_size.width,
+            self.terminal_layout_direction,
+            self.editor_ratio,
+        )?;
+        Ok(())
+    }
+
+    /// # Can reset editor
+    ///
+    /// Whether the player may start over with the untouched start file. Only
+    /// allowed while the game is in progress, so the reset can not be abused
+    /// in between games. Crashed editors can always be reset.
+    pub fn can_reset_editor(&self) -> bool {
+        self.editor.is_some()
+            && (matches!(self.status, LobbyStatus::InProgress(_))
+                || self.editor_restarts.error.is_some())
+    }
+
+    /// # Reset editor
+    ///
+    /// Restarts the editor with the untouched start file, which also sends the
+    /// resulting progress. This also retries editors that crashed too often.
+    pub fn reset_editor(&mut self) -> Result<()> {
+        if !self.can_reset_editor() {
+            return Ok(());
+        }
+        let Some(ref mut editor) = self.editor else {
+            return Ok(());
+        };
+        info!("Reset the editor to the start file.");
+        // A crashed editor has no running process anymore.
+        if self.editor_restarts.error.is_some() {
+            self.editor_restarts.reset();
+            return self.restart_editor();
+        }
+        match editor.kind {
+            // Killing the process triggers the restart via the termination
+            // handler of the editor.
+            EditorKind::Terminal(_) => {
+                self.editor_reset_requested = true;
+                editor.kill()?;
+            }
+            EditorKind::Builtin(_) | EditorKind::Unsupported => self.restart_editor()?,
+        }
+        Ok(())
+    }
+
     pub fn clean_up(&mut self) -> Result<()> {
-        self.goal.terminal.child_killer.kill()?;
-        self.editor.terminal.child_killer.kill()?;
+        self.goal.kill()?;
+        if let Some(ref mut editor) = self.editor {
+            editor.kill()?;
+        }
         Ok(())
     }
 }
@@ -5,7 +5,7 @@ use log::{debug, error, info};
 use reqwest::{Client, StatusCode};
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{app::AppMessage, constants::RECONNECT_INTERVAL};
+use crate::{app::AppMessage, config::Service};
 
 pub struct Offline {
     /// HTTP client to check the service connection.
@@ -14,10 +14,18 @@ pub struct Offline {
     pub dot_count: usize,
     pub last_dot: Instant,
     pub app_tx: UnboundedSender<AppMessage>,
+    /// Where to reach the backend service.
+    pub service: Service,
+    /// The time between two reconnect attempts.
+    pub reconnect_interval: Duration,
 }
 
 impl Offline {
-    pub fn new(app_tx: UnboundedSender<AppMessage>) -> Self {
+    pub fn new(
+        app_tx: UnboundedSender<AppMessage>,
+        service: Service,
+        reconnect_interval: Duration,
+    ) -> Self {
         let client = reqwest::Client::new();
         Self {
             client,
@@ -25,13 +33,16 @@ impl Offline {
             dot_count: 0,
             last_dot: Instant::now(),
             app_tx,
+            service,
+            reconnect_interval,
         }
     }
 
     pub async fn try_reconnect(&self) -> Result<()> {
         debug!("Try reconnect to backend service.");
 
-        let Ok(response) = self.client.get("http://127.0.0.1:3030/health").send().await else {
+        let url = self.service.http_url("health");
+        let Ok(response) = self.client.get(url).send().await else {
             error!("Backend service unreachable.");
             // TODO: Return an error here.
             return Ok(());
@@ -45,8 +56,8 @@ impl Offline {
     }
 
     pub async fn on_tick(&mut self) -> Result<()> {
-        // Try to reconnect every `RECONNECT_INTERVAL`.
-        if self.last_reconnect.elapsed() > RECONNECT_INTERVAL {
+        // Try to reconnect every `reconnect_interval`.
+        if self.last_reconnect.elapsed() > self.reconnect_interval {
             self.try_reconnect().await?;
             self.last_reconnect = Instant::now();
         }
@@ -0,0 +1,378 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use common::{ChallengeFiles, ContentType, CursorPosition};
+use log::{error, info};
+use ratatui::layout::{Direction, Size};
+use strsim::{generic_levenshtein, normalized_levenshtein};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use uuid::Uuid;
+
+use super::{
+    editor::{Editor, EditorKind},
+    goal::Goal,
+    lobby::{LobbyMessage, Restarts},
+    terminal::{challenge_directory, ChallengeFile},
+};
+use crate::{
+    config::Config,
+    constants::{MAX_EDITOR_RATIO, MIN_EDITOR_RATIO},
+};
+
+/// # Practice
+///
+/// A local round against a bundled challenge. Runs editor and goal just like
+/// a lobby, but computes the progress on the client without any backend.
+pub struct Practice {
+    pub challenge_files: ChallengeFiles,
+    /// The temporary directory holding the challenge files.
+    pub directory: PathBuf,
+    pub editor: Editor,
+    pub goal: Goal,
+    /// The similarity between the player file and the goal file.
+    pub progress: f64,
+    pub started_at: Instant,
+    /// The time it took to reach the goal. Is `None` until the goal is
+    /// reached.
+    pub finished_after: Option<Duration>,
+    /// Receives progress and termination messages of editor and goal.
+    pub tx: UnboundedSender<LobbyMessage>,
+    pub rx: UnboundedReceiver<LobbyMessage>,
+    pub app_size: Size,
+    pub terminal_layout_direction: Direction,
+    /// The share of the terminal area taken by the editor. The goal takes
+    /// the rest.
+    pub editor_ratio: f64,
+    pub use_builtin_editor: bool,
+    pub editor_command: String,
+    pub editor_args: Vec<String>,
+    /// Set while the user resets the editor, so the resulting termination is
+    /// not mistaken for a crash.
+    pub editor_reset_requested: bool,
+    /// Restart state of the editor after crashes.
+    pub editor_restarts: Restarts,
+    /// Restart state of the goal editor after crashes.
+    pub goal_restarts: Restarts,
+}
+
+impl Practice {
+    /// # Start practice
+    ///
+    /// Opens the bundled challenge in editor and goal.
+    pub fn new(
+        app_size: Size,
+        terminal_layout_direction: Direction,
+        editor_ratio: f64,
+        config: &Config,
+    ) -> Result<Self> {
+        let (tx, rx) = unbounded_channel();
+        let challenge_files = practice_challenge();
+        let use_builtin_editor = config.general.use_builtin_editor;
+        let editor_command = config.general.editor.clone();
+        let editor_args = config.general.editor_args.clone();
+
+        // Every practice round gets its own temporary directory.
+        let directory = challenge_directory(Uuid::new_v4());
+        let start_file = ChallengeFile {
+            content: challenge_files.start_file.clone(),
+            directory: directory.clone(),
+            extension: challenge_files.extension.clone(),
+            content_type: challenge_files.content_type,
+        };
+        let mut editor = Editor::new(
+            app_size,
+            tx.clone(),
+            start_file,
+            challenge_files.cursor,
+            false,
+            use_builtin_editor,
+            &editor_command,
+            &editor_args,
+        )?;
+        editor.resize(
+            app_size.height,
+            app_size.width,
+            terminal_layout_direction,
+            editor_ratio,
+        )?;
+        let goal_file = ChallengeFile {
+            content: challenge_files.goal_file.clone(),
+            directory: directory.clone(),
+            extension: challenge_files.extension.clone(),
+            content_type: challenge_files.content_type,
+        };
+        let mut goal = Goal::new(
+            app_size,
+            tx.clone(),
+            goal_file,
+            false,
+            use_builtin_editor,
+            &editor_command,
+            &editor_args,
+        )?;
+        goal.resize(
+            app_size.height,
+            app_size.width,
+            terminal_layout_direction,
+            editor_ratio,
+        )?;
+        info!("Started practicing {}.", challenge_files.name);
+
+        Ok(Self {
+            challenge_files,
+            directory,
+            editor,
+            goal,
+            progress: 0.0,
+            started_at: Instant::now(),
+            finished_after: None,
+            tx,
+            rx,
+            app_size,
+            terminal_layout_direction,
+            editor_ratio,
+            use_builtin_editor,
+            editor_command,
+            editor_args,
+            editor_reset_requested: false,
+            editor_restarts: Restarts::default(),
+            goal_restarts: Restarts::default(),
+        })
+    }
+
+    pub fn handle_message(&mut self, msg: LobbyMessage) -> Result<()> {
+        match msg {
+            LobbyMessage::SendProgress { progress } => {
+                self.progress = compute_progress(&self.challenge_files.goal_file, &progress);
+                if self.progress.eq(&1.0) && self.finished_after.is_none() {
+                    self.finished_after = Some(self.started_at.elapsed());
+                }
+            }
+            LobbyMessage::EditorTerminated { status } => {
+                // Restart the editor directly if the user quit it or reset it.
+                if status.success() || self.editor_reset_requested {
+                    self.editor_reset_requested = false;
+                    self.editor_restarts.reset();
+                    return self.restart_editor();
+                }
+                error!("The editor crashed with status: {status}");
+                if let Some(delay) = self.editor_restarts.handle_crash(&status) {
+                    let tx = self.tx.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        let _ = tx.send(LobbyMessage::RestartEditor);
+                    });
+                }
+            }
+            LobbyMessage::GoalTerminated { status } => {
+                if status.success() {
+                    self.goal_restarts.reset();
+                    return self.restart_goal();
+                }
+                error!("The goal editor crashed with status: {status}");
+                if let Some(delay) = self.goal_restarts.handle_crash(&status) {
+                    let tx = self.tx.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        let _ = tx.send(LobbyMessage::RestartGoal);
+                    });
+                }
+            }
+            // The user might have reset the editor in the meantime.
+            LobbyMessage::RestartEditor if self.editor_restarts.error.take().is_some() => {
+                self.restart_editor()?;
+            }
+            LobbyMessage::RestartGoal if self.goal_restarts.error.take().is_some() => {
+                self.restart_goal()?;
+            }
+            // Everything else concerns the backend.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// # Reset
+    ///
+    /// Starts the practice round over with the untouched start file.
+    pub fn reset(&mut self) -> Result<()> {
+        info!("Reset the practice round.");
+        self.started_at = Instant::now();
+        self.finished_after = None;
+        // A crashed editor has no running process anymore.
+        if self.editor_restarts.error.is_some() {
+            self.editor_restarts.reset();
+            return self.restart_editor();
+        }
+        match self.editor.kind {
+            // Killing the process triggers the restart via the termination
+            // handler of the editor.
+            EditorKind::Terminal(_) => {
+                self.editor_reset_requested = true;
+                self.editor.kill()?;
+            }
+            EditorKind::Builtin(_) | EditorKind::Unsupported => self.restart_editor()?,
+        }
+        Ok(())
+    }
+
+    fn challenge_file(&self, content: Vec<u8>) -> ChallengeFile {
+        ChallengeFile {
+            content,
+            directory: self.directory.clone(),
+            extension: self.challenge_files.extension.clone(),
+            content_type: self.challenge_files.content_type,
+        }
+    }
+
+    fn restart_editor(&mut self) -> Result<()> {
+        self.editor = Editor::new(
+            self.app_size,
+            self.tx.clone(),
+            self.challenge_file(self.challenge_files.start_file.clone()),
+            self.challenge_files.cursor,
+            self.editor.is_full_screen,
+            self.use_builtin_editor,
+            &self.editor_command,
+            &self.editor_args,
+        )?;
+        self.editor.resize(
+            self.app_size.height,
+            self.app_size.width,
+            self.terminal_layout_direction,
+            self.editor_ratio,
+        )
+    }
+
+    fn restart_goal(&mut self) -> Result<()> {
+        self.goal = Goal::new(
+            self.app_size,
+            self.tx.clone(),
+            self.challenge_file(self.challenge_files.goal_file.clone()),
+            self.goal.is_full_screen,
+            self.use_builtin_editor,
+            &self.editor_command,
+            &self.editor_args,
+        )?;
+        self.goal.resize(
+            self.app_size.height,
+            self.app_size.width,
+            self.terminal_layout_direction,
+            self.editor_ratio,
+        )
+    }
+
+    pub fn resize(&mut self, rows: u16, cols: u16) -> Result<()> {
+        self.app_size = Size::new(cols, rows);
+        self.editor.resize(
+            rows,
+            cols,
+            self.terminal_layout_direction,
+            self.editor_ratio,
+        )?;
+        self.goal.resize(
+            rows,
+            cols,
+            self.terminal_layout_direction,
+            self.editor_ratio,
+        )
+    }
+
+    pub fn toggle_terminal_layout(&mut self) {
+        if self.terminal_layout_direction == Direction::Vertical {
+            self.terminal_layout_direction = Direction::Horizontal;
+        } else {
+            self.terminal_layout_direction = Direction::Vertical;
+        }
+    }
+
+    /// # Adjust editor ratio
+    ///
+    /// Grows the editor by the given share of the terminal area, or shrinks
+    /// it for a negative share.
+    pub fn adjust_editor_ratio(&mut self, share: f64) {
+        self.editor_ratio = (self.editor_ratio + share).clamp(MIN_EDITOR_RATIO, MAX_EDITOR_RATIO);
+    }
+
+    pub fn clean_up(&mut self) -> Result<()> {
+        self.goal.kill()?;
+        self.editor.kill()
+    }
+}
+
+// Make sure the terminal instances are killed whenever we stop practicing.
+impl Drop for Practice {
+    fn drop(&mut self) {
+        if let Err(e) = self.clean_up() {
+            error!("Error cleaning up practice: {e}");
+        }
+    }
+}
+
+/// # Practice challenge
+///
+/// The challenge bundled with the client.
+fn practice_challenge() -> ChallengeFiles {
+    let start_file = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/assets/practice/start.rs"
+    ))
+    .to_vec();
+    let goal_file = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/assets/practice/goal.rs"
+    ))
+    .to_vec();
+
+    ChallengeFiles {
+        name: "Hello, world!".to_string(),
+        language: "Rust".to_string(),
+        extension: "rs".to_string(),
+        description: "Greet the world six times instead of the dude.".to_string(),
+        content_type: ContentType::Text,
+        start_file,
+        goal_file,
+        instructions: Some(
+            "Rename the function to `main`, greet the world and copy the function six times."
+                .to_string(),
+        ),
+        cursor: Some(CursorPosition { line: 1, column: 4 }),
+    }
+}
+
+/// # Compute progress
+///
+/// Computes the similarity between the goal file and the player file the same
+/// way the backend does by default, i.e., via the normalized levenshtein
+/// distance after converting CRLF line endings to LF.
+fn compute_progress(goal_file: &[u8], player_file: &[u8]) -> f64 {
+    let goal_file = convert_line_endings(goal_file);
+    let player_file = convert_line_endings(player_file);
+    match (
+        std::str::from_utf8(&goal_file),
+        std::str::from_utf8(&player_file),
+    ) {
+        (Ok(goal_file), Ok(player_file)) => normalized_levenshtein(goal_file, player_file),
+        _ => {
+            if goal_file.is_empty() && player_file.is_empty() {
+                return 1.0;
+            }
+            let distance = generic_levenshtein(&goal_file, &player_file);
+            1.0 - distance as f64 / goal_file.len().max(player_file.len()) as f64
+        }
+    }
+}
+
+/// Converts CRLF line endings to LF.
+fn convert_line_endings(file: &[u8]) -> Vec<u8> {
+    let mut converted = Vec::with_capacity(file.len());
+    for (i, byte) in file.iter().enumerate() {
+        if *byte == b'\r' && file.get(i + 1) == Some(&b'\n') {
+            continue;
+        }
+        converted.push(*byte);
+    }
+    converted
+}
@@ -0,0 +1,101 @@
+use std::time::{Duration, Instant};
+
+use crate::constants::REPLAY_HOLD;
+
+/// # Replay frame
+///
+/// A snapshot of the player file and when it was taken, relative to the
+/// start of the game.
+#[derive(Clone, Debug)]
+pub struct ReplayFrame {
+    pub offset: Duration,
+    pub content: Vec<u8>,
+}
+
+/// # Recording
+///
+/// Collects the snapshots of the player file the editor reports during a
+/// game.
+#[derive(Debug)]
+pub struct Recording {
+    pub started_at: Instant,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Recording {
+    /// Starts a recording with the untouched start file as first frame.
+    pub fn new(start_file: Vec<u8>) -> Self {
+        Self {
+            started_at: Instant::now(),
+            frames: vec![ReplayFrame {
+                offset: Duration::ZERO,
+                content: start_file,
+            }],
+        }
+    }
+
+    pub fn record(&mut self, content: Vec<u8>) {
+        self.frames.push(ReplayFrame {
+            offset: self.started_at.elapsed(),
+            content,
+        });
+    }
+
+    /// # Replay
+    ///
+    /// Starts replaying the recorded frames at the given speed, e.g., `2.0`
+    /// replays twice as fast as recorded.
+    pub fn replay(&self, speed: f64) -> Replay {
+        Replay {
+            frames: self.frames.clone(),
+            speed,
+            started_at: Instant::now(),
+        }
+    }
+}
+
+/// # Replay
+///
+/// Plays recorded frames back in the editor pane.
+#[derive(Debug)]
+pub struct Replay {
+    pub frames: Vec<ReplayFrame>,
+    pub speed: f64,
+    pub started_at: Instant,
+}
+
+impl Replay {
+    /// # Elapsed
+    ///
+    /// Returns the replayed time, i.e., the time since the start of the
+    /// replay scaled by its speed.
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.elapsed().mul_f64(self.speed)
+    }
+
+    /// # Duration
+    ///
+    /// Returns the recorded time up to the last frame.
+    pub fn duration(&self) -> Duration {
+        self.frames
+            .last()
+            .map(|frame| frame.offset)
+            .unwrap_or_default()
+    }
+
+    /// # Current frame
+    ///
+    /// Returns the latest frame that was recorded before the replayed time.
+    pub fn current_frame(&self) -> Option<&ReplayFrame> {
+        let elapsed = self.elapsed();
+        let index = self.frames.partition_point(|frame| frame.offset <= elapsed);
+        self.frames.get(index.saturating_sub(1))
+    }
+
+    /// # Is finished
+    ///
+    /// Whether the last frame was shown for `REPLAY_HOLD` already.
+    pub fn is_finished(&self) -> bool {
+        self.started_at.elapsed() >= self.duration().div_f64(self.speed) + REPLAY_HOLD
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use common::FinalStanding;
+use log::{error, info};
+use serde::Serialize;
+
+/// # Game results
+///
+/// The outcome of a finished game as written to the configured results
+/// directory.
+#[derive(Debug, Serialize)]
+pub struct GameResults<'a> {
+    pub lobby_name: &'a str,
+    pub challenge: &'a str,
+    pub finished_at: DateTime<Utc>,
+    pub standings: &'a [FinalStanding],
+}
+
+impl GameResults<'_> {
+    /// # Export
+    ///
+    /// Writes the results as JSON into the given directory. The file name
+    /// holds the time the game finished and the lobby name.
+    pub fn export(&self, directory: &Path) {
+        match self.write(directory) {
+            Ok(path) => info!("Exported the game results to {}.", path.display()),
+            Err(e) => error!(
+                "Unable to export the game results to {}: {e}",
+                directory.display()
+            ),
+        }
+    }
+
+    fn write(&self, directory: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(directory)?;
+        // Keep the lobby name from introducing separators or odd characters
+        // into the file name.
+        let lobby_name: String = self
+            .lobby_name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect();
+        let path = directory.join(format!(
+            "{}-{lobby_name}.json",
+            self.finished_at.format("%Y%m%d-%H%M%S")
+        ));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,51 @@
+use std::{io::ErrorKind, path::Path};
+
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::Service;
+
+/// # Session
+///
+/// The backend service and lobby the user played in last. Stored in the
+/// configured session file, so the next launch can offer to reconnect.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub service: Service,
+    /// The lobby the user was playing in. Is `None` if the user left it.
+    pub lobby_id: Option<Uuid>,
+    pub lobby_name: Option<String>,
+}
+
+impl Session {
+    /// # Load
+    ///
+    /// Reads the session from the given file. Returns `None` if there is no
+    /// stored session or it can not be read.
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!("Unable to read session file {}: {e}", path.display());
+                return None;
+            }
+        };
+        serde_json::from_str(&content)
+            .inspect_err(|e| warn!("Unable to parse session file {}: {e}", path.display()))
+            .ok()
+    }
+
+    /// # Save
+    ///
+    /// Writes the session to the given file.
+    pub fn save(&self, path: &Path) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| std::fs::write(path, content).map_err(anyhow::Error::from));
+        if let Err(e) = result {
+            error!("Unable to write session file {}: {e}", path.display());
+        }
+    }
+}
@@ -6,6 +6,7 @@ pub enum Tab {
     Home,
     Play,
     Logs,
+    Settings,
 }
 
 impl Tab {
@@ -13,7 +14,8 @@ impl Tab {
         let tab = match self {
             Tab::Home => Tab::Play,
             Tab::Play => Tab::Logs,
-            Tab::Logs => Tab::Home,
+            Tab::Logs => Tab::Settings,
+            Tab::Settings => Tab::Home,
         };
         debug!("Switch from tab {} to next tab {}.", self, tab);
         tab
@@ -21,9 +23,10 @@ impl Tab {
 
     pub fn previous(&self) -> Self {
         let tab = match self {
-            Tab::Home => Tab::Logs,
+            Tab::Home => Tab::Settings,
             Tab::Play => Tab::Home,
             Tab::Logs => Tab::Play,
+            Tab::Settings => Tab::Logs,
         };
         debug!("Switch from tab {} to previous tab {}.", self, tab);
         tab
@@ -34,6 +37,7 @@ impl Tab {
             Tab::Home => 0,
             Tab::Play => 1,
             Tab::Logs => 2,
+            Tab::Settings => 3,
         }
     }
 }
@@ -1,18 +1,122 @@
 use std::{
+    env,
+    fs::{self, File},
     io::{BufWriter, Write},
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use bytes::Bytes;
+use common::{ContentType, CursorPosition};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use log::debug;
+use log::{debug, error, warn};
 use portable_pty::{
     Child, ChildKiller, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem,
 };
-use ratatui::layout::Size;
+use ratatui::layout::{Direction, Size};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
 use tui_term::vt100::Parser;
+use uuid::Uuid;
+
+use crate::constants::{EDITOR_TERM, HELIX_PROGRAMS, TERMINAL_WIDTH};
+
+/// # Challenge file
+///
+/// The content of a challenge file together with where external editors find
+/// it.
+pub struct ChallengeFile {
+    pub content: Vec<u8>,
+    /// The temporary directory of the lobby the file belongs to.
+    pub directory: PathBuf,
+    /// The file extension matching the challenge language, e.g., `rs`.
+    pub extension: String,
+    /// Binary files are never opened by any editor.
+    pub content_type: ContentType,
+}
+
+/// # Pane size
+///
+/// Computes the rows and columns of a terminal that takes the given share of
+/// the terminal area. Saturates at a single row and column, so a tiny
+/// application never underflows into huge PTY sizes.
+pub fn pane_size(
+    rows: u16,
+    cols: u16,
+    direction: Direction,
+    share: f64,
+    is_full_screen: bool,
+) -> (u16, u16) {
+    let (rows, cols) = if is_full_screen {
+        (rows.saturating_sub(2), cols.saturating_sub(2))
+    } else {
+        // The full application height - header, help bar and borders.
+        let rows = rows.saturating_sub(6) as f64;
+        let cols = cols.saturating_sub(2) as f64 * TERMINAL_WIDTH;
+        match direction {
+            Direction::Horizontal => (rows as u16, ((cols * share) as u16).saturating_sub(1)),
+            Direction::Vertical => (((rows * share) as u16).saturating_sub(1), cols as u16),
+        }
+    };
+    (rows.max(1), cols.max(1))
+}
+
+/// # Challenge directory
+///
+/// Returns the temporary directory holding the challenge files of the lobby
+/// with the given ID. Every lobby gets its own directory, so files of
+/// concurrent lobbies never collide.
+pub fn challenge_directory(lobby_id: Uuid) -> PathBuf {
+    let mut directory = env::temp_dir();
+    directory.push("keyglide_challenge");
+    directory.push(lobby_id.to_string());
+    directory
+}
+
+impl ChallengeFile {
+    /// # Path
+    ///
+    /// Returns where to write the challenge file under the given name inside
+    /// the directory of its lobby. The extension of the challenge allows
+    /// editors to highlight the syntax.
+    pub fn path(&self, name: &str) -> PathBuf {
+        let mut path = self.directory.clone();
+        path.push(name);
+        path.set_extension(&self.extension);
+        path
+    }
+
+    /// # Write
+    ///
+    /// Writes the content of the challenge file to the given path.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Err(e) = fs::create_dir_all(&self.directory) {
+            return Err(anyhow!("Failed to create folder: {e}"));
+        }
+        let mut file = match File::create(path) {
+            Ok(file) => file,
+            Err(e) => return Err(anyhow!("Error creating file: {e}")),
+        };
+        if let Err(e) = file.write_all(&self.content) {
+            remove_challenge_file(path);
+            return Err(anyhow!("Error writing to file: {e}"));
+        }
+        Ok(())
+    }
+}
+
+/// # Remove challenge file
+///
+/// Removes a challenge file written for an external editor. Also removes the
+/// directory of the lobby once no other file is left inside.
+pub fn remove_challenge_file(path: &Path) {
+    if let Err(e) = fs::remove_file(path) {
+        warn!("Unable to remove {}: {e}", path.display());
+    }
+    if let Some(directory) = path.parent() {
+        let _ = fs::remove_dir(directory);
+    }
+}
 
 pub struct Terminal {
     pub sender: UnboundedSender<Bytes>,
@@ -21,6 +125,79 @@ pub struct Terminal {
     pub child_killer: Box<dyn ChildKiller + Send>,
 }
 
+/// # Editor command
+///
+/// Builds the command that opens the given file with the configured editor.
+/// The editor command is split at whitespace into the program and its
+/// arguments, followed by the extra editor arguments. The file path is
+/// appended last. The editor runs inside the directory of the file. Fails if
+/// the editor program can not be found.
+///
+/// An optional cursor position is passed as `file:line:column` to helix and
+/// as `+line` argument in front of the file to every other editor, which is
+/// understood by most terminal editors, e.g., vim, nano or emacs.
+pub fn editor_command<P: AsRef<Path>>(
+    editor: &str,
+    editor_args: &[String],
+    file_path: P,
+    cursor: Option<CursorPosition>,
+) -> Result<CommandBuilder> {
+    let mut parts = editor.split_whitespace();
+    let Some(program) = parts.next() else {
+        return Err(anyhow!("The configured editor command is empty."));
+    };
+    if find_program(program).is_none() {
+        return Err(anyhow!(
+            "The editor `{program}` was not found. Make sure it is installed and on your PATH."
+        ));
+    }
+    let file_path = file_path.as_ref();
+    let mut cmd = CommandBuilder::new(program);
+    cmd.args(parts);
+    cmd.args(editor_args);
+    match cursor {
+        Some(cursor) if is_helix(program) => {
+            let mut file = file_path.as_os_str().to_owned();
+            file.push(format!(":{}:{}", cursor.line, cursor.column));
+            cmd.arg(file);
+        }
+        Some(cursor) => {
+            cmd.arg(format!("+{}", cursor.line));
+            cmd.arg(file_path);
+        }
+        None => cmd.arg(file_path),
+    }
+    if let Some(directory) = file_path.parent() {
+        cmd.cwd(directory);
+    }
+    cmd.env("TERM", EDITOR_TERM);
+    Ok(cmd)
+}
+
+/// Whether the given program is helix, which takes the cursor position as part
+/// of the file argument.
+fn is_helix(program: &str) -> bool {
+    Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| HELIX_PROGRAMS.contains(&name))
+}
+
+/// # Find program
+///
+/// Resolves the given program to an existing file. Programs containing a path
+/// separator are taken as is, all others are looked up in the `PATH`.
+fn find_program(program: &str) -> Option<PathBuf> {
+    let program = Path::new(program);
+    if program.components().count() > 1 {
+        return program.is_file().then(|| program.to_path_buf());
+    }
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .map(|directory| directory.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
 impl Terminal {
     pub fn new(
         app_size: Size,
@@ -33,7 +210,11 @@ impl Terminal {
         let pair = pty_system.openpty(size)?;
 
         // Wait for the child to complete
-        let child = pair.slave.spawn_command(cmd)?;
+        let program = cmd.get_argv().first().cloned().unwrap_or_default();
+        let child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|e| anyhow!("Unable to start editor {}: {e}", program.to_string_lossy()))?;
 
         let mut reader = pair.master.try_clone_reader()?;
         let parser_clone = Arc::clone(&parser);
@@ -43,18 +224,23 @@ impl Terminal {
             let mut buf = [0u8; 8192];
             let mut processed_buf = Vec::new();
             loop {
-                let size = reader
-                    .read(&mut buf)
-                    .expect("Unable to read from terminal reader.");
+                let size = match reader.read(&mut buf) {
+                    Ok(size) => size,
+                    Err(e) => {
+                        error!("Unable to read from terminal reader: {e}");
+                        break;
+                    }
+                };
                 if size == 0 {
                     break;
                 }
                 if size > 0 {
                     processed_buf.extend_from_slice(&buf[..size]);
-                    parser_clone
-                        .lock()
-                        .expect("Unable to lock terminal parser.")
-                        .process(&processed_buf);
+                    let Ok(mut parser) = parser_clone.lock() else {
+                        error!("Unable to lock terminal parser.");
+                        break;
+                    };
+                    parser.process(&processed_buf);
 
                     // Clear the processed portion of the buffer
                     processed_buf.clear();
@@ -68,10 +254,10 @@ impl Terminal {
         let mut writer = BufWriter::new(pair.master.take_writer()?);
         tokio::spawn(async move {
             while let Some(bytes) = rx.recv().await {
-                writer
-                    .write_all(&bytes)
-                    .expect("Unable to write bytes to terminal writer.");
-                writer.flush().expect("Unable to flush terminal writer.");
+                if let Err(e) = writer.write_all(&bytes).and_then(|_| writer.flush()) {
+                    error!("Unable to write bytes to terminal writer: {e}");
+                    break;
+                }
             }
         });
 
@@ -139,3 +325,83 @@ impl Terminal {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiny_terminals_saturate_at_one_cell() {
+        for direction in [Direction::Horizontal, Direction::Vertical] {
+            for is_full_screen in [false, true] {
+                for (rows, cols) in [(0, 0), (1, 1), (2, 2), (6, 2), (0, u16::MAX)] {
+                    let (pane_rows, pane_cols) =
+                        pane_size(rows, cols, direction, 0.5, is_full_screen);
+                    assert!(pane_rows >= 1);
+                    assert!(pane_cols >= 1);
+                    assert!(pane_rows <= rows.max(1));
+                    assert!(pane_cols <= cols.max(1));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn full_screen_pane_only_loses_borders() {
+        assert_eq!(
+            pane_size(40, 100, Direction::Horizontal, 0.5, true),
+            (38, 98)
+        );
+        assert_eq!(pane_size(1, 1, Direction::Vertical, 0.5, true), (1, 1));
+    }
+
+    #[test]
+    fn panes_split_along_direction() {
+        // Both panes share 80% of the width minus borders, e.g., 160 columns.
+        assert_eq!(
+            pane_size(46, 202, Direction::Horizontal, 0.5, false),
+            (40, 79)
+        );
+        assert_eq!(
+            pane_size(46, 202, Direction::Vertical, 0.5, false),
+            (19, 160)
+        );
+    }
+
+    #[test]
+    fn missing_editor_is_reported() {
+        let result = editor_command("keyglide-missing-editor", &[], "goal.rs", None);
+        let error = result.expect_err("missing editor should fail");
+        assert!(error
+            .to_string()
+            .contains("The editor `keyglide-missing-editor` was not found."));
+
+        let result = editor_command("/keyglide/missing/editor --flag", &[], "goal.rs", None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn empty_editor_is_reported() {
+        assert!(editor_command("", &[], "goal.rs", None).is_err());
+        assert!(editor_command("   ", &[], "goal.rs", None).is_err());
+    }
+
+    #[test]
+    fn existing_editor_opens_file() {
+        let cmd = editor_command("sh -e", &["-u".to_string()], "/tmp/goal.rs", None)
+            .expect("sh should be found");
+        let argv: Vec<_> = cmd
+            .get_argv()
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(argv, ["sh", "-e", "-u", "/tmp/goal.rs"]);
+    }
+
+    #[test]
+    fn spawning_missing_program_fails() {
+        let cmd = CommandBuilder::new("keyglide-missing-editor");
+        let result = Terminal::new(Size::new(80, 24), cmd);
+        assert!(result.is_err());
+    }
+}
@@ -1,29 +1,48 @@
 use logs::draw_logs_tab;
-use play::{chat::draw_chat, editor::draw_editor, goal::draw_goal, join::draw_join};
-use rand::{thread_rng, Rng};
+use play::{
+    chat::draw_chat, diff::draw_diff, editor::draw_editor, goal::draw_goal, join::draw_join,
+    lobby::draw_lobby,
+};
+use rand::{seq::IteratorRandom, thread_rng};
 use ratatui::{
     layout::{Constraint, Flex, Layout, Rect},
     Frame,
 };
 
 use self::{
-    exit::draw_exit, header::draw_header, home::draw_home_tab, offline::draw_offline,
-    play::draw_play_tab,
+    command_palette::draw_command_palette, exit::draw_exit, header::draw_header, help::draw_help,
+    help_bar::draw_help_bar, home::draw_home_tab, offline::draw_offline, play::draw_play_tab,
+    reconnect::draw_reconnect, rename_lobby::draw_rename_lobby, reset_editor::draw_reset_editor,
+    settings::draw_settings_tab, too_small::draw_too_small,
 };
 use crate::{
     app::App,
-    constants::SYMBOLS,
+    constants::{MIN_APP_HEIGHT, MIN_APP_WIDTH},
     schema::{connection::Connection, focused_component::ComponentKind, tab::Tab},
 };
 
+mod command_palette;
 mod exit;
 mod header;
+mod help;
+mod help_bar;
 mod home;
 mod logs;
 mod offline;
 mod play;
+mod reconnect;
+mod rename_lobby;
+mod reset_editor;
+mod settings;
+mod too_small;
 
 pub fn draw(f: &mut Frame, app: &mut App) {
+    // Avoid rendering broken layouts inside tiny terminals.
+    if f.area().width < MIN_APP_WIDTH || f.area().height < MIN_APP_HEIGHT {
+        draw_too_small(f, &app.config);
+        return;
+    }
+
     // Check if one component is set to full screen. If that's the case draw the
     // full screen component and return directly.
     if app
@@ -41,27 +60,59 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     if app.focused_component_is_kind(ComponentKind::ExitPopup) {
         draw_exit(f, &app.config);
     }
+    // Optionally, offer to reconnect to the last session.
+    if app.focused_component_is_kind(ComponentKind::ReconnectPopup) {
+        if let Some(ref session) = app.last_session {
+            draw_reconnect(f, &app.config, session);
+        }
+    }
+    // Optionally, render a popup to rename the lobby.
+    if app.focused_component_is_kind(ComponentKind::RenameLobbyPopup) {
+        if let Connection::Lobby(ref lobby) = app.connection {
+            draw_rename_lobby(f, &lobby.name_input);
+        }
+    }
+    // Optionally, render a popup confirming the editor reset.
+    if app.focused_component_is_kind(ComponentKind::ResetEditorPopup) {
+        draw_reset_editor(f, &app.config);
+    }
+    // Optionally, render the command palette above the current content.
+    if app.focused_component_is_kind(ComponentKind::CommandPalette) {
+        draw_command_palette(f, app);
+    }
+    // Optionally, render the key binding overlay above the current content.
+    if app.focused_component_is_kind(ComponentKind::Help) {
+        draw_help(f, &app.config, &mut app.help_offset);
+    }
 }
 
 /// # Draw the application
 ///
-/// Draws the application. Divides the layout into a header and content field.
+/// Draws the application. Divides the layout into a header, content field and
+/// help bar.
 pub fn draw_application(f: &mut Frame, app: &mut App) {
-    // Split the layout into header and content.
-    let chunks = Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).split(f.area());
+    // Split the layout into header, content and help bar.
+    let chunks = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .split(f.area());
 
     draw_header(f, app, chunks[0]);
+    draw_help_bar(f, app, chunks[2]);
 
     // Render content depending on the selected tab.
     match app.current_tab {
         Tab::Home => draw_home_tab(f, app, chunks[1]),
         Tab::Play => draw_play_tab(f, app, chunks[1]),
-        Tab::Logs => draw_logs_tab(f, chunks[1]),
+        Tab::Logs => draw_logs_tab(f, app, chunks[1]),
+        Tab::Settings => draw_settings_tab(f, &app.config, chunks[1]),
     };
 
     // If we are offline just draw the offline UI above everything else.
     if let Connection::Offline(ref offline) = app.connection {
-        draw_offline(f, offline);
+        draw_offline(f, &app.config, offline);
     }
 }
 
@@ -93,13 +144,39 @@ pub fn draw_full_screen(f: &mut Frame, app: &mut App) {
     };
 
     let area = Rect::new(0, 0, app.size.width, app.size.height);
+
+    // The logs, the command palette and the key binding overlay do not
+    // depend on the current connection.
+    if focused_component.kind == ComponentKind::Logs {
+        draw_logs_tab(f, app, area);
+        return;
+    }
+    if focused_component.kind == ComponentKind::CommandPalette {
+        draw_command_palette(f, app);
+        return;
+    }
+    if focused_component.kind == ComponentKind::Help {
+        draw_help(f, &app.config, &mut app.help_offset);
+        return;
+    }
+
     match app.connection {
         Connection::Join(ref mut join) => match focused_component.kind {
             ComponentKind::Chat
+            | ComponentKind::CommandPalette
+            | ComponentKind::Diff
             | ComponentKind::Editor
             | ComponentKind::Goal
-            | ComponentKind::ExitPopup => {}
-            ComponentKind::Lobbies => draw_join(f, &app.config, area, join, &app.focused_component),
+            | ComponentKind::ExitPopup
+            | ComponentKind::Help
+            | ComponentKind::Logs
+            | ComponentKind::Players
+            | ComponentKind::ReconnectPopup
+            | ComponentKind::RenameLobbyPopup
+            | ComponentKind::ResetEditorPopup => {}
+            ComponentKind::Lobbies | ComponentKind::LobbySearch => {
+                draw_join(f, &app.config, area, join, &app.focused_component)
+            }
         },
         Connection::Lobby(ref mut lobby) => match focused_component.kind {
             ComponentKind::Chat => draw_chat(
@@ -110,20 +187,76 @@ pub fn draw_full_screen(f: &mut Frame, app: &mut App) {
                 &app.focused_component,
             ),
             ComponentKind::Editor => {
-                draw_editor(f, area, &app.config, &lobby.editor, &app.focused_component)
+                if let Some(ref editor) = lobby.editor {
+                    draw_editor(
+                        f,
+                        area,
+                        &app.config,
+                        editor,
+                        &lobby.editor_restarts.error,
+                        &app.focused_component,
+                    );
+                }
             }
-            ComponentKind::Goal => {
-                draw_goal(f, area, &app.config, &lobby.goal, &app.focused_component)
+            ComponentKind::Goal => draw_goal(
+                f,
+                area,
+                &app.config,
+                &lobby.goal,
+                &lobby.goal_restarts.error,
+                &app.focused_component,
+            ),
+            ComponentKind::Diff => draw_diff(f, area, &app.config, &lobby.diff),
+            ComponentKind::Players => {
+                draw_lobby(f, area, &app.config, lobby, &app.focused_component)
             }
             ComponentKind::ExitPopup => draw_exit(f, &app.config),
-            ComponentKind::Lobbies => {}
+            ComponentKind::RenameLobbyPopup => draw_rename_lobby(f, &lobby.name_input),
+            ComponentKind::ResetEditorPopup => draw_reset_editor(f, &app.config),
+            ComponentKind::CommandPalette
+            | ComponentKind::Help
+            | ComponentKind::Lobbies
+            | ComponentKind::LobbySearch
+            | ComponentKind::Logs
+            | ComponentKind::ReconnectPopup => {}
+        },
+        Connection::Practice(ref practice) => match focused_component.kind {
+            ComponentKind::Editor => draw_editor(
+                f,
+                area,
+                &app.config,
+                &practice.editor,
+                &practice.editor_restarts.error,
+                &app.focused_component,
+            ),
+            ComponentKind::Goal => draw_goal(
+                f,
+                area,
+                &app.config,
+                &practice.goal,
+                &practice.goal_restarts.error,
+                &app.focused_component,
+            ),
+            ComponentKind::ExitPopup => draw_exit(f, &app.config),
+            ComponentKind::ResetEditorPopup => draw_reset_editor(f, &app.config),
+            ComponentKind::Chat
+            | ComponentKind::CommandPalette
+            | ComponentKind::Diff
+            | ComponentKind::Help
+            | ComponentKind::Lobbies
+            | ComponentKind::LobbySearch
+            | ComponentKind::Logs
+            | ComponentKind::Players
+            | ComponentKind::ReconnectPopup
+            | ComponentKind::RenameLobbyPopup => {}
         },
         Connection::Offline(_) => {}
     }
 }
 
-pub fn get_random_symbol() -> char {
-    let mut rng = thread_rng();
-    let idx = rng.gen_range(0..SYMBOLS.len());
-    SYMBOLS.chars().nth(idx).unwrap_or('.')
+/// # Get random symbol
+///
+/// Picks one of the given symbols to scramble unrevealed characters with.
+pub fn get_random_symbol(symbols: &str) -> char {
+    symbols.chars().choose(&mut thread_rng()).unwrap_or('.')
 }
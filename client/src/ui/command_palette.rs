@@ -0,0 +1,47 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    widgets::{Block, Clear, List, ListState, Paragraph},
+    Frame,
+};
+
+use super::centered_rect;
+use crate::{
+    app::App,
+    constants::{COMMAND_PALETTE_HEIGHT, COMMAND_PALETTE_WIDTH},
+    schema::command_palette::commands,
+};
+
+/// # Draw command palette
+///
+/// Draws the query above all commands matching it. Every command shows the
+/// key binding it replaces.
+pub fn draw_command_palette(f: &mut Frame, app: &App) {
+    let palette = &app.command_palette;
+    let matches = palette.matching_commands(commands(&app.connection, &app.config));
+
+    let area = centered_rect(f.area(), COMMAND_PALETTE_WIDTH, COMMAND_PALETTE_HEIGHT);
+    let [query_area, list_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+    f.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .title("Commands")
+        .border_style(Style::default().fg(app.config.theme.focus_border));
+    let query = Paragraph::new(format!("{}|", palette.query)).block(block);
+    f.render_widget(query, query_area);
+
+    let items = matches
+        .iter()
+        .map(|command| format!("{} {}", command.name, command.key_binding));
+    let block = Block::bordered()
+        .title_bottom(format!("{} matching", matches.len()))
+        .border_style(Style::default().fg(app.config.theme.popup_border));
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(app.config.theme.highlight)
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = ListState::default().with_selected(Some(palette.selected));
+    f.render_stateful_widget(list, list_area, &mut state);
+}
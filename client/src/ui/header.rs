@@ -1,21 +1,53 @@
 use ratatui::{
-    layout::Rect,
+    layout::{Alignment, Rect},
     style::{Color, Style},
-    text::{self},
-    widgets::{Block, Tabs},
+    text::{self, Span},
+    widgets::{block::Title, Block, Tabs},
     Frame,
 };
 use strum::IntoEnumIterator;
 
-use crate::{app::App, constants::APP_TITLE, schema::tab::Tab};
+use crate::{
+    app::App,
+    constants::{APP_TITLE, LATENCY_ACCEPTABLE, LATENCY_GOOD, LATENCY_TIMEOUT},
+    schema::{connection::Connection, tab::Tab},
+};
 
 pub fn draw_header(f: &mut Frame, app: &App, area: Rect) {
+    let mut block = Block::bordered().title(APP_TITLE);
+    if let Some(latency) = draw_latency(app) {
+        block = block.title(Title::from(latency).alignment(Alignment::Right));
+    }
+
     // Display all tabs in the header. Highlight the current selection.
     let tabs = Tab::iter()
         .map(|t| text::Line::from(t.to_string()))
         .collect::<Tabs>()
-        .block(Block::bordered().title(APP_TITLE))
-        .highlight_style(Style::default().fg(Color::Yellow))
+        .block(block)
+        .highlight_style(Style::default().fg(app.config.theme.highlight))
         .select(app.current_tab.index());
     f.render_widget(tabs, area);
 }
+
+/// # Draw latency
+///
+/// Colors the latency to the backend service by its quality. Shows that the
+/// connection is dropping if the backend stopped answering pings.
+fn draw_latency(app: &App) -> Option<Span<'static>> {
+    if !matches!(app.connection, Connection::Join(_) | Connection::Lobby(_)) {
+        return None;
+    }
+    let last_pong = app.last_pong?;
+    if last_pong.elapsed() > LATENCY_TIMEOUT {
+        return Some(Span::styled("disconnecting", Color::Red));
+    }
+    let latency = app.latency?;
+    let color = if latency <= LATENCY_GOOD {
+        Color::Green
+    } else if latency <= LATENCY_ACCEPTABLE {
+        Color::Yellow
+    } else {
+        Color::Red
+    };
+    Some(Span::styled(format!("{}ms", latency.as_millis()), color))
+}
@@ -0,0 +1,60 @@
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+
+use super::centered_rect;
+use crate::{config::Config, constants::HELP_WIDTH};
+
+/// # Draw help
+///
+/// Lists every configured key binding grouped by its category. Clamps the
+/// scroll offset, so the last binding never scrolls out of view.
+pub fn draw_help(f: &mut Frame, config: &Config, offset: &mut u16) {
+    let mut lines = vec![];
+    for (category, key_bindings) in config.key_bindings.categories() {
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::styled(
+            category_title(category),
+            Style::default()
+                .fg(config.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        ));
+        for (name, key_binding) in key_bindings {
+            lines.push(Line::from(vec![
+                Span::raw(format!("  {name:<28}")),
+                Span::raw(key_binding.to_string()),
+            ]));
+        }
+    }
+
+    // Leave some space for the surrounding layout.
+    let height = (lines.len() as u16).min(f.area().height.saturating_sub(4));
+    *offset = (*offset).min(lines.len() as u16 - height);
+    let area = centered_rect(f.area(), HELP_WIDTH, height);
+    f.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .title("Key bindings")
+        .title_bottom(format!(
+            "{}/{} scroll",
+            config.key_bindings.movement.up, config.key_bindings.movement.down
+        ))
+        .border_style(Style::default().fg(config.theme.focus_border));
+    let paragraph = Paragraph::new(lines).block(block).scroll((*offset, 0));
+    f.render_widget(paragraph, area);
+}
+
+/// Turns the configuration name of a category into a title, e.g.,
+/// `miscellaneous` into `Miscellaneous`.
+fn category_title(category: &str) -> String {
+    let mut chars = category.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,209 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Paragraph,
+    Frame,
+};
+
+use common::LobbyStatus;
+
+use crate::{
+    app::App,
+    schema::{connection::Connection, focused_component::ComponentKind, tab::Tab},
+};
+
+/// # Draw help bar
+///
+/// Displays the most relevant key bindings for the currently focused component
+/// or, if nothing is focused, for the current tab and connection.
+pub fn draw_help_bar(f: &mut Frame, app: &App, area: Rect) {
+    let key_bindings = &app.config.key_bindings;
+    let mut hints = vec![];
+
+    if let Some(ref focused_component) = app.focused_component {
+        match focused_component.kind {
+            ComponentKind::ExitPopup
+            | ComponentKind::ReconnectPopup
+            | ComponentKind::ResetEditorPopup => {
+                hints.push(format!("{} confirm", key_bindings.popup.confirm));
+                hints.push(format!("{} abort", key_bindings.popup.abort));
+            }
+            ComponentKind::Lobbies => {
+                hints.push(format!(
+                    "{}/{} navigate",
+                    key_bindings.movement.up, key_bindings.movement.down
+                ));
+                hints.push(format!("{} join", key_bindings.join.join_selected));
+                hints.push(format!("{} search", key_bindings.join.search));
+                hints.push(format!("{} sort", key_bindings.join.cycle_sort_order));
+                hints.push(format!("{} spectate", key_bindings.join.spectate_selected));
+                hints.push(format!("{} quickplay", key_bindings.join.quickplay));
+                hints.push(format!("{} create", key_bindings.join.create));
+                hints.push(format!(
+                    "{}/{} capacity",
+                    key_bindings.movement.left, key_bindings.movement.right
+                ));
+            }
+            ComponentKind::LobbySearch => {
+                hints.push("Enter browse results".to_string());
+            }
+            ComponentKind::RenameLobbyPopup => {
+                hints.push("Enter rename".to_string());
+                hints.push("Esc abort".to_string());
+            }
+            ComponentKind::CommandPalette => {
+                hints.push("Up/Down select".to_string());
+                hints.push("Enter run".to_string());
+            }
+            ComponentKind::Help => {
+                hints.push(format!(
+                    "{}/{} scroll",
+                    key_bindings.movement.up, key_bindings.movement.down
+                ));
+                hints.push(format!("{} close", key_bindings.miscellaneous.help));
+            }
+            ComponentKind::Logs => {
+                hints.push(format!(
+                    "{}/{} scroll",
+                    key_bindings.movement.left, key_bindings.movement.right
+                ));
+            }
+            ComponentKind::Diff => {
+                hints.push(format!(
+                    "{}/{} scroll",
+                    key_bindings.movement.up, key_bindings.movement.down
+                ));
+            }
+            ComponentKind::Players => {
+                hints.push(format!(
+                    "{}/{} select",
+                    key_bindings.movement.up, key_bindings.movement.down
+                ));
+                if let Connection::Lobby(ref lobby) = app.connection {
+                    if lobby.owner == lobby.local_player && lobby.local_player.is_some() {
+                        hints.push(format!("{} kick", key_bindings.lobby.kick_player));
+                        hints.push(format!(
+                            "{} make owner",
+                            key_bindings.lobby.transfer_ownership
+                        ));
+                    }
+                }
+            }
+            ComponentKind::Chat | ComponentKind::Editor | ComponentKind::Goal => {}
+        }
+        if !matches!(
+            focused_component.kind,
+            ComponentKind::ExitPopup
+                | ComponentKind::ReconnectPopup
+                | ComponentKind::RenameLobbyPopup
+                | ComponentKind::ResetEditorPopup
+        ) {
+            hints.push(format!(
+                "{} full screen",
+                key_bindings.miscellaneous.toggle_full_screen
+            ));
+            hints.push(format!("{} unfocus", key_bindings.miscellaneous.unfocus));
+        }
+    } else {
+        hints.push(format!(
+            "{}/{} switch tab",
+            key_bindings.movement.left, key_bindings.movement.right
+        ));
+        hints.push(format!("{} key bindings", key_bindings.miscellaneous.help));
+        match app.current_tab {
+            Tab::Home => {}
+            Tab::Play => {
+                hints.push(format!(
+                    "{} commands",
+                    key_bindings.miscellaneous.command_palette
+                ));
+                match app.connection {
+                    Connection::Join(_) => {
+                        hints.push(format!(
+                            "{} browse lobbies",
+                            key_bindings.join.focus_lobby_list
+                        ));
+                        hints.push(format!("{} search", key_bindings.join.search));
+                        hints.push(format!("{} practice", key_bindings.join.practice));
+                    }
+                    Connection::Lobby(ref lobby) if lobby.spectating => {
+                        hints.push(format!("{} goal", key_bindings.lobby.focus_goal));
+                        hints.push(format!(
+                            "{} layout",
+                            key_bindings.lobby.toggle_terminal_layout
+                        ));
+                        hints.push(format!("{} disconnect", key_bindings.lobby.disconnect));
+                    }
+                    Connection::Lobby(ref lobby) => {
+                        hints.push(format!("{} editor", key_bindings.lobby.focus_editor));
+                        hints.push(format!(
+                            "{} full screen editor",
+                            key_bindings.miscellaneous.toggle_editor_full_screen
+                        ));
+                        hints.push(format!("{} goal", key_bindings.lobby.focus_goal));
+                        hints.push(format!("{} diff", key_bindings.lobby.focus_diff));
+                        hints.push(format!("{} players", key_bindings.lobby.focus_players));
+                        hints.push(format!("{} chat", key_bindings.lobby.focus_chat));
+                        hints.push(format!(
+                            "{} newest message",
+                            key_bindings.lobby.scroll_chat_bottom
+                        ));
+                        hints.push(format!(
+                            "{}/{} page chat",
+                            key_bindings.lobby.scroll_chat_page_up,
+                            key_bindings.lobby.scroll_chat_page_down
+                        ));
+                        hints.push(format!(
+                            "{} layout",
+                            key_bindings.lobby.toggle_terminal_layout
+                        ));
+                        hints.push(format!(
+                            "{}/{} resize editor",
+                            key_bindings.lobby.grow_editor, key_bindings.lobby.shrink_editor
+                        ));
+                        if let LobbyStatus::InProgress(_) = lobby.status {
+                            hints.push(format!("{} reset editor", key_bindings.lobby.reset_editor));
+                        } else if lobby.recording.is_some() {
+                            hints.push(format!("{} replay", key_bindings.lobby.replay));
+                        }
+                        hints.push(format!("{} disconnect", key_bindings.lobby.disconnect));
+                    }
+                    Connection::Practice(_) => {
+                        hints.push(format!("{} editor", key_bindings.lobby.focus_editor));
+                        hints.push(format!(
+                            "{} full screen editor",
+                            key_bindings.miscellaneous.toggle_editor_full_screen
+                        ));
+                        hints.push(format!("{} goal", key_bindings.lobby.focus_goal));
+                        hints.push(format!(
+                            "{} layout",
+                            key_bindings.lobby.toggle_terminal_layout
+                        ));
+                        hints.push(format!(
+                            "{}/{} resize editor",
+                            key_bindings.lobby.grow_editor, key_bindings.lobby.shrink_editor
+                        ));
+                        hints.push(format!("{} start over", key_bindings.lobby.reset_editor));
+                        hints.push(format!("{} end practice", key_bindings.lobby.disconnect));
+                    }
+                    Connection::Offline(_) => {
+                        hints.push(format!("{} practice", key_bindings.join.practice));
+                    }
+                }
+            }
+            Tab::Logs => {
+                hints.push(format!("{} focus logs", key_bindings.logs.focus_logs));
+            }
+            Tab::Settings => {
+                hints.push(format!(
+                    "{}/{} switch profile",
+                    key_bindings.movement.up, key_bindings.movement.down
+                ));
+            }
+        }
+        hints.push(format!("{} quit", key_bindings.miscellaneous.unfocus));
+    }
+
+    let help_bar = Paragraph::new(hints.join(" · ")).style(Style::default().fg(Color::DarkGray));
+    f.render_widget(help_bar, area);
+}
@@ -1,22 +1,91 @@
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
     text::Line,
-    widgets::{Block, Paragraph},
+    widgets::{Block, Paragraph, Row, Table},
     Frame,
 };
 
-use crate::app::App;
+use crate::{app::App, constants::LEADERBOARD_SIZE};
 
 use super::centered_rect;
 
 pub fn draw_home_tab(f: &mut Frame, app: &mut App, area: Rect) {
+    // Add padding for the borders of both blocks and the table header.
+    let leaderboard_height = app.leaderboard.len().min(LEADERBOARD_SIZE) as u16 + 4;
+    let [status_area, leaderboard_area] = Layout::vertical([
+        Constraint::Length(7),
+        Constraint::Length(leaderboard_height),
+    ])
+    .flex(Flex::Center)
+    .areas(area);
+
+    draw_live_status(f, app, status_area);
+    draw_leaderboard(f, app, leaderboard_area);
+}
+
+fn draw_live_status(f: &mut Frame, app: &App, area: Rect) {
     let popup = Block::bordered().title("Live status");
+    let average_players = if app.total_lobbies > 0 {
+        app.total_players as f64 / app.total_lobbies as f64
+    } else {
+        0.0
+    };
     let text = vec![
         Line::from(format!("Clients connected: {}", app.total_clients)),
         Line::from(format!("Players connected: {}", app.total_players)),
+        Line::from(format!("Spectators connected: {}", app.total_spectators)),
+        Line::from(format!("Active lobbies: {}", app.total_lobbies)),
+        Line::from(format!("Players per lobby: {average_players:.1}")),
     ];
-    let area = centered_rect(area, 25, 2);
+    let area = centered_rect(area, 28, 5);
 
     let paragraph = Paragraph::new(text).block(popup);
     f.render_widget(paragraph, area);
 }
+
+/// # Draw leaderboard
+///
+/// Draws the players with the most wins. Ties are broken by the best
+/// completion time.
+fn draw_leaderboard(f: &mut Frame, app: &App, area: Rect) {
+    let mut standings = app.leaderboard.iter().collect::<Vec<_>>();
+    standings.sort_by(|(_, a), (_, b)| {
+        b.wins.cmp(&a.wins).then_with(|| {
+            a.best_time_ms
+                .unwrap_or(i64::MAX)
+                .cmp(&b.best_time_ms.unwrap_or(i64::MAX))
+        })
+    });
+
+    let rows = standings
+        .into_iter()
+        .take(LEADERBOARD_SIZE)
+        .enumerate()
+        .map(|(index, (name, standing))| {
+            let best_time = standing
+                .best_time_ms
+                .map(|time| format!("{:.1}s", time as f64 / 1000.0))
+                .unwrap_or_else(|| "-".to_string());
+            Row::new(vec![
+                format!("{}.", index + 1),
+                name.clone(),
+                standing.wins.to_string(),
+                best_time,
+            ])
+        });
+    let widths = [
+        Constraint::Length(3),
+        Constraint::Length(20),
+        Constraint::Length(4),
+        Constraint::Length(9),
+    ];
+    let table = Table::new(rows, widths)
+        .column_spacing(1)
+        .header(Row::new(vec!["#", "Name", "Wins", "Best time"]).style(Style::new().bold()))
+        .block(Block::bordered().title("Leaderboard"));
+
+    // The table is as wide as its columns plus spacing.
+    let area = centered_rect(area, 39, area.height.saturating_sub(2));
+    f.render_widget(table, area);
+}
@@ -1,16 +1,27 @@
 use ratatui::{
-    layout::Rect,
+    buffer::Buffer,
+    layout::{Alignment, Rect},
     style::{Color, Style},
-    widgets::Block,
+    widgets::{block::Title, Block, Widget},
     Frame,
 };
 use tui_logger::{TuiLoggerLevelOutput, TuiLoggerWidget};
 
-pub fn draw_logs_tab(f: &mut Frame, area: Rect) {
-    let block = Block::bordered().title("Logger");
+use crate::{app::App, schema::focused_component::ComponentKind};
+
+pub fn draw_logs_tab(f: &mut Frame, app: &App, area: Rect) {
+    let focus_logs_key = format!("{}", app.config.key_bindings.logs.focus_logs);
+    let mut block = Block::bordered()
+        .title("Logger")
+        .title(Title::from(focus_logs_key).alignment(Alignment::Right));
+    if app.focused_component_is_kind(ComponentKind::Logs) {
+        block = block.border_style(Style::default().fg(app.config.theme.focus_border));
+    }
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
     let logger = TuiLoggerWidget::default()
-        .block(block)
-        .style_error(Style::default().fg(Color::LightRed))
+        .style_error(Style::default().fg(app.config.theme.error))
         .style_debug(Style::default().fg(Color::DarkGray))
         .style_warn(Style::default().fg(Color::LightYellow))
         .style_info(Style::default().fg(Color::LightGreen))
@@ -21,5 +32,22 @@ pub fn draw_logs_tab(f: &mut Frame, area: Rect) {
         .output_file(false)
         .output_line(false);
 
-    f.render_widget(logger, area);
+    // The logger wraps lines at the width of its area. To scroll
+    // horizontally, render it into a buffer widened by the offset and only
+    // copy the visible part.
+    let offset = app.logs_offset;
+    let mut buffer = Buffer::empty(Rect::new(0, 0, inner.width + offset, inner.height));
+    logger.render(buffer.area, &mut buffer);
+
+    let frame_buffer = f.buffer_mut();
+    for y in 0..inner.height {
+        for x in 0..inner.width {
+            if let (Some(cell), Some(target)) = (
+                buffer.cell((x + offset, y)),
+                frame_buffer.cell_mut((inner.x + x, inner.y + y)),
+            ) {
+                *target = cell.clone();
+            }
+        }
+    }
 }
@@ -1,28 +1,41 @@
 use chrono::{DateTime, Utc};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
-    style::{Color, Style},
-    widgets::{Block, Clear, Paragraph},
+    style::{Style, Stylize},
+    widgets::{Block, Clear, Paragraph, Row, Table},
     Frame,
 };
 
-use common::LobbyStatus;
+use common::{FinalStanding, LobbyStatus};
 
 use self::{
-    chat::draw_chat, editor::draw_editor, goal::draw_goal, join::draw_join, lobby::draw_lobby,
+    chat::draw_chat,
+    diff::draw_diff,
+    editor::draw_editor,
+    goal::draw_goal,
+    join::draw_join,
+    lobby::{draw_lobby, draw_spectating},
+    practice::draw_practice,
+    replay::draw_replay,
 };
 use super::centered_rect;
 use crate::{
     app::App,
-    constants::{EDITOR_HEIGHT, GOAL_HEIGHT, PLAY_SIDE_WIDTH, TERMINAL_WIDTH},
-    schema::connection::Connection,
+    config::Config,
+    constants::{PLAY_SIDE_WIDTH, TERMINAL_WIDTH},
+    schema::{connection::Connection, focused_component::ComponentKind},
 };
 
+mod challenge;
 pub mod chat;
+pub mod diff;
 pub mod editor;
 pub mod goal;
 pub mod join;
-mod lobby;
+pub mod lobby;
+mod player_detail;
+mod practice;
+mod replay;
 
 pub fn draw_play_tab(f: &mut Frame, app: &mut App, area: Rect) {
     match app.connection {
@@ -35,7 +48,7 @@ pub fn draw_play_tab(f: &mut Frame, app: &mut App, area: Rect) {
             let vertical =
                 Layout::vertical([Constraint::Min(0), Constraint::Min(0)]).split(horizontal[0]);
 
-            draw_lobby(f, vertical[0], &app.config, lobby);
+            draw_lobby(f, vertical[0], &app.config, lobby, &app.focused_component);
             draw_chat(
                 f,
                 vertical[1],
@@ -46,45 +59,105 @@ pub fn draw_play_tab(f: &mut Frame, app: &mut App, area: Rect) {
 
             let layout = Layout::new(
                 lobby.terminal_layout_direction,
-                [
-                    // Convert constant heights into integer percentage values.
-                    Constraint::Percentage((EDITOR_HEIGHT * 100.0) as u16),
-                    Constraint::Percentage((GOAL_HEIGHT * 100.0) as u16),
-                ],
+                terminal_constraints(lobby.editor_ratio),
             )
             .split(horizontal[1]);
 
+            // A running replay takes the place of the editor.
+            match (&lobby.replay, &lobby.editor) {
+                (Some(replay), _) => draw_replay(f, layout[0], replay),
+                (None, Some(editor)) => draw_editor(
+                    f,
+                    layout[0],
+                    &app.config,
+                    editor,
+                    &lobby.editor_restarts.error,
+                    &app.focused_component,
+                ),
+                (None, None) => draw_spectating(f, layout[0], &app.config),
+            }
+            // The focused diff takes the place of the goal.
+            let diff_focused = app
+                .focused_component
+                .as_ref()
+                .is_some_and(|component| component.kind.eq(&ComponentKind::Diff));
+            if diff_focused {
+                draw_diff(f, layout[1], &app.config, &lobby.diff);
+            } else {
+                draw_goal(
+                    f,
+                    layout[1],
+                    &app.config,
+                    &lobby.goal,
+                    &lobby.goal_restarts.error,
+                    &app.focused_component,
+                );
+            }
+
+            if let LobbyStatus::AboutToStart(start_date) = lobby.status {
+                draw_start_timer(f, area, &app.config, start_date);
+            }
+            if let LobbyStatus::Paused { .. } = lobby.status {
+                draw_paused(f, area, &app.config);
+            }
+            if let Some(ref standings) = lobby.final_standings {
+                draw_final_standings(f, area, &app.config, standings);
+            }
+        }
+        // If we are not connected to a lobby, draw the join form.
+        Connection::Join(ref mut join) => {
+            draw_join(f, &app.config, area, join, &app.focused_component);
+        }
+        Connection::Practice(ref practice) => {
+            let horizontal = Layout::horizontal([
+                Constraint::Percentage((PLAY_SIDE_WIDTH * 100.0) as u16),
+                Constraint::Percentage((TERMINAL_WIDTH * 100.0) as u16),
+            ])
+            .split(area);
+            draw_practice(f, horizontal[0], &app.config, practice);
+
+            let layout = Layout::new(
+                practice.terminal_layout_direction,
+                terminal_constraints(practice.editor_ratio),
+            )
+            .split(horizontal[1]);
             draw_editor(
                 f,
                 layout[0],
                 &app.config,
-                &lobby.editor,
+                &practice.editor,
+                &practice.editor_restarts.error,
                 &app.focused_component,
             );
             draw_goal(
                 f,
                 layout[1],
                 &app.config,
-                &lobby.goal,
+                &practice.goal,
+                &practice.goal_restarts.error,
                 &app.focused_component,
             );
-
-            if let LobbyStatus::AboutToStart(start_date) = lobby.status {
-                draw_start_timer(f, area, start_date);
-            }
-        }
-        // If we are not connected to a lobby, draw the join form.
-        Connection::Join(ref mut join) => {
-            draw_join(f, &app.config, area, join, &app.focused_component);
         }
         Connection::Offline(_) => {}
     }
 }
 
-fn draw_start_timer(f: &mut Frame, area: Rect, start_date: DateTime<Utc>) {
+/// # Terminal constraints
+///
+/// Splits the terminal area between editor and goal by the given editor
+/// ratio, converted into integer percentage values.
+fn terminal_constraints(editor_ratio: f64) -> [Constraint; 2] {
+    let editor_percentage = (editor_ratio * 100.0).round() as u16;
+    [
+        Constraint::Percentage(editor_percentage),
+        Constraint::Percentage(100 - editor_percentage),
+    ]
+}
+
+fn draw_start_timer(f: &mut Frame, area: Rect, config: &Config, start_date: DateTime<Utc>) {
     let popup = Block::bordered()
         .title("Get ready")
-        .border_style(Style::default().fg(Color::LightYellow));
+        .border_style(Style::default().fg(config.theme.popup_border));
     let now = Utc::now();
     let remaining_millis = start_date.signed_duration_since(now).num_milliseconds();
     let seconds_with_millis = remaining_millis as f64 / 1000.0;
@@ -95,3 +168,52 @@ fn draw_start_timer(f: &mut Frame, area: Rect, start_date: DateTime<Utc>) {
     f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
 }
+
+fn draw_paused(f: &mut Frame, area: Rect, config: &Config) {
+    let popup = Block::bordered()
+        .title("Paused")
+        .border_style(Style::default().fg(config.theme.popup_border));
+    let text = "The lobby owner paused the game.";
+
+    let area = centered_rect(area, text.len() as u16, 1);
+    let paragraph = Paragraph::new(text).block(popup);
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// # Draw final standings
+///
+/// Lists the players of the finished game by rank, together with their
+/// progress and, if they reached the goal, their time.
+fn draw_final_standings(f: &mut Frame, area: Rect, config: &Config, standings: &[FinalStanding]) {
+    let popup = Block::bordered()
+        .title("Final standings")
+        .border_style(Style::default().fg(config.theme.popup_border));
+    let rows = standings.iter().map(|standing| {
+        let time = standing
+            .finish_time_ms
+            .map(|time| format!("{:.1}s", time as f64 / 1000.0))
+            .unwrap_or_else(|| "-".to_string());
+        Row::new(vec![
+            format!("{}.", standing.rank),
+            standing.player_name.clone(),
+            format!("{:.0}%", standing.progress * 100.0),
+            time,
+        ])
+    });
+    let widths = [
+        Constraint::Length(3),
+        Constraint::Length(20),
+        Constraint::Length(4),
+        Constraint::Length(7),
+    ];
+    let table = Table::new(rows, widths)
+        .column_spacing(1)
+        .header(Row::new(vec!["#", "Name", "Done", "Time"]).style(Style::new().bold()))
+        .block(popup);
+
+    // The table is as wide as its columns plus spacing and holds a header.
+    let area = centered_rect(area, 37, standings.len() as u16 + 1);
+    f.render_widget(Clear, area);
+    f.render_widget(table, area);
+}
@@ -0,0 +1,42 @@
+use common::ChallengeFiles;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    widgets::{Block, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::constants::{CHALLENGE_HEIGHT, INSTRUCTIONS_HEIGHT};
+
+/// # Challenge height
+///
+/// Returns the rows needed to draw the given challenge, which grows by the
+/// instructions panel if the challenge has instructions.
+pub fn challenge_height(challenge: &ChallengeFiles) -> u16 {
+    if challenge.instructions.is_some() {
+        CHALLENGE_HEIGHT + INSTRUCTIONS_HEIGHT
+    } else {
+        CHALLENGE_HEIGHT
+    }
+}
+
+/// # Draw challenge
+///
+/// Displays the name, language and description of the challenge. Optional
+/// instructions are shown in a panel below.
+pub fn draw_challenge(f: &mut Frame, area: Rect, challenge: &ChallengeFiles) {
+    let [description_area, instructions_area] =
+        Layout::vertical([Constraint::Length(CHALLENGE_HEIGHT), Constraint::Min(0)]).areas(area);
+
+    let block = Block::bordered().title(format!("Challenge: {}", challenge.name));
+    let text = format!("{}: {}", challenge.language, challenge.description);
+    let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
+    f.render_widget(paragraph, description_area);
+
+    if let Some(ref instructions) = challenge.instructions {
+        let block = Block::bordered().title("Instructions");
+        let paragraph = Paragraph::new(instructions.as_str())
+            .block(block)
+            .wrap(Wrap { trim: true });
+        f.render_widget(paragraph, instructions_area);
+    }
+}
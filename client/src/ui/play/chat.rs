@@ -38,7 +38,7 @@ pub fn draw_chat(
         .as_ref()
         .is_some_and(|component| component.kind.eq(&ComponentKind::Chat))
     {
-        input_block = input_block.border_style(Style::default().fg(Color::Green));
+        input_block = input_block.border_style(Style::default().fg(config.theme.focus_border));
         input_text.push('|');
     }
 
@@ -55,6 +55,8 @@ pub fn draw_chat(
     f.render_widget(input, chunks[1]);
 
     let chat_width = chunks[0].width - 2;
+    // Page by the number of rows between the borders.
+    chat.page_height = chunks[0].height.saturating_sub(2).max(1).into();
     let messages: Vec<Row> = chat
         .messages
         .iter()
@@ -93,7 +95,7 @@ fn insert_newlines(text: &str, width: usize) -> (String, u16) {
 
             // Break the long word into chunks and add each to the result
             let word_chars: Vec<_> = word.chars().collect();
-            let chunk_count = (word.len() + width - 1) / width; // Number of chunks
+            let chunk_count = word.len().div_ceil(width); // Number of chunks
             for (i, chunk) in word_chars.chunks(width).enumerate() {
                 result.push_str(&chunk.iter().collect::<String>());
                 if i < chunk_count - 1 {
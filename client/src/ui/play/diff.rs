@@ -0,0 +1,42 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{block::Title, Block, Paragraph},
+    Frame,
+};
+
+use crate::{
+    config::Config,
+    schema::diff::{Diff, DiffLine},
+};
+
+/// # Draw diff
+///
+/// Renders the unified diff of the player file against the goal file. Lines
+/// missing from the player file are prefixed with `+`, lines that have to go
+/// with `-`.
+pub fn draw_diff(f: &mut Frame, area: Rect, config: &Config, diff: &Diff) {
+    let focus_diff_key = format!("{}", config.key_bindings.lobby.focus_diff);
+    let mut block = Block::bordered()
+        .title("Diff")
+        .title(Title::from(focus_diff_key).alignment(Alignment::Right))
+        .border_style(Style::default().fg(config.theme.focus_border));
+    if !diff.has_changes() {
+        block = block.title_bottom("No differences");
+    }
+
+    let lines: Vec<Line> = diff
+        .lines
+        .iter()
+        .skip(diff.offset)
+        .map(|line| match line {
+            DiffLine::Equal(text) => Line::raw(format!("  {text}")),
+            DiffLine::Added(text) => Line::styled(format!("+ {text}"), Color::Green),
+            DiffLine::Removed(text) => Line::styled(format!("- {text}"), Color::Red),
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(lines).block(block);
+    f.render_widget(paragraph, area);
+}
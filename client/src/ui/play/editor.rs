@@ -1,7 +1,8 @@
 use ratatui::{
-    layout::{Alignment, Rect},
-    style::{Color, Style},
-    widgets::{block::Title, Block},
+    layout::{Alignment, Position, Rect},
+    style::Style,
+    text::Line,
+    widgets::{block::Title, Block, Paragraph, Wrap},
     Frame,
 };
 use tui_term::widget::PseudoTerminal;
@@ -9,7 +10,8 @@ use tui_term::widget::PseudoTerminal;
 use crate::{
     config::Config,
     schema::{
-        editor::Editor,
+        builtin_editor::BuiltinEditor,
+        editor::{Editor, EditorKind},
         focused_component::{ComponentKind, FocusedComponent},
     },
 };
@@ -19,6 +21,7 @@ pub fn draw_editor(
     area: Rect,
     config: &Config,
     editor: &Editor,
+    error: &Option<String>,
     focused_component: &Option<FocusedComponent>,
 ) {
     let focus_editor_key = format!("{}", config.key_bindings.lobby.focus_editor);
@@ -26,17 +29,110 @@ pub fn draw_editor(
         .title("Editor")
         .title(Title::from(focus_editor_key).alignment(Alignment::Right));
 
-    if focused_component
+    let is_focused = focused_component
         .as_ref()
-        .is_some_and(|component| component.kind.eq(&ComponentKind::Editor))
-    {
-        block = block.border_style(Style::default().fg(Color::Green));
+        .is_some_and(|component| component.kind.eq(&ComponentKind::Editor));
+    if is_focused {
+        block = block.border_style(Style::default().fg(config.theme.focus_border));
+    }
+
+    // Show why the editor is not running instead of its stale content.
+    if let Some(error) = error {
+        let text = format!(
+            "{error}\n\nReset the editor with {}.",
+            config.key_bindings.lobby.reset_editor
+        );
+        draw_editor_error(f, area, config, block, text);
+        return;
+    }
+
+    match editor.kind {
+        EditorKind::Terminal(ref terminal) => {
+            let parser = terminal
+                .parser
+                .lock()
+                .expect("Unable to lock editor parser");
+            let terminal = PseudoTerminal::new(parser.screen()).block(block);
+            f.render_widget(terminal, area);
+        }
+        EditorKind::Builtin(ref builtin) => {
+            let block = with_fallback_reason(block, config, &editor.fallback_reason);
+            draw_builtin_editor(f, area, block, builtin, is_focused);
+        }
+        EditorKind::Unsupported => draw_unsupported(f, area, config, block),
+    }
+}
+
+/// # Draw unsupported
+///
+/// Explains why no editor is shown for a binary challenge.
+pub fn draw_unsupported(f: &mut Frame, area: Rect, config: &Config, block: Block) {
+    draw_editor_error(
+        f,
+        area,
+        config,
+        block,
+        "Binary challenge unsupported in editor.".to_string(),
+    );
+}
+
+/// # Draw editor error
+///
+/// Renders an error message of a crashed editor inside its pane.
+pub fn draw_editor_error(f: &mut Frame, area: Rect, config: &Config, block: Block, text: String) {
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .style(Style::default().fg(config.theme.error))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+/// # With fallback reason
+///
+/// Tells the user at the bottom of the block why the built-in editor replaced
+/// the external one.
+pub fn with_fallback_reason<'a>(
+    block: Block<'a>,
+    config: &Config,
+    fallback_reason: &Option<String>,
+) -> Block<'a> {
+    match fallback_reason {
+        Some(reason) => block.title_bottom(Line::styled(
+            format!("{reason} Using the built-in editor."),
+            Style::default().fg(config.theme.error),
+        )),
+        None => block,
+    }
+}
+
+/// # Draw built-in editor
+///
+/// Renders the buffer of the built-in editor and scrolls it so that the
+/// cursor always stays visible.
+pub fn draw_builtin_editor(
+    f: &mut Frame,
+    area: Rect,
+    block: Block,
+    editor: &BuiltinEditor,
+    is_focused: bool,
+) {
+    let inner = block.inner(area);
+    let (row, col) = editor.cursor;
+
+    // Scroll just enough to keep the cursor inside the visible area.
+    let row_offset = (row + 1).saturating_sub(inner.height as usize);
+    let col_offset = (col + 1).saturating_sub(inner.width as usize);
+
+    let text = editor.lines.join("\n");
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .scroll((row_offset as u16, col_offset as u16));
+    f.render_widget(paragraph, area);
+
+    if is_focused {
+        f.set_cursor_position(Position::new(
+            inner.x + (col - col_offset) as u16,
+            inner.y + (row - row_offset) as u16,
+        ));
     }
-    let parser = editor
-        .terminal
-        .parser
-        .lock()
-        .expect("Unable to lock editor parser");
-    let terminal = PseudoTerminal::new(parser.screen()).block(block);
-    f.render_widget(terminal, area);
 }
@@ -1,14 +1,18 @@
 use ratatui::{
     layout::{Alignment, Rect},
-    style::{Color, Style},
+    style::Style,
     widgets::{block::Title, Block},
     Frame,
 };
 use tui_term::widget::PseudoTerminal;
 
+use super::editor::{
+    draw_builtin_editor, draw_editor_error, draw_unsupported, with_fallback_reason,
+};
 use crate::{
     config::Config,
     schema::{
+        editor::EditorKind,
         focused_component::{ComponentKind, FocusedComponent},
         goal::Goal,
     },
@@ -19,6 +23,7 @@ pub fn draw_goal(
     area: Rect,
     config: &Config,
     goal: &Goal,
+    error: &Option<String>,
     focused_component: &Option<FocusedComponent>,
 ) {
     let focus_goal_key = format!("{}", config.key_bindings.lobby.focus_goal);
@@ -30,13 +35,27 @@ pub fn draw_goal(
         .as_ref()
         .is_some_and(|component| component.kind.eq(&ComponentKind::Goal))
     {
-        block = block.border_style(Style::default().fg(Color::Green));
+        block = block.border_style(Style::default().fg(config.theme.focus_border));
+    }
+    if let Some(error) = error {
+        draw_editor_error(f, area, config, block, error.clone());
+        return;
+    }
+
+    match goal.kind {
+        EditorKind::Terminal(ref terminal) => {
+            let parser = terminal
+                .parser
+                .lock()
+                .expect("Unable to lock editor parser");
+            let terminal = PseudoTerminal::new(parser.screen()).block(block);
+            f.render_widget(terminal, area);
+        }
+        // The goal is read-only, so never show a cursor.
+        EditorKind::Builtin(ref builtin) => {
+            let block = with_fallback_reason(block, config, &goal.fallback_reason);
+            draw_builtin_editor(f, area, block, builtin, false)
+        }
+        EditorKind::Unsupported => draw_unsupported(f, area, config, block),
     }
-    let parser = goal
-        .terminal
-        .parser
-        .lock()
-        .expect("Unable to lock editor parser");
-    let terminal = PseudoTerminal::new(parser.screen()).block(block);
-    f.render_widget(terminal, area);
 }
@@ -1,12 +1,18 @@
+use chrono::Utc;
 use ratatui::{
-    layout::{Alignment, Constraint, Margin, Rect},
+    layout::{Alignment, Constraint, Layout, Margin, Rect},
     style::{Color, Modifier, Style, Stylize},
-    widgets::{block::Title, Block, Cell, Row, Scrollbar, ScrollbarOrientation, Table},
+    text::Line,
+    widgets::{
+        block::Title, Block, Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, Table,
+        TableState,
+    },
     Frame,
 };
 
 use crate::{
     config::Config,
+    constants::LOBBY_TABLE_HEADER_HEIGHT,
     schema::{
         focused_component::{ComponentKind, FocusedComponent},
         join::Join,
@@ -22,23 +28,65 @@ pub fn draw_join(
     focused_component: &Option<FocusedComponent>,
 ) {
     let focus_lobby_key = format!("{}", config.key_bindings.join.focus_lobby_list);
+    let capacity = format!("New lobbies hold {} players", join.max_players);
+    let sort_order = format!("Sorted by {}", join.sort_order);
     let mut block = Block::bordered()
         .title("Lobbies")
-        .title(Title::from(focus_lobby_key).alignment(Alignment::Right));
+        .title(Title::from(focus_lobby_key).alignment(Alignment::Right))
+        .title_bottom(capacity)
+        .title_bottom(Line::from(sort_order).right_aligned());
+    if let Some(ref notice) = join.notice {
+        block = block.title_bottom(
+            Line::from(notice.clone())
+                .centered()
+                .style(Style::default().fg(config.theme.error)),
+        );
+    }
 
     if focused_component
         .as_ref()
         .is_some_and(|component| component.kind.eq(&ComponentKind::Lobbies))
     {
-        block = block.border_style(Style::default().fg(Color::Green));
+        block = block.border_style(Style::default().fg(config.theme.focus_border));
     }
 
+    // Show the search input while searching or filtering.
+    let is_searching = focused_component
+        .as_ref()
+        .is_some_and(|component| component.kind.eq(&ComponentKind::LobbySearch));
+    let area = if is_searching || !join.search.is_empty() {
+        let [search_area, table_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+        draw_search(f, config, search_area, &join.search, is_searching);
+        table_area
+    } else {
+        area
+    };
+
+    // Only build the rows that fit into the table, the bottom border takes up
+    // the last line.
+    join.page_height = area
+        .height
+        .saturating_sub(LOBBY_TABLE_HEADER_HEIGHT + 1)
+        .into();
+    let row_count = join.listed_lobbies().count();
+    join.scroll_to_selection(row_count);
+    let window = join.visible_window();
+
     let rows = join
-        .encrypted_names
-        .iter()
-        .zip(join.encrypted_player_counts.values())
-        .zip(join.encrypted_status.values())
-        .map(|(((_, name), player_count), status)| {
+        .listed_lobbies()
+        .skip(window.start)
+        .take(window.len())
+        .filter_map(|id| {
+            Some((
+                id,
+                join.encrypted_names.get(id)?,
+                join.encrypted_player_counts.get(id)?,
+                join.encrypted_challenges.get(id)?,
+                join.encrypted_status.get(id)?,
+            ))
+        })
+        .map(|(id, name, player_count, challenge, status)| {
             let encrypted_name = name
                 .value
                 .chars()
@@ -47,7 +95,7 @@ pub fn draw_join(
                     if i < name.index {
                         c
                     } else {
-                        get_random_symbol()
+                        get_random_symbol(&config.general.scramble_symbols)
                     }
                 })
                 .collect::<String>();
@@ -59,11 +107,23 @@ pub fn draw_join(
                     if i < player_count.index {
                         c
                     } else {
-                        get_random_symbol()
+                        get_random_symbol(&config.general.scramble_symbols)
+                    }
+                })
+                .collect::<String>();
+            let encrypted_challenge = challenge
+                .value
+                .chars()
+                .enumerate()
+                .map(|(i, c)| {
+                    if i < challenge.index {
+                        c
+                    } else {
+                        get_random_symbol(&config.general.scramble_symbols)
                     }
                 })
                 .collect::<String>();
-            let encrypted_status = status
+            let mut encrypted_status = status
                 .value
                 .chars()
                 .enumerate()
@@ -71,22 +131,39 @@ pub fn draw_join(
                     if i < status.index {
                         c
                     } else {
-                        get_random_symbol()
+                        get_random_symbol(&config.general.scramble_symbols)
                     }
                 })
                 .collect::<String>();
+
+            // Once the status is fully decrypted, tell the user when an empty
+            // lobby is about to be removed.
+            if let Some(removal_time) = join
+                .lobby_list
+                .get(id)
+                .and_then(|lobby| lobby.removal_time)
+                .filter(|_| status.index >= status.value.len())
+            {
+                let remaining_seconds = removal_time
+                    .signed_duration_since(Utc::now())
+                    .num_seconds()
+                    .max(0);
+                encrypted_status.push_str(&format!(" (closes in {}s if empty)", remaining_seconds));
+            }
             let row = Row::new(vec![
                 Cell::from(encrypted_name),
                 Cell::from(encrypted_player_count),
+                Cell::from(encrypted_challenge),
                 Cell::from(encrypted_status),
             ]);
             row
         });
     // Columns widths are constrained in the same way as Layout...
     let widths = [
-        Constraint::Percentage(33),
-        Constraint::Percentage(33),
-        Constraint::Percentage(33),
+        Constraint::Percentage(25),
+        Constraint::Percentage(15),
+        Constraint::Percentage(25),
+        Constraint::Percentage(35),
     ];
     let selected_style = Style::default()
         .add_modifier(Modifier::REVERSED)
@@ -94,14 +171,22 @@ pub fn draw_join(
     let table = Table::new(rows, widths)
         .column_spacing(1)
         .header(
-            Row::new(vec!["Name", "Player count", "Status"])
+            Row::new(vec!["Name", "Player count", "Challenge", "Status"])
                 .style(Style::new().bold())
                 .bottom_margin(1),
         )
         .block(block)
         .highlight_style(selected_style);
 
-    f.render_stateful_widget(table, area, &mut join.state);
+    // The table only knows about the visible rows, so the selection has to be
+    // relative to the visible window.
+    let mut state = TableState::default().with_selected(
+        join.state
+            .selected()
+            .and_then(|i| i.checked_sub(window.start)),
+    );
+    join.table_area = area;
+    f.render_stateful_widget(table, area, &mut state);
     f.render_stateful_widget(
         Scrollbar::default()
             .orientation(ScrollbarOrientation::VerticalRight)
@@ -114,3 +199,19 @@ pub fn draw_join(
         &mut join.scroll_state,
     );
 }
+
+/// # Draw search
+///
+/// Draws the query the lobby list is filtered by.
+fn draw_search(f: &mut Frame, config: &Config, area: Rect, search: &str, is_searching: bool) {
+    let search_key = format!("{}", config.key_bindings.join.search);
+    let mut block = Block::bordered()
+        .title("Search")
+        .title(Title::from(search_key).alignment(Alignment::Right));
+    let mut text = search.to_string();
+    if is_searching {
+        block = block.border_style(Style::default().fg(config.theme.focus_border));
+        text.push('|');
+    }
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
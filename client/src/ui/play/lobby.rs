@@ -1,22 +1,43 @@
 use chrono::Utc;
 use ratatui::{
-    layout::{Constraint, Layout, Margin, Rect},
+    layout::{Alignment, Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
     text::Line,
-    widgets::{Block, Gauge, List},
+    widgets::{block::Title, Block, Gauge, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
+use super::{
+    challenge::{challenge_height, draw_challenge},
+    player_detail::draw_player_detail,
+};
 use crate::{
     config::Config,
-    schema::{encryption::Encryption, lobby::Lobby},
+    schema::{
+        encryption::{Encryption, EncryptionAction},
+        focused_component::{ComponentKind, FocusedComponent},
+        lobby::Lobby,
+    },
     ui::get_random_symbol,
 };
 
-pub fn draw_lobby(f: &mut Frame, area: Rect, config: &Config, lobby: &mut Lobby) {
+pub fn draw_lobby(
+    f: &mut Frame,
+    area: Rect,
+    config: &Config,
+    lobby: &mut Lobby,
+    focused_component: &Option<FocusedComponent>,
+) {
+    // Highlight the selected player while the player list is focused.
+    let players_focused = focused_component
+        .as_ref()
+        .is_some_and(|component| component.kind.eq(&ComponentKind::Players));
+    let selected_player = lobby.selected_player.filter(|_| players_focused);
     let player_count = lobby.encryptions.len();
     let waiting_player_count = lobby.waiting_encryptions.len();
     let mut constraints = vec![
         Constraint::Length((player_count * 3) as u16 + 2),
+        Constraint::Length(challenge_height(&lobby.challenge_files)),
         Constraint::Min(0),
     ];
     if waiting_player_count > 0 {
@@ -29,12 +50,19 @@ pub fn draw_lobby(f: &mut Frame, area: Rect, config: &Config, lobby: &mut Lobby)
         common::LobbyStatus::AboutToStart(time) => Some(time),
         common::LobbyStatus::InProgress(time) => Some(time),
         common::LobbyStatus::Finish(time) => Some(time),
+        // The remaining time does not change while paused.
+        common::LobbyStatus::Paused { resumed_remaining } => Some(Utc::now() + resumed_remaining),
     };
 
     let title = lobby.name.as_str();
+    let focus_players_key = format!("{}", config.key_bindings.lobby.focus_players);
     let mut block = Block::bordered()
         .title(title)
+        .title(Title::from(focus_players_key).alignment(Alignment::Right))
         .title_bottom(lobby.status.to_string());
+    if players_focused {
+        block = block.border_style(Style::default().fg(config.theme.focus_border));
+    }
 
     if let Some(time) = time {
         let now = Utc::now();
@@ -56,51 +84,159 @@ pub fn draw_lobby(f: &mut Frame, area: Rect, config: &Config, lobby: &mut Lobby)
         let (
             player_id,
             Encryption {
-                action: _,
+                action,
                 index,
                 value,
             },
         ) = encryption;
-        let encryption = value
+        let mut encryption = value
             .chars()
             .enumerate()
-            .map(|(i, c)| if i < *index { c } else { get_random_symbol() })
+            .map(|(i, c)| {
+                if i < *index {
+                    c
+                } else {
+                    get_random_symbol(&config.general.scramble_symbols)
+                }
+            })
             .collect::<String>();
-        let mut gauge = Gauge::default().block(Block::bordered().title(encryption));
+        if matches!(action, EncryptionAction::Joined) {
+            encryption.push_str(&lobby.name_tags(player_id));
+        }
+        // Make the local player easy to find.
+        let mut title = Line::from(encryption);
+        if lobby.local_player.as_ref() == Some(player_id) {
+            title = title.style(Style::default().add_modifier(Modifier::BOLD));
+        }
+        let mut block = Block::bordered().title(title);
+        if selected_player.is_some_and(|selected| selected.eq(player_id)) {
+            block = block.border_style(Style::default().fg(config.theme.highlight));
+        }
+        let mut progress = 0.0;
         if let Some(player) = lobby.players.get(player_id) {
-            gauge = gauge.ratio(player.progress);
+            // Show who is ready for the next game.
+            if lobby.status == common::LobbyStatus::WaitingForPlayers {
+                let (symbol, color) = if player.ready {
+                    ("✓", Color::Green)
+                } else {
+                    ("✗", Color::Red)
+                };
+                block = block.title(Line::from(symbol).style(Style::default().fg(color)));
+            }
+            // Mark players who reached the goal with their position.
+            if let Some(position) = player.position {
+                block = block.title(
+                    Line::from(format!("🏆 #{position}"))
+                        .style(Style::default().fg(config.theme.progress_complete)),
+                );
+            }
+            // Mark players who are currently typing.
+            if lobby.active_players.contains_key(player_id) {
+                block =
+                    block.title(Line::from("✎").style(Style::default().fg(config.theme.highlight)));
+            }
+            // Show the edits per minute next to the progress.
+            let edits_per_minute = format!("{:.0}/min", player.edits_per_minute);
+            block = block.title(Title::from(edits_per_minute).alignment(Alignment::Right));
+            progress = player.progress;
         };
+        // Show the position and time of players who reached the goal.
+        if let Some((position, duration)) = lobby.finished_players.get(player_id) {
+            let result = format!("#{position} in {:.1}s", duration.as_secs_f64());
+            block = block.title_bottom(Line::from(result).right_aligned());
+        }
+        let gauge = Gauge::default()
+            .block(block)
+            .ratio(progress)
+            .gauge_style(Style::default().fg(config.theme.progress_color(progress)));
         f.render_widget(gauge, inner_chunks[i]);
     }
     f.render_widget(block, chunks[0]);
 
-    draw_lobby_commands(f, config, chunks[1], lobby);
+    draw_challenge(f, chunks[1], &lobby.challenge_files);
+    // Inspect the selected player in place of the lobby commands.
+    match selected_player {
+        Some(player_id) if lobby.players.contains_key(&player_id) => {
+            draw_player_detail(f, chunks[2], config, lobby, player_id)
+        }
+        _ => draw_lobby_commands(f, config, chunks[2], lobby),
+    }
 
     if waiting_player_count > 0 {
-        let encrypted_names = lobby.waiting_encryptions.values().map(
-            |Encryption {
-                 action: _,
-                 index,
-                 value,
-             }| {
-                value
+        let encrypted_names = lobby.waiting_encryptions.iter().map(
+            |(
+                player_id,
+                Encryption {
+                    action,
+                    index,
+                    value,
+                },
+            )| {
+                let mut name = value
                     .chars()
                     .enumerate()
-                    .map(|(i, c)| if i < *index { c } else { get_random_symbol() })
-                    .collect::<String>()
+                    .map(|(i, c)| {
+                        if i < *index {
+                            c
+                        } else {
+                            get_random_symbol(&config.general.scramble_symbols)
+                        }
+                    })
+                    .collect::<String>();
+                if matches!(action, EncryptionAction::Joined) {
+                    name.push_str(&lobby.name_tags(player_id));
+                }
+                let mut item = ListItem::new(name);
+                if selected_player.is_some_and(|selected| selected.eq(player_id)) {
+                    item = item.style(Style::default().fg(config.theme.highlight));
+                }
+                item
             },
         );
-        let waiting_players =
-            List::new(encrypted_names).block(Block::bordered().title("Waiting room"));
-        f.render_widget(waiting_players, chunks[2]);
+        // Explain why these players have no gauge.
+        let block = Block::bordered()
+            .title("Waiting room")
+            .title_bottom("Joining the next game");
+        let waiting_players = List::new(encrypted_names).block(block);
+        f.render_widget(waiting_players, chunks[3]);
     }
 }
 
+/// # Draw spectating
+///
+/// Takes the place of the editor while the user watches the lobby.
+pub fn draw_spectating(f: &mut Frame, area: Rect, config: &Config) {
+    let block = Block::bordered()
+        .title("Spectating")
+        .border_style(Style::default().fg(config.theme.popup_border));
+    let text = "You are watching this lobby. Progress, status and chat update live.";
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, area);
+}
+
 fn draw_lobby_commands(f: &mut Frame, config: &Config, area: Rect, lobby: &Lobby) {
     let mut commands = vec![format!(
         "{} - Disconnect from the lobby",
         config.key_bindings.lobby.disconnect
     )];
+    if lobby.status == common::LobbyStatus::WaitingForPlayers && !lobby.spectating {
+        commands.push(format!(
+            "{} - Toggle ready",
+            config.key_bindings.lobby.toggle_ready
+        ));
+    }
+    // Getting unready cancels an automatic start.
+    if let common::LobbyStatus::AboutToStart(_) = lobby.status {
+        if lobby.auto_start && !lobby.spectating {
+            commands.push(format!(
+                "{} - Cancel the automatic start",
+                config.key_bindings.lobby.toggle_ready
+            ));
+        }
+    }
 
     // Add lobby owner specific commands depending on the lobby status.
     if lobby.local_player == lobby.owner && lobby.local_player.is_some() {
@@ -110,11 +246,45 @@ fn draw_lobby_commands(f: &mut Frame, config: &Config, area: Rect, lobby: &Lobby
                     "{} - Start the lobby",
                     config.key_bindings.lobby.start
                 ));
+                commands.push(format!(
+                    "{} - Start without waiting for everyone",
+                    config.key_bindings.lobby.force_start
+                ));
+                commands.push(format!(
+                    "{} - Turn automatic start {}",
+                    config.key_bindings.lobby.toggle_auto_start,
+                    if lobby.auto_start { "off" } else { "on" }
+                ));
+            }
+            common::LobbyStatus::AboutToStart(_) => {
+                commands.push(format!(
+                    "{} - Cancel the start",
+                    config.key_bindings.lobby.cancel_start
+                ));
+            }
+            common::LobbyStatus::InProgress(_) => {
+                commands.push(format!(
+                    "{} - Pause the lobby",
+                    config.key_bindings.lobby.toggle_pause
+                ));
+            }
+            common::LobbyStatus::Paused { .. } => {
+                commands.push(format!(
+                    "{} - Resume the lobby",
+                    config.key_bindings.lobby.toggle_pause
+                ));
             }
-            common::LobbyStatus::AboutToStart(_) => {}
-            common::LobbyStatus::InProgress(_) => {}
             common::LobbyStatus::Finish(_) => {}
         }
+        commands.push(format!(
+            "{} - Select a player to inspect, kick or make owner",
+            config.key_bindings.lobby.focus_players
+        ));
+    } else {
+        commands.push(format!(
+            "{} - Select a player to inspect",
+            config.key_bindings.lobby.focus_players
+        ));
     }
 
     let block = Block::bordered().title("Lobby commands");
@@ -0,0 +1,70 @@
+use common::LobbyStatus;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+use uuid::Uuid;
+
+use crate::{config::Config, schema::lobby::Lobby};
+
+/// # Draw player detail
+///
+/// Shows name, progress, edits per minute, finished position and connection
+/// status of the selected player.
+pub fn draw_player_detail(f: &mut Frame, area: Rect, config: &Config, lobby: &Lobby, id: Uuid) {
+    let Some(player) = lobby.players.get(&id) else {
+        return;
+    };
+
+    let name = format!("{}{}", player.name, lobby.name_tags(&id));
+    let finished = match lobby.finished_players.get(&id) {
+        Some((position, duration)) => format!("#{position} in {:.1}s", duration.as_secs_f64()),
+        None => "Not yet".to_string(),
+    };
+    let status = if player.waiting {
+        "In the waiting room"
+    } else if lobby.active_players.contains_key(&id) {
+        "Typing"
+    } else if lobby.status == LobbyStatus::WaitingForPlayers && player.ready {
+        "Ready"
+    } else if lobby.status == LobbyStatus::WaitingForPlayers {
+        "Not ready"
+    } else {
+        "Connected"
+    };
+
+    let lines = vec![
+        detail_line(config, "Name", name),
+        detail_line(
+            config,
+            "Progress",
+            format!("{:.0}%", player.progress * 100.0),
+        ),
+        detail_line(
+            config,
+            "Speed",
+            format!("{:.0} edits/min", player.edits_per_minute),
+        ),
+        detail_line(config, "Finished", finished),
+        detail_line(config, "Status", status.to_string()),
+    ];
+    let block = Block::bordered()
+        .title("Player")
+        .border_style(Style::default().fg(config.theme.highlight));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn detail_line(config: &Config, label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("{label:<10}"),
+            Style::default()
+                .fg(config.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(value),
+    ])
+}
@@ -0,0 +1,44 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    text::Line,
+    widgets::{Block, Gauge, List},
+    Frame,
+};
+
+use super::challenge::{challenge_height, draw_challenge};
+use crate::{config::Config, schema::practice::Practice};
+
+/// # Draw practice
+///
+/// Shows the practiced challenge, the current progress and the available
+/// commands.
+pub fn draw_practice(f: &mut Frame, area: Rect, config: &Config, practice: &Practice) {
+    let chunks = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(challenge_height(&practice.challenge_files)),
+        Constraint::Min(0),
+    ])
+    .split(area);
+
+    let time = match practice.finished_after {
+        Some(duration) => format!("Reached the goal in {:.1}s", duration.as_secs_f64()),
+        None => format!("{:.1}s", practice.started_at.elapsed().as_secs_f64()),
+    };
+    let block = Block::bordered()
+        .title("Practice")
+        .title_bottom(Line::from(time).right_aligned());
+    let gauge = Gauge::default()
+        .block(block)
+        .ratio(practice.progress.clamp(0.0, 1.0));
+    f.render_widget(gauge, chunks[0]);
+
+    draw_challenge(f, chunks[1], &practice.challenge_files);
+
+    let commands = vec![
+        format!("{} - End practice", config.key_bindings.lobby.disconnect),
+        format!("{} - Start over", config.key_bindings.lobby.reset_editor),
+    ];
+    let block = Block::bordered().title("Practice commands");
+    let command_list = List::new(commands).block(block);
+    f.render_widget(command_list, chunks[2]);
+}
@@ -0,0 +1,31 @@
+use ratatui::{
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::schema::replay::Replay;
+
+/// # Draw replay
+///
+/// Shows the player file as it was at the replayed time, in place of the
+/// editor.
+pub fn draw_replay(f: &mut Frame, area: Rect, replay: &Replay) {
+    let elapsed = replay.elapsed().min(replay.duration());
+    let time = format!(
+        "{:.1}s / {:.1}s",
+        elapsed.as_secs_f64(),
+        replay.duration().as_secs_f64()
+    );
+    let block = Block::bordered()
+        .title(format!("Replay ({}x)", replay.speed))
+        .title_bottom(Line::from(time).right_aligned());
+
+    let content = replay
+        .current_frame()
+        .map(|frame| String::from_utf8_lossy(&frame.content).into_owned())
+        .unwrap_or_default();
+    let paragraph = Paragraph::new(content).block(block);
+    f.render_widget(paragraph, area);
+}
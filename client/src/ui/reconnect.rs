@@ -0,0 +1,34 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use super::centered_rect;
+use crate::{config::Config, schema::session::Session};
+
+/// # Draw reconnect
+///
+/// Offers to reconnect to the backend service and lobby of the last session.
+pub fn draw_reconnect(f: &mut Frame, config: &Config, session: &Session) {
+    let title = "Reconnect to last session?";
+    let target = match session.lobby_name {
+        Some(ref name) => format!("Lobby {name} on {}", session.service.address),
+        None => format!("Server {}", session.service.address),
+    };
+    let keys = format!(
+        "Confirm {}, Abort {}",
+        config.key_bindings.popup.confirm, config.key_bindings.popup.abort
+    );
+    let popup = Block::bordered()
+        .title(title)
+        .border_style(Style::default().fg(Color::Black));
+    // Make sure the title fits into the popup.
+    let width = target.len().max(keys.len()).max(title.len()) as u16;
+    let area = centered_rect(f.area(), width, 2);
+    let paragraph = Paragraph::new(vec![Line::from(target), Line::from(keys)])
+        .block(popup)
+        .style(Style::default().bg(Color::LightGreen).fg(Color::Black));
+    f.render_widget(paragraph, area);
+}
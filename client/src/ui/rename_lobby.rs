@@ -0,0 +1,27 @@
+use common::constants::MAX_LOBBY_NAME_LENGTH;
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use super::centered_rect;
+
+/// # Draw rename lobby
+///
+/// Shows the new lobby name while the owner types it.
+pub fn draw_rename_lobby(f: &mut Frame, name_input: &str) {
+    let title = "Rename lobby";
+    let keys = "Confirm Enter, Abort Esc";
+    let popup = Block::bordered()
+        .title(title)
+        .border_style(Style::default().fg(Color::Black));
+    // Make sure the longest possible name fits into the popup.
+    let width = (MAX_LOBBY_NAME_LENGTH + 1).max(keys.len()) as u16;
+    let area = centered_rect(f.area(), width, 2);
+    let paragraph = Paragraph::new(vec![Line::from(format!("{name_input}|")), Line::from(keys)])
+        .block(popup)
+        .style(Style::default().bg(Color::LightCyan).fg(Color::Black));
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,27 @@
+use ratatui::{
+    style::{Color, Style},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::config::Config;
+
+use super::centered_rect;
+
+pub fn draw_reset_editor(f: &mut Frame, config: &Config) {
+    let title = "Reset editor?";
+    let popup = Block::bordered()
+        .title(title)
+        .border_style(Style::default().fg(Color::Black));
+    let text = format!(
+        "Confirm {}, Abort {}",
+        config.key_bindings.popup.confirm, config.key_bindings.popup.abort
+    );
+    // Make sure the title fits into the popup.
+    let width = text.len().max(title.len()) as u16;
+    let area = centered_rect(f.area(), width, 1);
+    let paragraph = Paragraph::new(text)
+        .block(popup)
+        .style(Style::default().bg(Color::LightYellow).fg(Color::Black));
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,31 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::Style,
+    text::Line,
+    widgets::{block::Title, Block, List},
+    Frame,
+};
+
+use crate::config::Config;
+
+pub fn draw_settings_tab(f: &mut Frame, config: &Config, area: Rect) {
+    let switch_keys = format!(
+        "{}/{}",
+        config.key_bindings.movement.up, config.key_bindings.movement.down
+    );
+    let block = Block::bordered()
+        .title("Key binding profiles")
+        .title(Title::from(switch_keys).alignment(Alignment::Right));
+
+    // List all profiles and highlight the active one.
+    let active_profile = config.active_profile();
+    let profiles = config.key_binding_profiles.keys().map(|name| {
+        if name.eq(active_profile) {
+            Line::from(format!("> {name}")).style(Style::default().fg(config.theme.highlight))
+        } else {
+            Line::from(format!("  {name}"))
+        }
+    });
+    let list = List::new(profiles).block(block);
+    f.render_widget(list, area);
+}
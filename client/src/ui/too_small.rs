@@ -0,0 +1,31 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Flex, Layout},
+    style::Style,
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    config::Config,
+    constants::{MIN_APP_HEIGHT, MIN_APP_WIDTH},
+};
+
+/// # Draw too small
+///
+/// Asks the user to enlarge the terminal, since the layout breaks below
+/// `MIN_APP_WIDTH` columns and `MIN_APP_HEIGHT` rows.
+pub fn draw_too_small(f: &mut Frame, config: &Config) {
+    let area = f.area();
+    let text = format!(
+        "Terminal too small: {}x{}\nNeeds at least {}x{}",
+        area.width, area.height, MIN_APP_WIDTH, MIN_APP_HEIGHT
+    );
+    let [area] = Layout::vertical([Constraint::Length(2)])
+        .flex(Flex::Center)
+        .areas(area);
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .style(Style::default().fg(config.theme.error));
+    f.render_widget(paragraph, area);
+}
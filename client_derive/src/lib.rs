@@ -1,12 +1,13 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Fields};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident};
 
 /// # Check duplicates derive macro
 ///
 /// Implements the `has_duplicates` method for given struct. The method iterates
-/// over all fields of the struct, inserts the values into a temporary `HashSet`
-/// and checks for duplicate values.
+/// over all fields of the struct, inserts the values into a temporary `HashMap`
+/// and checks for duplicate values. Returns the duplicate value together with
+/// the configuration names (kebab-case) of both fields using it.
 ///
 /// By using this macro we can extend a struct without having to worry about adding
 /// the new field to the duplicate check.
@@ -23,22 +24,23 @@ pub fn derive_check_duplicates(input: TokenStream) -> TokenStream {
                 _ => panic!("CheckDuplicates can only be derived for structs with named fields."),
             };
 
-            // Create an iterator over the fields and generate code to insert into HashSet
+            // Create an iterator over the fields and generate code to insert into HashMap
             let field_checks = fields.iter().map(|field| {
                 let field_name = &field.ident;
+                let config_name = config_name(field_name);
                 quote! {
-                    if !set.insert(&self.#field_name) {
-                        return true;
+                    if let Some(other) = map.insert(&self.#field_name, #config_name) {
+                        return Some((self.#field_name.to_string(), other, #config_name));
                     }
                 }
             });
 
             quote! {
                 impl #name {
-                    fn has_duplicates(&self) -> bool {
-                        let mut set = std::collections::HashSet::new();
+                    fn has_duplicates(&self) -> Option<(String, &'static str, &'static str)> {
+                        let mut map = std::collections::HashMap::new();
                         #(#field_checks)*
-                        false
+                        None
                     }
                 }
             }
@@ -54,7 +56,8 @@ pub fn derive_check_duplicates(input: TokenStream) -> TokenStream {
 /// Implements the `children_have_duplicates` method for given struct.
 /// The method iterates over all fields of the struct and calls
 /// `field.has_duplicates()` generated via the `CheckDuplicates` derive macro.
-/// Whenever a field has duplicates, return true. Otherwise return false.
+/// Whenever a field has duplicates, returns an error message naming the
+/// duplicate value and both conflicting key bindings.
 ///
 /// By using this macro we can extend a struct without having to worry about adding
 /// the new field to the duplicate check.
@@ -74,18 +77,22 @@ pub fn derive_has_duplicates(input: TokenStream) -> TokenStream {
             // duplicate keybindings.
             let field_checks = fields.iter().map(|field| {
                 let field_name = &field.ident;
+                let category = config_name(field_name);
                 quote! {
-                    if self.#field_name.has_duplicates() {
-                        return true;
+                    if let Some((value, first, second)) = self.#field_name.has_duplicates() {
+                        return Some(format!(
+                            "Duplicate key binding {} used by both {}.{} and {}.{}",
+                            value, #category, first, #category, second
+                        ));
                     }
                 }
             });
 
             quote! {
                 impl KeyBindings {
-                    pub fn children_have_duplicates(&self) -> bool {
+                    pub fn children_have_duplicates(&self) -> Option<String> {
                         #(#field_checks)*
-                        false
+                        None
                     }
                 }
             }
@@ -95,3 +102,94 @@ pub fn derive_has_duplicates(input: TokenStream) -> TokenStream {
 
     TokenStream::from(check_duplicates_impl)
 }
+
+/// # List key bindings derive macro
+///
+/// Implements the `key_bindings` method for given struct. The method returns
+/// the configuration names (kebab-case) of all fields together with their key
+/// bindings, in the order of declaration.
+///
+/// By using this macro we can extend a struct without having to worry about adding
+/// the new field to the listing, e.g., in the help overlay.
+#[proc_macro_derive(ListKeyBindings)]
+pub fn derive_list_key_bindings(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident;
+
+    let list_key_bindings_impl = match input.data {
+        Data::Struct(data_struct) => {
+            let fields = match &data_struct.fields {
+                Fields::Named(fields) => &fields.named,
+                _ => panic!("ListKeyBindings can only be derived for structs with named fields."),
+            };
+
+            let entries = fields.iter().map(|field| {
+                let field_name = &field.ident;
+                let config_name = config_name(field_name);
+                quote! {
+                    (#config_name, &self.#field_name)
+                }
+            });
+
+            quote! {
+                impl #name {
+                    pub fn key_bindings(&self) -> Vec<(&'static str, &KeyBinding)> {
+                        vec![#(#entries),*]
+                    }
+                }
+            }
+        }
+        _ => panic!("ListKeyBindings can only be derived for structs."),
+    };
+
+    TokenStream::from(list_key_bindings_impl)
+}
+
+/// # List children key bindings derive macro
+///
+/// Implements the `categories` method for given struct. The method returns
+/// the configuration name (kebab-case) of every field together with the key
+/// bindings generated via the `ListKeyBindings` derive macro.
+#[proc_macro_derive(ListChildrenKeyBindings)]
+pub fn derive_list_children_key_bindings(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let list_key_bindings_impl = match input.data {
+        Data::Struct(data_struct) => {
+            let fields = match data_struct.fields {
+                Fields::Named(ref fields) => &fields.named,
+                _ => panic!(
+                    "ListChildrenKeyBindings can only be derived for structs with named fields."
+                ),
+            };
+
+            let entries = fields.iter().map(|field| {
+                let field_name = &field.ident;
+                let category = config_name(field_name);
+                quote! {
+                    (#category, self.#field_name.key_bindings())
+                }
+            });
+
+            quote! {
+                impl KeyBindings {
+                    #[allow(clippy::type_complexity)]
+                    pub fn categories(&self) -> Vec<(&'static str, Vec<(&'static str, &KeyBinding)>)> {
+                        vec![#(#entries),*]
+                    }
+                }
+            }
+        }
+        _ => panic!("ListChildrenKeyBindings can only be derived for structs."),
+    };
+
+    TokenStream::from(list_key_bindings_impl)
+}
+
+/// Converts a field name into its kebab-case name used in the configuration.
+fn config_name(field_name: &Option<Ident>) -> String {
+    field_name
+        .as_ref()
+        .map(|ident| ident.to_string().replace('_', "-"))
+        .unwrap_or_default()
+}
@@ -1,2 +1,22 @@
+use std::time::Duration;
+
+/// The default number of players a lobby holds.
 pub static MAX_LOBBY_SIZE: usize = 4;
+/// The smallest capacity a lobby can be created with.
+pub static MIN_LOBBY_CAPACITY: usize = 1;
+/// The largest capacity a lobby can be created with.
+pub static MAX_LOBBY_CAPACITY: usize = 16;
 pub static MAX_CHAT_MESSAGE_LENGTH: usize = 50;
+/// The maximum number of characters of a player name.
+pub static MAX_PLAYER_NAME_LENGTH: usize = 20;
+/// The maximum number of characters of a lobby name.
+pub static MAX_LOBBY_NAME_LENGTH: usize = 30;
+/// The shortest countdown before a game starts, in seconds.
+pub static MIN_START_COUNTDOWN: u64 = 3;
+/// The longest countdown before a game starts, in seconds.
+pub static MAX_START_COUNTDOWN: u64 = 60;
+/// The backend pings every connection at this interval.
+pub static HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+/// Connections that stay silent for longer than the heartbeat interval plus
+/// this timeout are considered dead.
+pub static HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(10);
@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, str::FromStr};
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -7,13 +7,50 @@ use strum::Display;
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
+mod base64_bytes;
 pub mod constants;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ClientMessage {
-    Progress { progress: Vec<u8> },
-    SendMessage { message: String },
+    Progress {
+        progress: Vec<u8>,
+    },
+    SendMessage {
+        message: String,
+    },
+    /// Starts the game once all players are ready. Only has an effect for the
+    /// lobby owner.
     RequestStart,
+    /// Starts the game even if not all players are ready. Only has an effect
+    /// for the lobby owner.
+    ForceStart,
+    /// Aborts the countdown before the game starts. Only has an effect for
+    /// the lobby owner.
+    CancelStart,
+    /// Marks the player as ready or not ready for the next game.
+    ToggleReady,
+    /// Pauses or resumes the game. Only has an effect for the lobby owner.
+    TogglePause,
+    /// Turns starting the game once all players are ready on or off. Only
+    /// has an effect for the lobby owner.
+    ToggleAutoStart,
+    /// Removes another player from the lobby. Only has an effect for the
+    /// lobby owner.
+    KickPlayer {
+        player_id: Uuid,
+    },
+    /// Makes another active player the lobby owner. Only has an effect for
+    /// the lobby owner.
+    TransferOwnership {
+        player_id: Uuid,
+    },
+    /// Gives the lobby a new name. Only has an effect for the lobby owner.
+    RenameLobby {
+        name: String,
+    },
+    /// Opts a client into receiving `BackendMessage::Event`. Only has an
+    /// effect on the clients route.
+    SubscribeEvents,
 }
 
 #[cfg(feature = "client")]
@@ -28,7 +65,12 @@ impl From<ClientMessage> for Message {
 impl From<Message> for BackendMessage {
     fn from(value: Message) -> Self {
         match value {
-            Message::Text(msg) => serde_json::from_str(&msg).unwrap(),
+            // Messages of a newer or older backend version may not match any
+            // variant. Report them as unknown instead of failing.
+            Message::Text(msg) => serde_json::from_str(&msg).unwrap_or_else(|e| {
+                log::warn!("Unable to parse backend message {msg}: {e}");
+                Self::Unknown
+            }),
             Message::Close(_) => Self::CloseConnection,
             Message::Binary(_) | Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => {
                 Self::Unknown
@@ -42,14 +84,54 @@ pub struct Player {
     pub id: Uuid,
     pub name: String,
     pub progress: f64,
+    /// The approximate number of edits per minute since the game started.
+    pub edits_per_minute: f64,
     pub waiting: bool,
+    /// Whether the player is ready for the next game.
+    pub ready: bool,
+    /// The position the player finished the current game in, starting at
+    /// one. Is `None` while the player has not reached the goal.
+    #[serde(default)]
+    pub position: Option<usize>,
+}
+
+/// # Standing
+///
+/// The wins and best completion time of a player across all games.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Standing {
+    pub wins: u32,
+    pub best_time_ms: Option<i64>,
+}
+
+/// # Final standing
+///
+/// The result of a player once a game finished.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FinalStanding {
+    pub player_id: Uuid,
+    pub player_name: String,
+    /// The position of the player in the game, starting at one.
+    pub rank: usize,
+    pub progress: f64,
+    /// The time the player needed to reach the goal file. Is `None` if the
+    /// player did not finish.
+    pub finish_time_ms: Option<i64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LobbyListItem {
     pub name: String,
     pub player_count: usize,
+    /// The number of players the lobby holds.
+    pub max_players: usize,
+    /// The name of the challenge played in the lobby.
+    pub challenge: String,
+    /// The language of the challenge played in the lobby.
+    pub language: String,
     pub status: LobbyStatus,
+    /// Holds the time an empty lobby is removed.
+    pub removal_time: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Display, PartialEq, Eq)]
@@ -65,6 +147,9 @@ pub enum LobbyStatus {
     /// Holds the time the game reset.
     #[strum(to_string = "Just finished")]
     Finish(DateTime<Utc>),
+    /// Holds the remaining play time once the game resumes.
+    #[strum(to_string = "Paused")]
+    Paused { resumed_remaining: Duration },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -75,14 +160,74 @@ pub struct LobbyInformation {
     pub owner: Option<Uuid>,
     pub players: BTreeMap<Uuid, Player>,
     pub challenge_files: ChallengeFiles,
+    /// Whether the game starts on its own once all players are ready.
+    #[serde(default)]
+    pub auto_start: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChallengeFiles {
+    /// Short name of the challenge.
+    pub name: String,
+    /// The programming language of the start and goal file.
+    pub language: String,
+    /// The file extension matching the language, e.g., `rs`. Lets editors
+    /// highlight the syntax of start and goal file.
+    #[serde(default)]
+    pub extension: String,
+    /// One-line description of the task.
+    pub description: String,
+    /// Whether start and goal file hold text or arbitrary bytes.
+    #[serde(default)]
+    pub content_type: ContentType,
     /// File all players start from.
+    #[serde(with = "base64_bytes")]
     pub start_file: Vec<u8>,
     /// The goal state of the start file.
+    #[serde(with = "base64_bytes")]
     pub goal_file: Vec<u8>,
+    /// Optional hints on how to approach the challenge.
+    #[serde(default)]
+    pub instructions: Option<String>,
+    /// Optional position the editor opens the start file at.
+    #[serde(default)]
+    pub cursor: Option<CursorPosition>,
+}
+
+/// # Content type
+///
+/// Tells clients whether they can open the challenge files in an editor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContentType {
+    #[default]
+    Text,
+    Binary,
+}
+
+impl ContentType {
+    /// # Detect content type
+    ///
+    /// Considers the files binary as soon as one of them is no valid UTF-8 or
+    /// contains a NUL byte.
+    pub fn detect(files: &[&[u8]]) -> Self {
+        let is_binary = files
+            .iter()
+            .any(|file| file.contains(&0) || std::str::from_utf8(file).is_err());
+        if is_binary {
+            ContentType::Binary
+        } else {
+            ContentType::Text
+        }
+    }
+}
+
+/// # Cursor position
+///
+/// A position inside a file. Line and column start at one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursorPosition {
+    pub line: usize,
+    pub column: usize,
 }
 
 #[derive(Debug, Display)]
@@ -95,6 +240,24 @@ pub enum JoinMode {
     Join { lobby_id: Uuid },
     /// Client wants to create a new lobby.
     Create,
+    /// Client wants to watch a specific lobby without playing.
+    #[strum(to_string = "spectate-{lobby_id}")]
+    Spectate { lobby_id: Uuid },
+}
+
+/// Options for lobbies created via `JoinMode::Create`. Passed as query
+/// parameters when requesting lobby information.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CreateLobbyOptions {
+    /// The number of players the new lobby holds. Falls back to
+    /// `MAX_LOBBY_SIZE` if unset.
+    pub max_players: Option<usize>,
+    /// The ID of the challenge played in the new lobby. Falls back to the
+    /// default challenge of the backend if unset.
+    pub challenge: Option<String>,
+    /// The seconds between a start request and the start of the game. Falls
+    /// back to the default countdown of the backend if unset.
+    pub start_countdown: Option<u64>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -107,6 +270,13 @@ impl FromStr for JoinMode {
         match s {
             "create" => Ok(JoinMode::Create),
             "quickplay" => Ok(JoinMode::Quickplay),
+            s if s.starts_with("spectate-") => {
+                if let Ok(lobby_id) = Uuid::from_str(&s["spectate-".len()..]) {
+                    Ok(JoinMode::Spectate { lobby_id })
+                } else {
+                    Err(ParseJoinModeError)
+                }
+            }
             s => {
                 if let Ok(lobby_id) = Uuid::from_str(s) {
                     Ok(JoinMode::Join { lobby_id })
@@ -118,27 +288,172 @@ impl FromStr for JoinMode {
     }
 }
 
+/// # Game event
+///
+/// A structured event about something that happened on the backend service.
+/// Events are only sent to clients that subscribed via
+/// `ClientMessage::SubscribeEvents` and are meant as stable API for overlays,
+/// bots and other integrations.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum GameEvent {
+    /// A new lobby was created.
+    LobbyCreated { lobby_id: Uuid, name: String },
+    /// An empty lobby was removed.
+    LobbyRemoved { lobby_id: Uuid },
+    /// A player joined a lobby. Waiting players joined a lobby that is
+    /// currently not waiting for players and take part in the next game.
+    PlayerJoined {
+        lobby_id: Uuid,
+        player_id: Uuid,
+        name: String,
+        waiting: bool,
+    },
+    /// A player left a lobby.
+    PlayerLeft { lobby_id: Uuid, player_id: Uuid },
+    /// A player reached the goal file. The position starts at one.
+    PlayerFinished {
+        lobby_id: Uuid,
+        player_id: Uuid,
+        position: usize,
+    },
+    /// The status of a lobby changed.
+    StatusChanged { lobby_id: Uuid, status: LobbyStatus },
+}
+
 #[cfg_attr(feature = "client", derive(Deserialize))]
 #[derive(Clone, Debug, Serialize)]
 pub enum BackendMessage {
     CurrentLobbies(BTreeMap<Uuid, LobbyListItem>),
     AddLobby(Uuid, LobbyListItem),
-    UpdateLobbyPlayerCount { id: Uuid, player_count: usize },
-    UpdateLobbyStatus { id: Uuid, status: LobbyStatus },
+    UpdateLobbyPlayerCount {
+        id: Uuid,
+        player_count: usize,
+    },
+    UpdateLobbyStatus {
+        id: Uuid,
+        status: LobbyStatus,
+        removal_time: Option<DateTime<Utc>>,
+    },
+    /// The owner renamed a lobby.
+    UpdateLobbyName {
+        id: Uuid,
+        name: String,
+    },
     RemoveLobby(Uuid),
     LobbyFull,
     LobbyNotWaitingForPlayers,
-    ConnectionCounts { clients: usize, players: usize },
+    /// The backend holds the maximum number of lobbies and can not create
+    /// another one.
+    ServerFull,
+    /// The requested lobby does not exist (anymore).
+    LobbyNotFound,
+    /// The backend does not accept the options of a lobby to create.
+    InvalidLobbyOptions(String),
+    /// The standings of all players by name.
+    Leaderboard(BTreeMap<String, Standing>),
+    /// The results of all players of a finished game, ordered by rank.
+    FinalStandings(Vec<FinalStanding>),
+    ConnectionCounts {
+        clients: usize,
+        players: usize,
+        spectators: usize,
+        lobbies: usize,
+    },
 
     SendLobbyInformation(LobbyInformation),
-    ProvidePlayerId { id: Uuid },
-    AssignOwner { id: Uuid },
+    /// Holds the ID of the player and the token that allows the player to
+    /// reconnect after an accidental disconnect.
+    ProvidePlayerId {
+        id: Uuid,
+        token: Uuid,
+    },
+    /// The reconnect token is unknown or the grace period has passed.
+    ReconnectFailed,
+    AssignOwner {
+        id: Uuid,
+    },
     AddPlayer(Player),
     RemovePlayer(Uuid),
-    StatusUpdate { status: LobbyStatus },
-    UpdatePlayerProgress { player_id: Uuid, progress: f64 },
+    StatusUpdate {
+        status: LobbyStatus,
+    },
+    UpdatePlayerProgress {
+        player_id: Uuid,
+        progress: f64,
+        edits_per_minute: f64,
+    },
+    /// A player edited his file. Sent at most once per second per player.
+    PlayerActive {
+        player_id: Uuid,
+    },
+    /// A player reached the goal file. The position starts at one and the
+    /// duration is measured from the start of the game.
+    PlayerFinished {
+        player_id: Uuid,
+        position: usize,
+        duration: Duration,
+    },
+    /// A player toggled whether he is ready for the next game.
+    UpdatePlayerReady {
+        player_id: Uuid,
+        ready: bool,
+    },
+    /// A waiting player takes part in the games from now on.
+    PromotePlayer {
+        player_id: Uuid,
+    },
+    /// The owner turned the automatic start on or off.
+    UpdateAutoStart {
+        auto_start: bool,
+    },
 
     SendMessage(String),
+    /// The most recent chat messages of the lobby, sent to joining players.
+    ChatHistory(Vec<String>),
+    /// A game event with the time it was published.
+    Event {
+        timestamp: DateTime<Utc>,
+        event: GameEvent,
+    },
     CloseConnection,
     Unknown,
 }
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_backend_message_tag_is_unknown() {
+        let message = Message::Text(r#"{"SomeFutureMessage":{"value":1}}"#.to_string());
+        assert!(matches!(
+            BackendMessage::from(message),
+            BackendMessage::Unknown
+        ));
+    }
+
+    #[test]
+    fn malformed_backend_message_is_unknown() {
+        for text in ["not json", r#"{"AssignOwner":{"id":"no uuid"}}"#, ""] {
+            let message = Message::Text(text.to_string());
+            assert!(matches!(
+                BackendMessage::from(message),
+                BackendMessage::Unknown
+            ));
+        }
+    }
+
+    #[test]
+    fn known_backend_message_is_parsed() {
+        let id = Uuid::new_v4();
+        let message = Message::Text(format!(r#"{{"AssignOwner":{{"id":"{id}"}}}}"#));
+        assert!(matches!(
+            BackendMessage::from(message),
+            BackendMessage::AssignOwner { id: owner_id } if owner_id == id
+        ));
+        assert!(matches!(
+            BackendMessage::from(Message::Close(None)),
+            BackendMessage::CloseConnection
+        ));
+    }
+}